@@ -142,6 +142,24 @@ pub struct ColorIntensity {
     pub color: DmxColor,
 }
 
+#[derive(
+    Debug, serde::Deserialize, serde::Serialize, PartialEq, Clone, get_size::GetSize, JsonSchema,
+)]
+pub struct ShutterStrobe {
+    pub shutter_effect: ShutterEffect,
+}
+
+#[derive(
+    Debug, serde::Deserialize, serde::Serialize, PartialEq, Clone, get_size::GetSize, JsonSchema,
+)]
+pub enum ShutterEffect {
+    Open,
+    Closed,
+    /// Strobing and every other effect
+    #[serde(other)]
+    Other,
+}
+
 #[derive(
     Debug, serde::Deserialize, serde::Serialize, PartialEq, Clone, get_size::GetSize, JsonSchema,
 )]
@@ -213,7 +231,7 @@ pub enum FixtureCapability {
     Intensity(Intensity),
     ColorIntensity(ColorIntensity),
     ColorPreset,
-    ShutterStrobe,
+    ShutterStrobe(ShutterStrobe),
     EffectSpeed,
     Rotation(Rotation),
     Pan(PanTilt),
@@ -0,0 +1,197 @@
+//! Response curves shaping values before they are output, e.g. gamma correction for LEDs or square law dimmers.
+//!
+//! Curves are assigned to features of patched fixtures or to single channels. Feature curves shape the 0..1 value
+//! of the feature before it is mapped onto the DMX range of its channels, channel curves shape the raw DMX value
+//! of the channel at the output.
+
+use schemars::JsonSchema;
+
+use crate::patched::feature::FixtureFeatureType;
+use crate::patched::{FixtureId, UniverseAddress, UniverseId};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Default, JsonSchema)]
+pub enum OutputCurve {
+    #[default]
+    Linear,
+    /// `v²`, for conventional dimmers that come up too fast
+    Square,
+    /// `√v`, the inverse of [`OutputCurve::Square`]
+    InverseSquare,
+    /// `v^gamma`, LEDs usually look right with a gamma around 2.2
+    Gamma(f64),
+    /// Output values for evenly spaced inputs from 0 to full, interpolated linearly in between
+    Lookup(Vec<u8>),
+}
+
+impl OutputCurve {
+    /// Shapes a value from 0 to 1
+    pub fn apply(&self, v: f64) -> f64 {
+        let v = v.clamp(0.0, 1.0);
+        match self {
+            OutputCurve::Linear => v,
+            OutputCurve::Square => v * v,
+            OutputCurve::InverseSquare => v.sqrt(),
+            OutputCurve::Gamma(gamma) => v.powf(*gamma),
+            OutputCurve::Lookup(table) => match table.len() {
+                0 => v,
+                1 => table[0] as f64 / 255.0,
+                n => {
+                    let pos = v * (n - 1) as f64;
+                    let i = (pos.floor() as usize).min(n - 2);
+                    let t = pos - i as f64;
+                    let (a, b) = (table[i] as f64, table[i + 1] as f64);
+                    (a + (b - a) * t) / 255.0
+                }
+            },
+        }
+    }
+
+    /// The value that shapes to `v`, found by bisection as the curves rise from 0 to 1
+    pub fn invert(&self, v: f64) -> f64 {
+        if matches!(self, OutputCurve::Linear) {
+            return v.clamp(0.0, 1.0);
+        }
+
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..32 {
+            let mid = (low + high) / 2.0;
+            if self.apply(mid) < v {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// The output for every DMX value
+    pub fn table(&self) -> [u8; 256] {
+        std::array::from_fn(|i| (self.apply(i as f64 / 255.0) * 255.0).round() as u8)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            OutputCurve::Gamma(gamma) if !gamma.is_finite() || *gamma <= 0.0 => {
+                Err(format!("A gamma of {gamma} is not valid"))
+            }
+            OutputCurve::Lookup(table) if table.is_empty() => {
+                Err("A lookup table needs at least one value".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum CurveTarget {
+    Feature {
+        fixture: FixtureId,
+        feature: FixtureFeatureType,
+    },
+    Channel {
+        universe: UniverseId,
+        channel: UniverseAddress,
+    },
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct CurveAssignment {
+    pub target: CurveTarget,
+    pub curve: OutputCurve,
+}
+
+/// Assigns a curve to a target, `None` removes the curve of the target
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct CurveRequest {
+    pub target: CurveTarget,
+    pub curve: Option<OutputCurve>,
+}
+
+pub fn assign(assignments: &mut Vec<CurveAssignment>, request: CurveRequest) -> Result<(), String> {
+    if let Some(curve) = &request.curve {
+        curve.validate()?;
+    }
+
+    assignments.retain(|a| a.target != request.target);
+    if let Some(curve) = request.curve {
+        assignments.push(CurveAssignment {
+            target: request.target,
+            curve,
+        });
+    }
+    Ok(())
+}
+
+static LINEAR: OutputCurve = OutputCurve::Linear;
+
+/// The curves assigned to the features of one fixture
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeatureCurves(Vec<(FixtureFeatureType, OutputCurve)>);
+
+impl FeatureCurves {
+    pub fn of(assignments: &[CurveAssignment], fixture: FixtureId) -> FeatureCurves {
+        FeatureCurves(
+            assignments
+                .iter()
+                .filter_map(|a| match a.target {
+                    CurveTarget::Feature {
+                        fixture: f,
+                        feature,
+                    } if f == fixture => Some((feature, a.curve.clone())),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// The curve of the feature, linear if there is none
+    pub fn get(&self, feature: FixtureFeatureType) -> &OutputCurve {
+        self.0
+            .iter()
+            .find(|(f, _)| *f == feature)
+            .map(|(_, c)| c)
+            .unwrap_or(&LINEAR)
+    }
+}
+
+/// The lookup tables of every channel of the universe with a curve
+pub fn channel_tables(
+    assignments: &[CurveAssignment],
+    universe: UniverseId,
+) -> Vec<(usize, [u8; 256])> {
+    assignments
+        .iter()
+        .filter_map(|a| match a.target {
+            CurveTarget::Channel {
+                universe: u,
+                channel,
+            } if u == universe => Some((channel.i(), a.curve.table())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_keep_the_ends() {
+        let curves = [
+            OutputCurve::Linear,
+            OutputCurve::Square,
+            OutputCurve::InverseSquare,
+            OutputCurve::Gamma(2.2),
+            OutputCurve::Lookup(vec![0, 10, 255]),
+        ];
+        for c in curves {
+            let table = c.table();
+            assert_eq!((table[0], table[255]), (0, 255), "{c:?}");
+        }
+
+        assert_eq!(OutputCurve::Square.apply(0.5), 0.25);
+        assert_eq!(OutputCurve::Lookup(vec![0, 10, 255]).table()[128], 11);
+        assert!(OutputCurve::Gamma(2.2).table()[128] < 128);
+        assert!(OutputCurve::InverseSquare.table()[128] > 128);
+    }
+}
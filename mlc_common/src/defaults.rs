@@ -0,0 +1,72 @@
+//! Values channels fall back to when no other source holds them, like the home position of pan and tilt.
+
+use schemars::JsonSchema;
+
+use crate::patched::{UniverseAddress, UniverseId};
+use crate::universe::UNIVERSE_SIZE;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct DefaultValue {
+    pub universe: UniverseId,
+    pub channel: UniverseAddress,
+    pub value: u8,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum DefaultRequest {
+    Set {
+        universe: UniverseId,
+        channel: UniverseAddress,
+        value: u8,
+    },
+    /// The channel falls back to 0 again
+    Reset {
+        universe: UniverseId,
+        channel: UniverseAddress,
+    },
+    ResetAll,
+}
+
+/// Applies the request to the defaults, channels can only be set in universes that exist
+pub fn apply(
+    defaults: &mut Vec<DefaultValue>,
+    request: DefaultRequest,
+    universes: &[UniverseId],
+) -> Result<(), String> {
+    match request {
+        DefaultRequest::Set {
+            universe,
+            channel,
+            value,
+        } => {
+            if !universes.contains(&universe) {
+                return Err(format!("Universe {} does not exist", universe.0));
+            }
+            defaults.retain(|d| d.universe != universe || d.channel != channel);
+            defaults.push(DefaultValue {
+                universe,
+                channel,
+                value,
+            });
+        }
+        DefaultRequest::Reset { universe, channel } => {
+            defaults.retain(|d| d.universe != universe || d.channel != channel);
+        }
+        DefaultRequest::ResetAll => defaults.clear(),
+    }
+
+    defaults.sort_by_key(|d| (d.universe, d.channel.i()));
+    Ok(())
+}
+
+/// The defaults of one universe, `None` for channels that fall back to 0
+pub fn default_values(
+    defaults: &[DefaultValue],
+    universe: UniverseId,
+) -> [Option<u8>; UNIVERSE_SIZE] {
+    let mut values = [None; UNIVERSE_SIZE];
+    for d in defaults.iter().filter(|d| d.universe == universe) {
+        values[d.channel.i()] = Some(d.value);
+    }
+    values
+}
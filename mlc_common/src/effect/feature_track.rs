@@ -5,6 +5,7 @@ use chrono::Duration;
 use serde_with::serde_as;
 
 use crate::effect::track_key::{D3PercentageKey, PercentageKey, RotationKey};
+use crate::group::{FixtureGroup, GroupId};
 use crate::patched::feature::FixtureFeatureType;
 use crate::patched::FixtureId;
 
@@ -14,6 +15,9 @@ pub struct FeatureTrack {
     #[serde(alias = "fixture")]
     #[serde_as(deserialize_as = "OneOrMany<_, PreferOne>")]
     pub fixtures: Vec<FixtureId>,
+    /// The members of these groups are animated in addition to `fixtures`
+    #[serde(default)]
+    pub groups: Vec<GroupId>,
     pub feature: FixtureFeatureType,
     pub detail: FeatureTrackDetail,
     #[serde_as(as = "DurationSecondsWithFrac<f64, Flexible>")]
@@ -21,6 +25,21 @@ pub struct FeatureTrack {
 }
 
 impl FeatureTrack {
+    /// The fixtures of the track followed by the members of its groups, each fixture once
+    pub fn all_fixtures(&self, groups: &[FixtureGroup]) -> Vec<FixtureId> {
+        let mut fixtures = self.fixtures.clone();
+        for member in groups
+            .iter()
+            .filter(|g| self.groups.contains(&g.id))
+            .flat_map(|g| &g.fixtures)
+        {
+            if !fixtures.contains(member) {
+                fixtures.push(*member);
+            }
+        }
+        fixtures
+    }
+
     pub fn insert_default_key(&mut self, time: Duration) {
         match &mut self.detail {
             FeatureTrackDetail::SinglePercent(t) => t.values.push(PercentageKey {
@@ -23,6 +23,10 @@ pub struct Effect {
     #[serde_as(as = "DurationSecondsWithFrac<f64, Flexible>")]
     pub duration: Duration,
     pub tracks: Vec<Track>,
+    /// The timecode at which the effect starts while effects chase a timecode
+    #[serde_as(as = "DurationSecondsWithFrac<f64, Flexible>")]
+    #[serde(default)]
+    pub timecode_start: Duration,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize, Clone)]
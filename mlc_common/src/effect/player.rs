@@ -10,12 +10,56 @@ use super::EffectId;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub enum EffectPlayerMsg {
-    PlayingEffects { effects: Vec<EffectId> },
+    PlayingEffects {
+        effects: Vec<EffectId>,
+    },
     EffectProgresses(Vec<(EffectId, BoundedValue<f32, Zero, One>)>),
+    /// Sent whenever an effect starts, stops or its playback is changed
+    EffectStates(Vec<PlayingEffect>),
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub enum EffectPlayerRequest {
-    Play { effect: EffectId },
-    Stop { effect: EffectId },
+    Play {
+        effect: EffectId,
+    },
+    Stop {
+        effect: EffectId,
+    },
+    Pause {
+        effect: EffectId,
+    },
+    Resume {
+        effect: EffectId,
+    },
+    Seek {
+        effect: EffectId,
+        position: SeekPosition,
+    },
+    /// Multiplies the speed the effect was programmed with, 0 holds it
+    SetRate {
+        effect: EffectId,
+        rate: f64,
+    },
+    /// Scales the intensity features of the effect
+    SetIntensity {
+        effect: EffectId,
+        intensity: BoundedValue<f64, Zero, One>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum SeekPosition {
+    Seconds(f64),
+    /// Of the duration of the effect
+    Fraction(BoundedValue<f64, Zero, One>),
+}
+
+/// The playback of a running effect
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PlayingEffect {
+    pub effect: EffectId,
+    pub paused: bool,
+    pub rate: f64,
+    pub intensity: BoundedValue<f64, Zero, One>,
 }
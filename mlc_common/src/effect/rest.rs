@@ -25,6 +25,9 @@ pub enum EffectHandlerRequest {
         looping: bool,
         #[serde_as(as = "DurationSecondsWithFrac<f64, Flexible>")]
         duration: Duration,
+        #[serde_as(as = "DurationSecondsWithFrac<f64, Flexible>")]
+        #[serde(default)]
+        timecode_start: Duration,
     },
     Get {
         id: uuid::Uuid,
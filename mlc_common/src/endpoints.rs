@@ -1,21 +1,492 @@
 use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::time::Duration;
 
+use chrono::{DateTime, Local};
 use schemars::JsonSchema;
 
 use crate::patched::UniverseId;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Default, JsonSchema)]
 pub struct EndPointConfig {
+    #[serde(deserialize_with = "deserialize_endpoints")]
     pub endpoints: HashMap<UniverseId, Vec<EPConfigItem>>,
+    /// Unassigned in projects from before sACN had a persistent identity
+    #[serde(default = "SacnSource::unassigned")]
+    pub sacn: SacnSource,
+    #[serde(default)]
+    pub clock: FrameClock,
+    #[serde(default)]
+    pub osc: OscConfig,
+}
+
+/// The OSC server that lets tools like TouchOSC or QLab control effects, faders and fixtures
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct OscConfig {
+    pub enabled: bool,
+    /// The UDP port the server listens on
+    pub port: u16,
+    /// Always receive feedback, in addition to the peers that subscribed with `/mlc/feedback/subscribe`
+    #[serde(default)]
+    pub feedback: Vec<SocketAddr>,
+}
+
+impl Default for OscConfig {
+    fn default() -> Self {
+        OscConfig {
+            enabled: false,
+            port: 8000,
+            feedback: vec![],
+        }
+    }
+}
+
+/// The clock all DMX output is sent on. Every endpoint receives at most one frame of its universe per tick.
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Copy, JsonSchema)]
+pub struct FrameClock {
+    /// Frames per second
+    pub rate: u16,
+    /// Milliseconds after which unchanged universes are sent again, nodes drop output that is not refreshed
+    pub keep_alive: u32,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        FrameClock {
+            rate: 40,
+            keep_alive: 1000,
+        }
+    }
+}
+
+impl FrameClock {
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(1) / u32::from(self.rate.max(1))
+    }
+
+    pub fn keep_alive_duration(&self) -> Duration {
+        Duration::from_millis(u64::from(self.keep_alive))
+    }
+}
+
+/// The identity all sACN output is sent with. Is stored in the project so receivers see the same source after a restart.
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct SacnSource {
+    pub name: String,
+    pub cid: uuid::Uuid,
+}
+
+impl Default for SacnSource {
+    fn default() -> Self {
+        SacnSource {
+            cid: uuid::Uuid::new_v4(),
+            ..SacnSource::unassigned()
+        }
+    }
+}
+
+impl SacnSource {
+    /// A source without a CID yet
+    pub fn unassigned() -> Self {
+        SacnSource {
+            name: "MLC Controller".to_string(),
+            cid: uuid::Uuid::nil(),
+        }
+    }
+
+    pub fn is_assigned(&self) -> bool {
+        !self.cid.is_nil()
+    }
 }
 
 #[derive(Debug, serde::Serialize, PartialEq, serde::Deserialize, Clone, JsonSchema)]
 pub enum EPConfigItem {
     Logger,
-    ArtNet,
-    Sacn { universe: u16, speed: Speed },
-    Usb { port: String, speed: Speed },
+    ArtNet {
+        port_address: ArtNetPortAddress,
+        /// Nodes that always receive the output, whether they answered a poll or not
+        #[serde(default)]
+        unicast: Vec<Ipv4Addr>,
+        /// Broadcasts the output while no node is known for the port address
+        #[serde(default = "default_artnet_broadcast")]
+        broadcast: bool,
+        /// The local interface to bind to. `None` binds to all interfaces
+        #[serde(default)]
+        interface: Option<Ipv4Addr>,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    Sacn {
+        universe: u16,
+        /// The shortest time between two packets with new values, slows the frame clock down for this endpoint
+        speed: Speed,
+        /// 0 - 200, receivers merge sources by priority
+        #[serde(default = "default_sacn_priority")]
+        priority: u8,
+        /// Destinations that receive the output via unicast in addition to multicast
+        #[serde(default)]
+        unicast: Vec<Ipv4Addr>,
+        /// All sACN endpoints with the same sync universe are sent together followed by one E1.31 sync packet
+        #[serde(default)]
+        sync_universe: Option<u16>,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    Usb {
+        port: String,
+        speed: Speed,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    /// An Enttec DMX USB Pro compatible widget
+    UsbPro {
+        port: String,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    /// Receives sACN from another source and merges it into the universe
+    SacnInput {
+        universe: u16,
+        merge: MergeMode,
+        /// The priority the local values have when merging with [`MergeMode::Priority`]
+        #[serde(default = "default_sacn_priority")]
+        local_priority: u8,
+    },
+    /// Receives Art-Net from another source and merges it into the universe
+    ArtNetInput {
+        port_address: ArtNetPortAddress,
+        merge: MergeMode,
+    },
+    /// Streams a channel range of the universe to a DDP pixel controller.
+    /// All DDP endpoints with the same destination are sent as one frame
+    Ddp {
+        destination: Ipv4Addr,
+        #[serde(default)]
+        mapping: PixelMapping,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    /// Streams a channel range of the universe to an Open Pixel Control server.
+    /// All OPC endpoints with the same address and channel are sent as one message
+    Opc {
+        /// `host:port` of the server, the port defaults to 7890
+        address: String,
+        #[serde(default)]
+        channel: u8,
+        #[serde(default)]
+        mapping: PixelMapping,
+        #[serde(default)]
+        channel_map: Option<ChannelMap>,
+    },
+    /// Receives Art-Net timecode, playing effects follow it instead of their own clock and pause when it stops.
+    /// The universe it is added to does not matter
+    ArtNetTimecode,
+    /// Records the output of the universe with timestamps, see [`crate::recording`].
+    /// All recorders with the same file write into one recording, every start of the endpoints begins a new one
+    Recorder {
+        /// The name of the recordings in the data directory of MLC, the start time and the extension are appended
+        file: String,
+    },
+}
+
+/// Rearranges the channels an endpoint outputs.
+///
+/// Without a channel map an endpoint outputs its universe as is. With one, only the channels of the patches are
+/// output and all other channels are dropped and sent as 0.
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Default, JsonSchema)]
+pub struct ChannelMap {
+    pub patches: Vec<ChannelPatch>,
+}
+
+/// Copies a range of channels of a universe to another address of the output. Later patches overwrite earlier ones
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Copy, JsonSchema)]
+pub struct ChannelPatch {
+    /// The universe the channels are taken from. `None` is the universe of the endpoint
+    #[serde(default)]
+    pub universe: Option<UniverseId>,
+    /// The first channel that is taken, starting at 0
+    pub from: u16,
+    /// The output channel the first channel is written to, starting at 0
+    pub to: u16,
+    pub length: u16,
+}
+
+impl Default for ChannelPatch {
+    fn default() -> Self {
+        ChannelPatch {
+            universe: None,
+            from: 0,
+            to: 0,
+            length: 512,
+        }
+    }
+}
+
+/// Where the channels of a universe end up in the pixel data of a pixel controller
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+pub struct PixelMapping {
+    /// The first channel of the universe that is sent, starting at 0
+    pub start: u16,
+    /// How many channels are sent
+    pub length: u16,
+    /// The byte in the pixel data of the controller the first channel is written to
+    pub offset: u32,
+}
+
+impl Default for PixelMapping {
+    /// 170 RGB pixels, as many as fit into one universe
+    fn default() -> Self {
+        PixelMapping {
+            start: 0,
+            length: 510,
+            offset: 0,
+        }
+    }
+}
+
+/// How received values are combined with the values set in MLC
+#[derive(
+    Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy, Default, JsonSchema,
+)]
+pub enum MergeMode {
+    /// Highest takes precedence, per channel
+    #[default]
+    Htp,
+    /// Latest takes precedence, per channel. Whoever changed a channel last owns it
+    Ltp,
+    /// The source with the higher priority owns the whole universe, equal priorities merge HTP.
+    /// Art-Net carries no priority and always counts as the default priority of 100
+    Priority,
+}
+
+fn default_sacn_priority() -> u8 {
+    100
+}
+
+fn default_artnet_broadcast() -> bool {
+    true
+}
+
+/// Also accepts projects from before Art-Net output had settings, which stored it as a bare `"ArtNet"`
+fn deserialize_endpoints<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<UniverseId, Vec<EPConfigItem>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum Item {
+        Current(EPConfigItem),
+        Legacy(LegacyItem),
+    }
+
+    #[derive(serde::Deserialize)]
+    enum LegacyItem {
+        ArtNet,
+    }
+
+    let endpoints: HashMap<UniverseId, Vec<Item>> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(endpoints
+        .into_iter()
+        .map(|(universe, items)| {
+            let items = items
+                .into_iter()
+                .map(|item| match item {
+                    Item::Current(item) => item,
+                    Item::Legacy(LegacyItem::ArtNet) => EPConfigItem::default_artnet(),
+                })
+                .collect();
+            (universe, items)
+        })
+        .collect())
+}
+
+impl EPConfigItem {
+    /// The channel map of an endpoint that outputs DMX, `None` for all other endpoints
+    pub fn channel_map(&self) -> Option<&Option<ChannelMap>> {
+        match self {
+            EPConfigItem::ArtNet { channel_map, .. }
+            | EPConfigItem::Sacn { channel_map, .. }
+            | EPConfigItem::Usb { channel_map, .. }
+            | EPConfigItem::UsbPro { channel_map, .. }
+            | EPConfigItem::Ddp { channel_map, .. }
+            | EPConfigItem::Opc { channel_map, .. } => Some(channel_map),
+            EPConfigItem::Logger
+            | EPConfigItem::SacnInput { .. }
+            | EPConfigItem::ArtNetInput { .. }
+            | EPConfigItem::ArtNetTimecode
+            | EPConfigItem::Recorder { .. } => None,
+        }
+    }
+
+    pub fn channel_map_mut(&mut self) -> Option<&mut Option<ChannelMap>> {
+        match self {
+            EPConfigItem::ArtNet { channel_map, .. }
+            | EPConfigItem::Sacn { channel_map, .. }
+            | EPConfigItem::Usb { channel_map, .. }
+            | EPConfigItem::UsbPro { channel_map, .. }
+            | EPConfigItem::Ddp { channel_map, .. }
+            | EPConfigItem::Opc { channel_map, .. } => Some(channel_map),
+            EPConfigItem::Logger
+            | EPConfigItem::SacnInput { .. }
+            | EPConfigItem::ArtNetInput { .. }
+            | EPConfigItem::ArtNetTimecode
+            | EPConfigItem::Recorder { .. } => None,
+        }
+    }
+
+    pub fn default_sacn() -> Self {
+        EPConfigItem::Sacn {
+            universe: 1,
+            speed: Speed::Medium,
+            priority: default_sacn_priority(),
+            unicast: vec![],
+            sync_universe: None,
+            channel_map: None,
+        }
+    }
+
+    pub fn default_sacn_input() -> Self {
+        EPConfigItem::SacnInput {
+            universe: 1,
+            merge: MergeMode::default(),
+            local_priority: default_sacn_priority(),
+        }
+    }
+
+    pub fn default_artnet_input() -> Self {
+        EPConfigItem::ArtNetInput {
+            port_address: ArtNetPortAddress::default(),
+            merge: MergeMode::default(),
+        }
+    }
+
+    pub fn default_ddp() -> Self {
+        EPConfigItem::Ddp {
+            destination: Ipv4Addr::new(192, 168, 0, 100),
+            mapping: PixelMapping::default(),
+            channel_map: None,
+        }
+    }
+
+    pub fn default_opc() -> Self {
+        EPConfigItem::Opc {
+            address: "127.0.0.1:7890".to_string(),
+            channel: 0,
+            mapping: PixelMapping::default(),
+            channel_map: None,
+        }
+    }
+
+    pub fn default_recorder() -> Self {
+        EPConfigItem::Recorder {
+            file: "recording".to_string(),
+        }
+    }
+
+    pub fn default_artnet() -> Self {
+        EPConfigItem::ArtNet {
+            port_address: ArtNetPortAddress::default(),
+            unicast: vec![],
+            broadcast: default_artnet_broadcast(),
+            interface: None,
+            channel_map: None,
+        }
+    }
+}
+
+/// The 15 bit Art-Net Port-Address split into its Net (7 bit), Sub-Net (4 bit) and Universe (4 bit) parts
+#[derive(
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    Clone,
+    Copy,
+    Default,
+    JsonSchema,
+)]
+#[serde(try_from = "PortAddressParts")]
+pub struct ArtNetPortAddress {
+    pub net: u8,
+    pub subnet: u8,
+    pub universe: u8,
+}
+
+impl ArtNetPortAddress {
+    pub fn create(net: u8, subnet: u8, universe: u8) -> Result<Self, &'static str> {
+        if net > 0x7f {
+            return Err("The Art-Net net only has 7 bits.");
+        }
+        if subnet > 0x0f {
+            return Err("The Art-Net sub-net only has 4 bits.");
+        }
+        if universe > 0x0f {
+            return Err("The Art-Net universe only has 4 bits.");
+        }
+
+        Ok(ArtNetPortAddress {
+            net,
+            subnet,
+            universe,
+        })
+    }
+
+    /// The low byte of the Port-Address as it is sent in ArtDmx (SubUni)
+    pub fn sub_uni(&self) -> u8 {
+        ((self.subnet & 0x0f) << 4) | (self.universe & 0x0f)
+    }
+}
+
+/// The parts of a Port-Address as they are stored, before their ranges are checked
+#[derive(serde::Deserialize)]
+struct PortAddressParts {
+    net: u8,
+    subnet: u8,
+    universe: u8,
+}
+
+impl TryFrom<PortAddressParts> for ArtNetPortAddress {
+    type Error = &'static str;
+
+    fn try_from(value: PortAddressParts) -> Result<Self, Self::Error> {
+        ArtNetPortAddress::create(value.net, value.subnet, value.universe)
+    }
+}
+
+impl From<ArtNetPortAddress> for u16 {
+    fn from(value: ArtNetPortAddress) -> Self {
+        ((value.net as u16 & 0x7f) << 8) | value.sub_uni() as u16
+    }
+}
+
+impl From<u16> for ArtNetPortAddress {
+    fn from(value: u16) -> Self {
+        ArtNetPortAddress {
+            net: ((value >> 8) & 0x7f) as u8,
+            subnet: ((value >> 4) & 0x0f) as u8,
+            universe: (value & 0x0f) as u8,
+        }
+    }
+}
+
+/// An Art-Net node as announced by its ArtPollReply
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct ArtNetNode {
+    pub ip: Ipv4Addr,
+    /// Nodes with more than 4 ports reply once per bind index
+    pub bind_index: u8,
+    pub short_name: String,
+    pub long_name: String,
+    pub node_report: String,
+    /// The port addresses of all output ports
+    pub port_addresses: Vec<ArtNetPortAddress>,
+    pub firmware: u16,
+    pub status: u8,
+    pub last_seen: DateTime<Local>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Copy, JsonSchema)]
@@ -38,3 +509,80 @@ impl Speed {
         }
     }
 }
+
+/// The lifecycle of a running endpoint
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum EndpointState {
+    Starting,
+    Running,
+    /// The endpoint failed and is restarted after a backoff
+    Error(String),
+    Stopped,
+}
+
+/// The state of the endpoint at `index` in the endpoint list of `universe`
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct EndpointStatus {
+    pub universe: UniverseId,
+    pub index: usize,
+    pub state: EndpointState,
+    /// How often the endpoint was restarted after an error
+    pub restarts: u32,
+    pub since: DateTime<Local>,
+    /// What the endpoint read back from its device
+    #[serde(default)]
+    pub device: Option<EndpointDevice>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum EndpointDevice {
+    UsbPro(UsbProDevice),
+}
+
+/// The serial number and the parameters stored on an Enttec DMX USB Pro compatible widget
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct UsbProDevice {
+    pub serial_number: u32,
+    pub firmware: u16,
+    /// DMX break time in 10.67 microsecond units
+    pub break_time: u8,
+    /// DMX mark after break time in 10.67 microsecond units
+    pub mab_time: u8,
+    /// DMX packets per second, 0 sends as fast as possible
+    pub refresh_rate: u8,
+}
+
+impl UsbProDevice {
+    /// The firmware version as major.minor
+    pub fn firmware_version(&self) -> String {
+        let [minor, major] = self.firmware.to_le_bytes();
+        format!("{major}.{minor}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_baseline_config() {
+        let json = r#"{"endpoints":{"1":["Logger","ArtNet"]}}"#;
+
+        let config: EndPointConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.endpoints[&UniverseId(1)],
+            vec![EPConfigItem::Logger, EPConfigItem::default_artnet()]
+        );
+    }
+
+    #[test]
+    fn checks_port_address_ranges() {
+        let address: Result<ArtNetPortAddress, _> =
+            serde_json::from_str(r#"{"net":127,"subnet":15,"universe":15}"#);
+        assert_eq!(u16::from(address.unwrap()), 0x7fff);
+
+        let address: Result<ArtNetPortAddress, _> =
+            serde_json::from_str(r#"{"net":0,"subnet":16,"universe":0}"#);
+        assert!(address.is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! The binary stream of fader values, for clients on slow connections.
+//!
+//! Every universe has its own sequence of frames. A keyframe carries all values of the universe, a delta frame only
+//! the channels that changed since the frame before it. Keyframes are repeated periodically, so a client that missed
+//! a frame notices the gap in the sequence numbers and resyncs with the next keyframe or by requesting the universe.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+
+use crate::patched::UniverseId;
+use crate::universe::UNIVERSE_SIZE;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct FaderFrame {
+    pub universe: UniverseId,
+    /// Counts the frames of the universe, wrapping around
+    pub seq: u32,
+    pub values: FrameValues,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum FrameValues {
+    /// All values of the universe
+    Key(Vec<u8>),
+    /// The changed channels and their new values
+    Delta(Vec<(u16, u8)>),
+}
+
+/// Tracks the values a client was sent last, one per connection
+#[derive(Debug, Default)]
+pub struct FaderEncoder {
+    universes: HashMap<UniverseId, (u32, [u8; UNIVERSE_SIZE])>,
+}
+
+impl FaderEncoder {
+    pub fn keyframe(&mut self, universe: UniverseId, values: &[u8; UNIVERSE_SIZE]) -> FaderFrame {
+        let seq = self.next_seq(&universe);
+        self.universes.insert(universe, (seq, *values));
+        FaderFrame {
+            universe,
+            seq,
+            values: FrameValues::Key(values.to_vec()),
+        }
+    }
+
+    /// The channels that changed since the last frame of the universe, `None` if nothing changed.
+    /// A universe that was never sent gets a keyframe.
+    pub fn delta(
+        &mut self,
+        universe: UniverseId,
+        values: &[u8; UNIVERSE_SIZE],
+    ) -> Option<FaderFrame> {
+        let Some((seq, last)) = self.universes.get_mut(&universe) else {
+            return Some(self.keyframe(universe, values));
+        };
+
+        let changes = (0..UNIVERSE_SIZE)
+            .filter(|i| last[*i] != values[*i])
+            .map(|i| (i as u16, values[i]))
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            return None;
+        }
+
+        *seq = seq.wrapping_add(1);
+        *last = *values;
+        Some(FaderFrame {
+            universe,
+            seq: *seq,
+            values: FrameValues::Delta(changes),
+        })
+    }
+
+    /// Marks a value as known to the client without sending it, e.g. because the client set it itself
+    pub fn assume(&mut self, universe: UniverseId, channel: usize, value: u8) {
+        if let Some(v) = self
+            .universes
+            .get_mut(&universe)
+            .and_then(|(_, last)| last.get_mut(channel))
+        {
+            *v = value;
+        }
+    }
+
+    fn next_seq(&self, universe: &UniverseId) -> u32 {
+        self.universes
+            .get(universe)
+            .map(|(seq, _)| seq.wrapping_add(1))
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decoded {
+    Updated(UniverseId),
+    /// A frame of the universe was missed, a keyframe has to be requested
+    Missed(UniverseId),
+    /// The universe waits for a keyframe after a missed frame
+    Stale(UniverseId),
+}
+
+/// Rebuilds the values of every universe from the frames the client receives
+#[derive(Debug, Default)]
+pub struct FaderDecoder {
+    /// `None` for universes that missed a frame
+    universes: HashMap<UniverseId, Option<(u32, [u8; UNIVERSE_SIZE])>>,
+}
+
+impl FaderDecoder {
+    pub fn apply(&mut self, frame: FaderFrame) -> Decoded {
+        let universe = frame.universe;
+        match frame.values {
+            FrameValues::Key(values) => {
+                let mut all = [0; UNIVERSE_SIZE];
+                for (v, new) in all.iter_mut().zip(values) {
+                    *v = new;
+                }
+                self.universes.insert(universe, Some((frame.seq, all)));
+                Decoded::Updated(universe)
+            }
+            FrameValues::Delta(changes) => match self.universes.get_mut(&universe) {
+                Some(Some((seq, values))) if seq.wrapping_add(1) == frame.seq => {
+                    *seq = frame.seq;
+                    for (c, v) in changes {
+                        if let Some(value) = values.get_mut(c as usize) {
+                            *value = v;
+                        }
+                    }
+                    Decoded::Updated(universe)
+                }
+                Some(None) => Decoded::Stale(universe),
+                _ => {
+                    self.universes.insert(universe, None);
+                    Decoded::Missed(universe)
+                }
+            },
+        }
+    }
+
+    /// The values of the universe, `None` while it waits for a keyframe
+    pub fn values(&self, universe: &UniverseId) -> Option<&[u8; UNIVERSE_SIZE]> {
+        self.universes
+            .get(universe)?
+            .as_ref()
+            .map(|(_, values)| values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resyncs_after_missed_frame() {
+        let universe = UniverseId(1);
+        let mut encoder = FaderEncoder::default();
+        let mut decoder = FaderDecoder::default();
+        let mut values = [0; UNIVERSE_SIZE];
+
+        let key = encoder.delta(universe, &values).unwrap();
+        assert!(matches!(key.values, FrameValues::Key(_)));
+        assert_eq!(decoder.apply(key), Decoded::Updated(universe));
+        assert_eq!(encoder.delta(universe, &values), None);
+
+        values[3] = 200;
+        let delta = encoder.delta(universe, &values).unwrap();
+        assert_eq!(delta.values, FrameValues::Delta(vec![(3, 200)]));
+        assert_eq!(decoder.apply(delta), Decoded::Updated(universe));
+        assert_eq!(decoder.values(&universe).unwrap()[3], 200);
+
+        // The frame setting channel 4 gets lost
+        values[4] = 10;
+        encoder.delta(universe, &values).unwrap();
+        values[5] = 20;
+        let delta = encoder.delta(universe, &values).unwrap();
+        assert_eq!(decoder.apply(delta.clone()), Decoded::Missed(universe));
+        assert_eq!(decoder.apply(delta), Decoded::Stale(universe));
+        assert_eq!(decoder.values(&universe), None);
+
+        let key = encoder.keyframe(universe, &values);
+        assert_eq!(decoder.apply(key), Decoded::Updated(universe));
+        assert_eq!(decoder.values(&universe), Some(&values));
+    }
+}
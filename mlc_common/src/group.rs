@@ -0,0 +1,141 @@
+//! Named, ordered groups of patched fixtures, usable wherever a list of fixtures is accepted.
+
+use schemars::JsonSchema;
+
+use crate::patched::FixtureId;
+
+pub type GroupId = uuid::Uuid;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct FixtureGroup {
+    pub id: GroupId,
+    pub name: String,
+    /// In the order of the group, e.g. for spreading values
+    pub fixtures: Vec<FixtureId>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum GroupRequest {
+    Create {
+        name: String,
+        fixtures: Vec<FixtureId>,
+    },
+    Rename {
+        group: GroupId,
+        name: String,
+    },
+    SetFixtures {
+        group: GroupId,
+        fixtures: Vec<FixtureId>,
+    },
+    Delete {
+        group: GroupId,
+    },
+}
+
+/// Applies the request to the groups and returns the id of the group it changed.
+///
+/// Every fixture has to be in `patched`, fixtures listed more than once are kept at their first position.
+pub fn apply(
+    groups: &mut Vec<FixtureGroup>,
+    request: GroupRequest,
+    patched: &[FixtureId],
+) -> Result<GroupId, String> {
+    match request {
+        GroupRequest::Create { name, fixtures } => {
+            let group = FixtureGroup {
+                id: GroupId::new_v4(),
+                name,
+                fixtures: members(fixtures, patched)?,
+            };
+            let id = group.id;
+            groups.push(group);
+            Ok(id)
+        }
+        GroupRequest::Rename { group, name } => {
+            find(groups, group)?.name = name;
+            Ok(group)
+        }
+        GroupRequest::SetFixtures { group, fixtures } => {
+            let fixtures = members(fixtures, patched)?;
+            find(groups, group)?.fixtures = fixtures;
+            Ok(group)
+        }
+        GroupRequest::Delete { group } => {
+            find(groups, group)?;
+            groups.retain(|g| g.id != group);
+            Ok(group)
+        }
+    }
+}
+
+/// Removes fixtures that are no longer patched from the groups and returns whether any group changed
+pub fn prune(groups: &mut [FixtureGroup], patched: &[FixtureId]) -> bool {
+    let mut changed = false;
+    for g in groups {
+        let len = g.fixtures.len();
+        g.fixtures.retain(|f| patched.contains(f));
+        changed |= g.fixtures.len() != len;
+    }
+    changed
+}
+
+fn find(groups: &mut [FixtureGroup], id: GroupId) -> Result<&mut FixtureGroup, String> {
+    groups
+        .iter_mut()
+        .find(|g| g.id == id)
+        .ok_or_else(|| format!("Group {id} does not exist"))
+}
+
+fn members(fixtures: Vec<FixtureId>, patched: &[FixtureId]) -> Result<Vec<FixtureId>, String> {
+    let mut members = Vec::with_capacity(fixtures.len());
+    for f in fixtures {
+        if !patched.contains(&f) {
+            return Err(format!("Fixture {f} is not patched"));
+        }
+        if !members.contains(&f) {
+            members.push(f);
+        }
+    }
+    Ok(members)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_keep_order_and_drop_unpatched() {
+        let patched = [
+            FixtureId::new_v4(),
+            FixtureId::new_v4(),
+            FixtureId::new_v4(),
+        ];
+        let mut groups = vec![];
+
+        let id = apply(
+            &mut groups,
+            GroupRequest::Create {
+                name: "Wash".to_string(),
+                fixtures: vec![patched[2], patched[0], patched[2]],
+            },
+            &patched,
+        )
+        .unwrap();
+        assert_eq!(groups[0].fixtures, vec![patched[2], patched[0]]);
+
+        assert!(apply(
+            &mut groups,
+            GroupRequest::SetFixtures {
+                group: id,
+                fixtures: vec![FixtureId::new_v4()],
+            },
+            &patched,
+        )
+        .is_err());
+
+        assert!(prune(&mut groups, &patched[..2]));
+        assert_eq!(groups[0].fixtures, vec![patched[0]]);
+        assert!(!prune(&mut groups, &patched[..2]));
+    }
+}
@@ -0,0 +1,55 @@
+//! Locating fixtures: the highlighted fixture shines open white and centered while every other fixture is dark.
+
+use schemars::JsonSchema;
+
+use crate::group::GroupId;
+use crate::patched::FixtureId;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum HighlightRequest {
+    Fixture(FixtureId),
+    /// The member of the group after the highlighted fixture, the first member if none is highlighted
+    Next(GroupId),
+    /// The member of the group before the highlighted fixture, the last member if none is highlighted
+    Previous(GroupId),
+    /// Every fixture returns to its previous state
+    Off,
+}
+
+/// The member after or before `current`, wrapping around. `None` for a group without members.
+pub fn step(members: &[FixtureId], current: Option<FixtureId>, forward: bool) -> Option<FixtureId> {
+    let len = members.len();
+    if len == 0 {
+        return None;
+    }
+
+    let index = match current.and_then(|c| members.iter().position(|m| *m == c)) {
+        Some(i) if forward => (i + 1) % len,
+        Some(i) => (i + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    Some(members[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_around_group() {
+        let members = [
+            FixtureId::new_v4(),
+            FixtureId::new_v4(),
+            FixtureId::new_v4(),
+        ];
+        let outside = Some(FixtureId::new_v4());
+
+        assert_eq!(step(&members, outside, true), Some(members[0]));
+        assert_eq!(step(&members, outside, false), Some(members[2]));
+        assert_eq!(step(&members, Some(members[2]), true), Some(members[0]));
+        assert_eq!(step(&members, Some(members[0]), false), Some(members[2]));
+        assert_eq!(step(&members, Some(members[1]), true), Some(members[2]));
+        assert_eq!(step(&[], None, true), None);
+    }
+}
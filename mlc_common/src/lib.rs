@@ -8,9 +8,20 @@ use crate::patched::{UniverseAddress, UniverseId};
 use crate::universe::UNIVERSE_SIZE;
 
 pub mod config;
+pub mod curve;
+pub mod defaults;
 pub mod endpoints;
+pub mod fader_stream;
 pub mod fixture;
+pub mod group;
+pub mod highlight;
+pub mod masters;
+pub mod merge_policy;
+pub mod parked;
 pub mod patched;
+pub mod rdm;
+pub mod recording;
+pub mod scene;
 pub mod universe;
 
 pub mod effect;
@@ -27,7 +38,17 @@ pub enum Info {
     UniversePatchChanged(UniverseId),
     UniversesUpdated,
     EndpointConfigChanged,
+    ArtNetNodesChanged,
+    RdmDevicesChanged,
+    ParkedChanged,
+    MergePoliciesChanged,
+    DefaultsChanged,
+    CurvesChanged,
+    EndpointStatesChanged,
     EffectListChanged,
+    ScenesChanged,
+    GroupsChanged,
+    HighlightChanged,
     RequireReload,
     None,
 }
@@ -47,6 +68,16 @@ pub struct ProjectSettings {
     pub save_on_quit: bool,
 }
 
+/// Identifies a websocket client, `0` is the engine itself
+pub type ClientId = usize;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema, PartialEq)]
+pub struct ClientInfo {
+    pub id: ClientId,
+    pub name: Option<String>,
+    pub connected: DateTime<Local>,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[serde_with::serde_as]
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
@@ -55,19 +86,47 @@ pub enum RuntimeUpdate {
         universe: UniverseId,
         channel_index: usize,
         value: u8,
+        author: ClientId,
     },
     ValuesUpdated {
         universes: Vec<UniverseId>,
         channel_indexes: Vec<usize>,
         values: Vec<u8>,
+        author: ClientId,
     },
     Universe {
         universe: UniverseId,
         #[schemars(skip)]
         #[serde_as(as = "[_;UNIVERSE_SIZE]")]
         values: [u8; UNIVERSE_SIZE],
-        author: usize,
+        author: ClientId,
     },
+    /// Sent first on every connection, the id changes of this client are authored by
+    Connected { client: ClientId },
+}
+
+impl RuntimeUpdate {
+    /// The client that caused the update
+    pub fn author(&self) -> ClientId {
+        match self {
+            RuntimeUpdate::ValueUpdated { author, .. }
+            | RuntimeUpdate::ValuesUpdated { author, .. }
+            | RuntimeUpdate::Universe { author, .. } => *author,
+            RuntimeUpdate::Connected { .. } => 0,
+        }
+    }
+
+    /// The universes the update changed
+    pub fn universes(&self) -> Vec<UniverseId> {
+        match self {
+            RuntimeUpdate::ValueUpdated { universe, .. }
+            | RuntimeUpdate::Universe { universe, .. } => {
+                vec![*universe]
+            }
+            RuntimeUpdate::ValuesUpdated { universes, .. } => universes.clone(),
+            RuntimeUpdate::Connected { .. } => vec![],
+        }
+    }
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
@@ -0,0 +1,143 @@
+//! Masters that scale the intensity of every fixture in the output.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+
+use crate::patched::UniverseId;
+use crate::universe::UNIVERSE_SIZE;
+
+/// The grand master, the blackout and the masters of single universes.
+///
+/// Only intensity channels are scaled, so positions and colors of fixtures with a dimmer stay where they are.
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct Masters {
+    pub grand: u8,
+    pub blackout: bool,
+    /// Universes without an entry are at full
+    #[serde(default)]
+    pub universes: HashMap<UniverseId, u8>,
+}
+
+impl Default for Masters {
+    fn default() -> Self {
+        Masters {
+            grand: u8::MAX,
+            blackout: false,
+            universes: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum MasterRequest {
+    Grand(u8),
+    Blackout(bool),
+    /// `None` removes the master of the universe
+    Universe {
+        universe: UniverseId,
+        value: Option<u8>,
+    },
+}
+
+impl Masters {
+    pub fn apply(&mut self, request: MasterRequest) {
+        match request {
+            MasterRequest::Grand(v) => self.grand = v,
+            MasterRequest::Blackout(b) => self.blackout = b,
+            MasterRequest::Universe {
+                universe,
+                value: Some(v),
+            } => {
+                self.universes.insert(universe, v);
+            }
+            MasterRequest::Universe {
+                universe,
+                value: None,
+            } => {
+                self.universes.remove(&universe);
+            }
+        }
+    }
+
+    /// The factor intensity channels of the universe are scaled with
+    pub fn factor(&self, universe: &UniverseId) -> f64 {
+        if self.blackout {
+            return 0.0;
+        }
+        let universe = self.universes.get(universe).copied().unwrap_or(u8::MAX);
+        self.grand as f64 / 255.0 * universe as f64 / 255.0
+    }
+
+    /// Scales the intensity channels of a universe frame.
+    ///
+    /// Every tile lists the channels of one intensity value, coarse first.
+    /// Fine channels are scaled together with their coarse channel, so 16 and 24 bit dimmers fade smoothly.
+    pub fn scale(
+        &self,
+        universe: &UniverseId,
+        tiles: &[Vec<usize>],
+        values: &mut [u8; UNIVERSE_SIZE],
+    ) {
+        let factor = self.factor(universe);
+        if factor >= 1.0 {
+            return;
+        }
+
+        for tile in tiles {
+            if tile.is_empty() || tile.iter().any(|c| *c >= UNIVERSE_SIZE) {
+                continue;
+            }
+            let mut bytes = tile.iter().map(|c| values[*c]).collect::<Vec<_>>();
+            scale_tile(&mut bytes, factor);
+            for (c, b) in tile.iter().zip(bytes) {
+                values[*c] = b;
+            }
+        }
+    }
+}
+
+/// Scales the value the bytes of a tile form together, coarse first
+pub fn scale_tile(bytes: &mut [u8], factor: f64) {
+    let value = bytes.iter().fold(0u64, |v, b| (v << 8) | *b as u64);
+    let mut scaled = (value as f64 * factor).round() as u64;
+    for b in bytes.iter_mut().rev() {
+        *b = (scaled & 0xff) as u8;
+        scaled >>= 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_intensity_tiles() {
+        let mut values = [255; UNIVERSE_SIZE];
+        values[1] = 0;
+        let tiles = vec![vec![0], vec![1, 2]];
+        let universe = UniverseId(1);
+
+        let mut masters = Masters::default();
+        let mut frame = values;
+        masters.scale(&universe, &tiles, &mut frame);
+        assert_eq!(frame, values);
+
+        masters.apply(MasterRequest::Grand(128));
+        masters.apply(MasterRequest::Universe {
+            universe,
+            value: Some(128),
+        });
+        let mut frame = values;
+        masters.scale(&universe, &tiles, &mut frame);
+        assert_eq!(frame[0], 64);
+        // 0x00ff scaled as one value
+        assert_eq!((frame[1], frame[2]), (0, 64));
+        assert_eq!(frame[3], 255);
+
+        masters.apply(MasterRequest::Blackout(true));
+        let mut frame = values;
+        masters.scale(&universe, &tiles, &mut frame);
+        assert_eq!(&frame[..4], &[0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,74 @@
+//! Channels the playbacks merge with a fixed policy instead of HTP for intensity and LTP for everything else.
+
+use schemars::JsonSchema;
+
+use crate::endpoints::MergeMode;
+use crate::patched::{UniverseAddress, UniverseId};
+use crate::universe::UNIVERSE_SIZE;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct MergePolicy {
+    pub universe: UniverseId,
+    pub channel: UniverseAddress,
+    /// With [`MergeMode::Priority`] the playbacks merge HTP among each other but always win over the defaults
+    pub mode: MergeMode,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum MergePolicyRequest {
+    Set {
+        universe: UniverseId,
+        channel: UniverseAddress,
+        mode: MergeMode,
+    },
+    /// The channel merges by the default policy again
+    Reset {
+        universe: UniverseId,
+        channel: UniverseAddress,
+    },
+    ResetAll,
+}
+
+/// Applies the request to the policies, channels can only be set in universes that exist
+pub fn apply(
+    policies: &mut Vec<MergePolicy>,
+    request: MergePolicyRequest,
+    universes: &[UniverseId],
+) -> Result<(), String> {
+    match request {
+        MergePolicyRequest::Set {
+            universe,
+            channel,
+            mode,
+        } => {
+            if !universes.contains(&universe) {
+                return Err(format!("Universe {} does not exist", universe.0));
+            }
+            policies.retain(|p| p.universe != universe || p.channel != channel);
+            policies.push(MergePolicy {
+                universe,
+                channel,
+                mode,
+            });
+        }
+        MergePolicyRequest::Reset { universe, channel } => {
+            policies.retain(|p| p.universe != universe || p.channel != channel);
+        }
+        MergePolicyRequest::ResetAll => policies.clear(),
+    }
+
+    policies.sort_by_key(|p| (p.universe, p.channel.i()));
+    Ok(())
+}
+
+/// The policies of one universe, `None` for channels that merge by the default policy
+pub fn policy_modes(
+    policies: &[MergePolicy],
+    universe: UniverseId,
+) -> [Option<MergeMode>; UNIVERSE_SIZE] {
+    let mut modes = [None; UNIVERSE_SIZE];
+    for p in policies.iter().filter(|p| p.universe == universe) {
+        modes[p.channel.i()] = Some(p.mode);
+    }
+    modes
+}
@@ -0,0 +1,264 @@
+//! Channels parked at a fixed value, which nothing else can change until they are unparked.
+
+use std::collections::{HashMap, HashSet};
+
+use schemars::JsonSchema;
+
+use crate::patched::{FixtureId, UniverseAddress, UniverseId};
+use crate::universe::{FixtureUniverse, UNIVERSE_SIZE};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct ParkedChannel {
+    pub universe: UniverseId,
+    pub channel: UniverseAddress,
+    pub value: u8,
+    /// The fixture the channel was parked with
+    pub fixture: Option<FixtureId>,
+    /// The channel of that fixture, counted from its start channel, so it follows the fixture when it is patched again
+    #[serde(default)]
+    pub fixture_channel: Option<u8>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum ParkRequest {
+    Channel {
+        universe: UniverseId,
+        channel: UniverseAddress,
+        value: u8,
+    },
+    /// Parks every channel of the fixture, at `value` or at the current output if `None`
+    Fixture {
+        fixture: FixtureId,
+        value: Option<u8>,
+    },
+    UnparkChannel {
+        universe: UniverseId,
+        channel: UniverseAddress,
+    },
+    UnparkFixture {
+        fixture: FixtureId,
+    },
+    UnparkAll,
+}
+
+/// Applies the request to the parked channels.
+///
+/// `current` holds the output values of every universe, fixtures parked without a value keep them.
+pub fn park(
+    parked: &mut Vec<ParkedChannel>,
+    request: ParkRequest,
+    universes: &[FixtureUniverse],
+    current: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+) -> Result<(), String> {
+    match request {
+        ParkRequest::Channel {
+            universe,
+            channel,
+            value,
+        } => {
+            if !universes.iter().any(|u| u.id == universe) {
+                return Err(format!("Universe {} does not exist", universe.0));
+            }
+            parked.retain(|p| p.universe != universe || p.channel != channel);
+            parked.push(ParkedChannel {
+                universe,
+                channel,
+                value,
+                fixture: None,
+                fixture_channel: None,
+            });
+        }
+        ParkRequest::Fixture { fixture, value } => {
+            let (universe, f) = universes
+                .iter()
+                .find_map(|u| {
+                    u.fixtures
+                        .iter()
+                        .find(|f| f.id == fixture)
+                        .map(|f| (u.id, f))
+                })
+                .ok_or_else(|| format!("Fixture {fixture} is not patched"))?;
+
+            let start = f.start_channel.i();
+            let end = (start + f.num_channels as usize).min(UNIVERSE_SIZE);
+            for (offset, i) in (start..end).enumerate() {
+                let channel = UniverseAddress::create(i as u16).map_err(str::to_string)?;
+                let value = value
+                    .or_else(|| current.get(&universe).map(|v| v[i]))
+                    .unwrap_or_default();
+                parked.retain(|p| p.universe != universe || p.channel != channel);
+                parked.push(ParkedChannel {
+                    universe,
+                    channel,
+                    value,
+                    fixture: Some(fixture),
+                    fixture_channel: Some(offset as u8),
+                });
+            }
+        }
+        ParkRequest::UnparkChannel { universe, channel } => {
+            parked.retain(|p| p.universe != universe || p.channel != channel);
+        }
+        ParkRequest::UnparkFixture { fixture } => {
+            parked.retain(|p| p.fixture != Some(fixture));
+        }
+        ParkRequest::UnparkAll => parked.clear(),
+    }
+
+    parked.sort_by_key(|p| (p.universe, p.channel.i()));
+    Ok(())
+}
+
+/// Moves the channels parked with a fixture to where it is patched now and drops those of fixtures or universes that
+/// are gone. Returns whether any parked channel changed
+pub fn follow_patch(parked: &mut Vec<ParkedChannel>, universes: &[FixtureUniverse]) -> bool {
+    let before = parked.clone();
+    parked.retain_mut(|p| {
+        let Some(fixture) = p.fixture else {
+            return universes.iter().any(|u| u.id == p.universe);
+        };
+        let Some((universe, f)) = universes.iter().find_map(|u| {
+            u.fixtures
+                .iter()
+                .find(|f| f.id == fixture)
+                .map(|f| (u.id, f))
+        }) else {
+            return false;
+        };
+
+        p.universe = universe;
+        // Channels parked before they were tracked keep their address within the new universe
+        if let Some(offset) = p.fixture_channel {
+            if offset >= f.num_channels {
+                return false;
+            }
+            match UniverseAddress::create(f.start_channel.i() as u16 + u16::from(offset)) {
+                Ok(channel) => p.channel = channel,
+                Err(_) => return false,
+            }
+        }
+        true
+    });
+
+    // A channel can only be parked once, the last one parked there wins
+    let mut seen = HashSet::new();
+    for i in (0..parked.len()).rev() {
+        if !seen.insert((parked[i].universe, parked[i].channel.i())) {
+            parked.remove(i);
+        }
+    }
+    parked.sort_by_key(|p| (p.universe, p.channel.i()));
+    *parked != before
+}
+
+/// The parked values of one universe, `None` for channels that are not parked
+pub fn parked_values(
+    parked: &[ParkedChannel],
+    universe: UniverseId,
+) -> [Option<u8>; UNIVERSE_SIZE] {
+    let mut values = [None; UNIVERSE_SIZE];
+    for p in parked.iter().filter(|p| p.universe == universe) {
+        values[p.channel.i()] = Some(p.value);
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn park_and_unpark() {
+        let universe = UniverseId(1);
+        let universes = [FixtureUniverse::empty(universe)];
+        let current = HashMap::from([(universe, [7; UNIVERSE_SIZE])]);
+        let mut parked = vec![];
+
+        let channel = UniverseAddress::create(10).unwrap();
+        park(
+            &mut parked,
+            ParkRequest::Channel {
+                universe,
+                channel,
+                value: 100,
+            },
+            &universes,
+            &current,
+        )
+        .unwrap();
+        park(
+            &mut parked,
+            ParkRequest::Channel {
+                universe,
+                channel,
+                value: 200,
+            },
+            &universes,
+            &current,
+        )
+        .unwrap();
+        assert_eq!(parked.len(), 1);
+        assert_eq!(parked_values(&parked, universe)[10], Some(200));
+
+        assert!(park(
+            &mut parked,
+            ParkRequest::Channel {
+                universe: UniverseId(2),
+                channel,
+                value: 0,
+            },
+            &universes,
+            &current,
+        )
+        .is_err());
+
+        park(
+            &mut parked,
+            ParkRequest::UnparkChannel { universe, channel },
+            &universes,
+            &current,
+        )
+        .unwrap();
+        assert!(parked.is_empty());
+    }
+
+    #[test]
+    fn follow_patch_drops_what_is_gone() {
+        let universe = UniverseId(1);
+        let channel = UniverseAddress::create(10).unwrap();
+        let mut parked = vec![
+            ParkedChannel {
+                universe,
+                channel,
+                value: 100,
+                fixture: None,
+                fixture_channel: None,
+            },
+            ParkedChannel {
+                universe,
+                channel: UniverseAddress::create(20).unwrap(),
+                value: 100,
+                fixture: Some(uuid::Uuid::new_v4()),
+                fixture_channel: Some(0),
+            },
+            ParkedChannel {
+                universe: UniverseId(2),
+                channel,
+                value: 100,
+                fixture: None,
+                fixture_channel: None,
+            },
+        ];
+
+        assert!(follow_patch(
+            &mut parked,
+            &[FixtureUniverse::empty(universe)]
+        ));
+        assert_eq!(parked.len(), 1);
+        assert_eq!(parked_values(&parked, universe)[10], Some(100));
+        assert!(!follow_patch(
+            &mut parked,
+            &[FixtureUniverse::empty(universe)]
+        ));
+    }
+}
@@ -11,6 +11,14 @@ pub mod feature;
 
 pub type FixtureId = uuid::Uuid;
 
+/// The fixtures a request applies to, in order
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, JsonSchema, PartialEq)]
+pub enum FixtureSelection {
+    Fixtures(Vec<FixtureId>),
+    /// The current members of a group
+    Group(crate::group::GroupId),
+}
+
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, get_size::GetSize, JsonSchema, PartialEq)]
 pub struct PatchedFixture {
     pub config: FixtureType,
@@ -1,11 +1,15 @@
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use get_size::GetSize;
 use schemars::JsonSchema;
 
-use crate::config::DmxRange;
+use crate::config::{DmxRange, ValueResolution};
+use crate::curve::{FeatureCurves, OutputCurve};
 use crate::fixture::FaderAddress;
+use crate::patched::{FixtureSelection, UniverseId};
+use crate::universe::UNIVERSE_SIZE;
 use crate::utils::bounds::{NegOne, One, Zero};
 use crate::utils::BoundedValue;
 
@@ -113,6 +117,55 @@ impl FixtureFeature {
             FixtureFeature::Amber(_) => FixtureFeatureType::Amber,
         }
     }
+
+    pub fn tiles(&self) -> Vec<&FeatureTile> {
+        match self {
+            FixtureFeature::Dimmer(d) | FixtureFeature::White(d) | FixtureFeature::Amber(d) => {
+                vec![&d.dimmer]
+            }
+            FixtureFeature::Rgb(rgb) => vec![&rgb.red, &rgb.green, &rgb.blue],
+            FixtureFeature::Rotation(r) => vec![&r.cw, &r.ccw],
+            FixtureFeature::PanTilt(p) => vec![&p.pan, &p.tilt],
+        }
+    }
+
+    /// The current values of the feature read back from the output, as the request that would set them.
+    /// `None` if a universe of the feature has no output.
+    pub fn read(
+        &self,
+        output: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+        curves: &FeatureCurves,
+    ) -> Option<FeatureSetRequest> {
+        let curve = curves.get(self.name());
+        let read = |tile: &FeatureTile| tile.read(output, curve).map(BoundedValue::create);
+        Some(match self {
+            FixtureFeature::Dimmer(d) => FeatureSetRequest::Dimmer {
+                value: read(&d.dimmer)?,
+            },
+            FixtureFeature::White(d) => FeatureSetRequest::White {
+                value: read(&d.dimmer)?,
+            },
+            FixtureFeature::Amber(d) => FeatureSetRequest::Amber {
+                value: read(&d.dimmer)?,
+            },
+            FixtureFeature::Rgb(rgb) => FeatureSetRequest::Rgb {
+                red: read(&rgb.red)?,
+                green: read(&rgb.green)?,
+                blue: read(&rgb.blue)?,
+            },
+            FixtureFeature::Rotation(r) => {
+                let cw = r.cw.read(output, curve)?;
+                let ccw = r.ccw.read(output, curve)?;
+                FeatureSetRequest::Rotation {
+                    value: BoundedValue::create(if cw > 0.0 { cw } else { -ccw }),
+                }
+            }
+            FixtureFeature::PanTilt(p) => FeatureSetRequest::PanTilt {
+                pan: read(&p.pan)?,
+                tilt: read(&p.tilt)?,
+            },
+        })
+    }
 }
 
 pub trait HasFixtureFeature {
@@ -164,6 +217,59 @@ pub enum FeatureTile {
     },
 }
 
+impl FeatureTile {
+    /// The faders of the tile, coarse first
+    pub fn faders(&self) -> Vec<FaderAddress> {
+        match self {
+            FeatureTile::Single { fader, .. } => vec![*fader],
+            FeatureTile::Double {
+                fader, fader_fine, ..
+            } => vec![*fader, *fader_fine],
+            FeatureTile::Tripple {
+                fader,
+                fader_fine,
+                fader_grain,
+                ..
+            } => vec![*fader, *fader_fine, *fader_grain],
+        }
+    }
+
+    pub fn range(&self) -> &DmxRange {
+        match self {
+            FeatureTile::Single { range, .. }
+            | FeatureTile::Double { range, .. }
+            | FeatureTile::Tripple { range, .. } => range,
+        }
+    }
+
+    /// The value from 0 to 1 the output of the faders was mapped from, undoing the range and the curve.
+    /// `None` if a universe of the tile has no output.
+    pub fn read(
+        &self,
+        output: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+        curve: &OutputCurve,
+    ) -> Option<f64> {
+        let faders = self.faders();
+        let mut raw = 0u32;
+        for f in &faders {
+            raw = (raw << 8) | *output.get(&f.universe)?.get(f.address.i())? as u32;
+        }
+        let resolution = match faders.len() {
+            1 => ValueResolution::U8,
+            2 => ValueResolution::U16,
+            _ => ValueResolution::U24,
+        };
+
+        let range = self.range();
+        let span = range.end.raw() - range.start.raw();
+        if span == 0.0 {
+            return Some(0.0);
+        }
+        let v = raw as f64 / resolution.max() as f64;
+        Some(curve.invert((v - range.start.raw()) / span))
+    }
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
 pub enum FeatureSetRequest {
     // 0.0 -> 1.0
@@ -196,8 +302,164 @@ pub enum FeatureSetRequest {
     GetAvailableFeatures,
 }
 
+impl FeatureSetRequest {
+    /// The request between `self` at 0 and `to` at 1, `None` if they set different features
+    pub fn lerp(&self, to: &FeatureSetRequest, t: f64) -> Option<FeatureSetRequest> {
+        let l = |a: f64, b: f64| a + (b - a) * t;
+        Some(match (self, to) {
+            (FeatureSetRequest::Dimmer { value: a }, FeatureSetRequest::Dimmer { value: b }) => {
+                FeatureSetRequest::Dimmer {
+                    value: BoundedValue::create(l(**a, **b)),
+                }
+            }
+            (FeatureSetRequest::White { value: a }, FeatureSetRequest::White { value: b }) => {
+                FeatureSetRequest::White {
+                    value: BoundedValue::create(l(**a, **b)),
+                }
+            }
+            (FeatureSetRequest::Amber { value: a }, FeatureSetRequest::Amber { value: b }) => {
+                FeatureSetRequest::Amber {
+                    value: BoundedValue::create(l(**a, **b)),
+                }
+            }
+            (
+                FeatureSetRequest::Rgb {
+                    red: r0,
+                    green: g0,
+                    blue: b0,
+                },
+                FeatureSetRequest::Rgb {
+                    red: r1,
+                    green: g1,
+                    blue: b1,
+                },
+            ) => FeatureSetRequest::Rgb {
+                red: BoundedValue::create(l(**r0, **r1)),
+                green: BoundedValue::create(l(**g0, **g1)),
+                blue: BoundedValue::create(l(**b0, **b1)),
+            },
+            (
+                FeatureSetRequest::Rotation { value: a },
+                FeatureSetRequest::Rotation { value: b },
+            ) => FeatureSetRequest::Rotation {
+                value: BoundedValue::create(l(**a, **b)),
+            },
+            (
+                FeatureSetRequest::PanTilt { pan: p0, tilt: t0 },
+                FeatureSetRequest::PanTilt { pan: p1, tilt: t1 },
+            ) => FeatureSetRequest::PanTilt {
+                pan: BoundedValue::create(l(**p0, **p1)),
+                tilt: BoundedValue::create(l(**t0, **t1)),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Sets a feature of every fixture in a selection at once, fixtures without the feature are skipped
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, PartialEq)]
+pub struct SelectionFeatureRequest {
+    pub selection: FixtureSelection,
+    pub request: FeatureSetRequest,
+    /// The value of the last fixture, the values fan out evenly from `request` on the first fixture to it
+    #[serde(default)]
+    pub spread_to: Option<FeatureSetRequest>,
+}
+
+impl SelectionFeatureRequest {
+    /// The request for every fixture of the selection
+    pub fn spread(&self, fixtures: usize) -> Result<Vec<FeatureSetRequest>, String> {
+        let Some(to) = &self.spread_to else {
+            return Ok(vec![self.request.clone(); fixtures]);
+        };
+
+        (0..fixtures)
+            .map(|i| {
+                let t = if fixtures > 1 {
+                    i as f64 / (fixtures - 1) as f64
+                } else {
+                    0.0
+                };
+                self.request
+                    .lerp(to, t)
+                    .ok_or_else(|| "A spread has to end on the same feature".to_string())
+            })
+            .collect()
+    }
+}
+
 /// The Offset of channelss from the start of the Fixture Fader = start_index + self
 #[derive(
     Debug, serde::Serialize, serde::Deserialize, Clone, get_size::GetSize, JsonSchema, PartialEq,
 )]
 pub struct FeatureChannel(pub usize);
+
+#[cfg(test)]
+mod tests {
+    use crate::config::Percentage;
+    use crate::patched::UniverseAddress;
+
+    use super::*;
+
+    #[test]
+    fn reads_fine_tiles_back() {
+        let universe = UniverseId(1);
+        let fader = |c| FaderAddress {
+            universe,
+            address: UniverseAddress::create(c).unwrap(),
+        };
+        let tile = FeatureTile::Double {
+            channel: FeatureChannel(0),
+            channel_fine: FeatureChannel(1),
+            fader: fader(0),
+            fader_fine: fader(1),
+            range: DmxRange {
+                start: Percentage::new(0.5),
+                end: Percentage::full(),
+            },
+        };
+
+        // 0.5 of the upper half of the range is 0xbfff
+        let mut values = [0; UNIVERSE_SIZE];
+        values[0] = 0xbf;
+        values[1] = 0xff;
+        let output = HashMap::from([(universe, values)]);
+        let v = tile.read(&output, &OutputCurve::Linear).unwrap();
+        assert!((v - 0.5).abs() < 0.0001, "{v}");
+
+        let v = tile.read(&output, &OutputCurve::Square).unwrap();
+        assert!((v * v - 0.5).abs() < 0.0001, "{v}");
+
+        assert_eq!(tile.read(&HashMap::new(), &OutputCurve::Linear), None);
+    }
+
+    #[test]
+    fn spreads_across_selection() {
+        let dimmer = |v| FeatureSetRequest::Dimmer {
+            value: BoundedValue::create(v),
+        };
+        let request = SelectionFeatureRequest {
+            selection: FixtureSelection::Fixtures(vec![]),
+            request: dimmer(0.0),
+            spread_to: Some(dimmer(1.0)),
+        };
+        assert_eq!(
+            request.spread(5).unwrap(),
+            vec![
+                dimmer(0.0),
+                dimmer(0.25),
+                dimmer(0.5),
+                dimmer(0.75),
+                dimmer(1.0)
+            ]
+        );
+
+        let request = SelectionFeatureRequest {
+            spread_to: Some(FeatureSetRequest::White {
+                value: BoundedValue::create(1.0),
+            }),
+            ..request
+        };
+        assert!(request.spread(2).is_err());
+    }
+}
@@ -0,0 +1,321 @@
+//! RDM devices found behind Art-Net nodes and how they compare to the patch of the project.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::net::Ipv4Addr;
+
+use chrono::{DateTime, Local};
+use schemars::JsonSchema;
+
+use crate::config::FixtureType;
+use crate::endpoints::ArtNetPortAddress;
+use crate::patched::{FixtureId, UniverseId};
+use crate::universe::FixtureUniverse;
+
+/// The unique id of an RDM device, the ESTA manufacturer id followed by the device id
+#[derive(
+    Debug,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Clone,
+    Copy,
+    JsonSchema,
+)]
+pub struct RdmUid {
+    pub manufacturer: u16,
+    pub device: u32,
+}
+
+impl RdmUid {
+    pub fn from_bytes(bytes: [u8; 6]) -> RdmUid {
+        RdmUid {
+            manufacturer: u16::from_be_bytes([bytes[0], bytes[1]]),
+            device: u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]),
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; 6] {
+        let mut bytes = [0; 6];
+        bytes[..2].copy_from_slice(&self.manufacturer.to_be_bytes());
+        bytes[2..].copy_from_slice(&self.device.to_be_bytes());
+        bytes
+    }
+}
+
+impl Display for RdmUid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04X}:{:08X}", self.manufacturer, self.device)
+    }
+}
+
+/// An RDM device as reported by its responses to DEVICE_INFO and the label PIDs
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct RdmDevice {
+    pub uid: RdmUid,
+    /// The Art-Net node the device is connected to
+    pub node: Ipv4Addr,
+    pub port_address: ArtNetPortAddress,
+    pub manufacturer: String,
+    pub model: String,
+    pub model_id: u16,
+    pub label: String,
+    pub software_version: u32,
+    /// The 1-based start address, `None` for devices without a DMX footprint
+    pub dmx_start_address: Option<u16>,
+    pub footprint: u16,
+    /// The 1-based number of the current personality
+    pub personality: u8,
+    pub personality_count: u8,
+    pub personality_description: String,
+    pub last_seen: DateTime<Local>,
+}
+
+/// Changes the DMX start address of an RDM device
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone, JsonSchema)]
+pub struct RdmAddressRequest {
+    pub uid: RdmUid,
+    /// The 1-based start address
+    pub address: u16,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub enum RdmPatchState {
+    /// A fixture of the same model and footprint starts at the address of the device
+    Matched,
+    /// The fixture at the address of the device uses a different number of channels
+    FootprintMismatch { patched: u16 },
+    /// The fixture type at the address of the device does not name its model
+    ModelMismatch,
+    /// No fixture starts at the address of the device
+    Unpatched,
+    /// The Art-Net port of the device is not the output of any universe
+    NoUniverse,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct RdmPatchCheck {
+    pub device: RdmDevice,
+    pub universe: Option<UniverseId>,
+    /// The id and name of the fixture patched at the address of the device
+    pub fixture: Option<(FixtureId, String)>,
+    pub state: RdmPatchState,
+}
+
+/// A patched fixture without an RDM device at its address
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct RdmMissingFixture {
+    pub universe: UniverseId,
+    pub fixture: FixtureId,
+    pub name: String,
+    /// The 1-based start address
+    pub address: u16,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, Default, JsonSchema)]
+pub struct RdmPatchReport {
+    pub devices: Vec<RdmPatchCheck>,
+    /// Only universes with at least one discovered device are checked for missing fixtures,
+    /// the others might just not support RDM
+    pub missing: Vec<RdmMissingFixture>,
+}
+
+/// Compares the discovered devices to the fixtures patched in the universes their Art-Net ports output
+pub fn check_patch(
+    devices: &[RdmDevice],
+    outputs: &HashMap<ArtNetPortAddress, UniverseId>,
+    universes: &[FixtureUniverse],
+) -> RdmPatchReport {
+    let mut report = RdmPatchReport::default();
+
+    for device in devices {
+        let universe = outputs.get(&device.port_address).copied();
+        let fixture = universe.zip(device.dmx_start_address).and_then(|(u, address)| {
+            universes
+                .iter()
+                .filter(|fu| fu.id == u)
+                .flat_map(|fu| &fu.fixtures)
+                .find(|f| u16::from(f.start_channel) + 1 == address)
+        });
+
+        let state = match (universe, fixture) {
+            (None, _) => RdmPatchState::NoUniverse,
+            (Some(_), None) => RdmPatchState::Unpatched,
+            (Some(_), Some(f)) if !same_model(&f.config, device) => RdmPatchState::ModelMismatch,
+            (Some(_), Some(f)) if f.num_channels as u16 != device.footprint => {
+                RdmPatchState::FootprintMismatch {
+                    patched: f.num_channels as u16,
+                }
+            }
+            (Some(_), Some(_)) => RdmPatchState::Matched,
+        };
+
+        report.devices.push(RdmPatchCheck {
+            device: device.clone(),
+            universe,
+            fixture: fixture.map(|f| (f.id, f.name.clone())),
+            state,
+        });
+    }
+
+    for fu in universes {
+        let found = report
+            .devices
+            .iter()
+            .filter(|c| c.universe == Some(fu.id))
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            continue;
+        }
+
+        for f in &fu.fixtures {
+            let address = u16::from(f.start_channel) + 1;
+            if !found
+                .iter()
+                .any(|c| c.device.dmx_start_address == Some(address))
+            {
+                report.missing.push(RdmMissingFixture {
+                    universe: fu.id,
+                    fixture: f.id,
+                    name: f.name.clone(),
+                    address,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Whether the name of the fixture type and the model description of the device name the same model.
+///
+/// Both are compared without case, spaces and punctuation as fixture libraries and firmwares rarely agree on them.
+/// Devices that do not report a model are assumed to match.
+fn same_model(fixture: &FixtureType, device: &RdmDevice) -> bool {
+    let model = normalize(&device.model);
+    if model.is_empty() {
+        return true;
+    }
+
+    [&fixture.name, &fixture.short_name]
+        .into_iter()
+        .map(|n| normalize(n))
+        .filter(|n| !n.is_empty())
+        .any(|n| model.contains(&n) || n.contains(&model))
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{FixtureType, Matrix};
+    use crate::patched::PatchedFixture;
+
+    use super::*;
+
+    fn fixture(name: &str, start: u16, channels: u8) -> PatchedFixture {
+        PatchedFixture {
+            config: FixtureType {
+                name: name.to_string(),
+                short_name: String::new(),
+                categories: vec![],
+                fixture_key: String::new(),
+                modes: vec![],
+                available_channels: HashMap::new(),
+                matrix: Matrix {
+                    mat: vec![],
+                    dimensions: [0, 0, 0],
+                },
+                id: uuid::Uuid::new_v4(),
+            },
+            num_channels: channels,
+            channels: vec![],
+            start_channel: start.into(),
+            name: name.to_string(),
+            mode: 0,
+            features: vec![],
+            id: uuid::Uuid::new_v4(),
+        }
+    }
+
+    fn device(device: u32, model: &str, address: u16, footprint: u16) -> RdmDevice {
+        RdmDevice {
+            uid: RdmUid {
+                manufacturer: 0x7ff0,
+                device,
+            },
+            node: Ipv4Addr::LOCALHOST,
+            port_address: ArtNetPortAddress::default(),
+            manufacturer: String::new(),
+            model: model.to_string(),
+            model_id: 0,
+            label: String::new(),
+            software_version: 0,
+            dmx_start_address: Some(address),
+            footprint,
+            personality: 1,
+            personality_count: 1,
+            personality_description: String::new(),
+            last_seen: Local::now(),
+        }
+    }
+
+    #[test]
+    fn uid_bytes() {
+        let uid = RdmUid::from_bytes([0x7f, 0xf0, 0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(uid.to_string(), "7FF0:12345678");
+        assert_eq!(uid.to_bytes(), [0x7f, 0xf0, 0x12, 0x34, 0x56, 0x78]);
+    }
+
+    #[test]
+    fn patch_states() {
+        let universe = FixtureUniverse {
+            id: UniverseId(1),
+            channels: std::array::from_fn(|_| None),
+            fixtures: vec![
+                fixture("Spot 575", 0, 16),
+                fixture("LED PAR", 16, 7),
+                fixture("Wash", 32, 10),
+                fixture("Strobe", 100, 2),
+            ],
+        };
+        let outputs = HashMap::from([(ArtNetPortAddress::default(), UniverseId(1))]);
+        let mut elsewhere = device(5, "", 1, 1);
+        elsewhere.port_address.universe = 3;
+        let devices = [
+            device(1, "Spot-575 Pro", 1, 16),
+            device(2, "LED Par", 17, 8),
+            device(3, "Scanner", 33, 10),
+            device(4, "Wash", 200, 10),
+            elsewhere,
+        ];
+
+        let report = check_patch(&devices, &outputs, &[universe]);
+        let states = report
+            .devices
+            .iter()
+            .map(|c| c.state.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            states,
+            vec![
+                RdmPatchState::Matched,
+                RdmPatchState::FootprintMismatch { patched: 7 },
+                RdmPatchState::ModelMismatch,
+                RdmPatchState::Unpatched,
+                RdmPatchState::NoUniverse,
+            ]
+        );
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].address, 101);
+    }
+}
@@ -0,0 +1,242 @@
+//! A compact file format for timestamped universe frames.
+//!
+//! A recording starts with [`MAGIC`] and a version byte, followed by one record per changed frame:
+//!
+//! | Field    | Size        | Content                                           |
+//! |----------|-------------|---------------------------------------------------|
+//! | time     | 4           | Milliseconds since the start of the recording, LE |
+//! | universe | 2           | The [`UniverseId`], LE                            |
+//! | kind     | 1           | 0 for a full frame, 1 for a delta                 |
+//! | data     | 512 / 2 + n | All channels, or a count followed by n changes    |
+//!
+//! A change is the channel index as u16 LE followed by the new value. The first record of every universe is always
+//! full, later ones are deltas against the previous frame of the universe whenever that is smaller.
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+
+use crate::patched::UniverseId;
+use crate::universe::UNIVERSE_SIZE;
+
+pub const MAGIC: &[u8; 4] = b"MLCR";
+pub const VERSION: u8 = 1;
+/// The file extension recordings are saved with
+pub const EXTENSION: &str = "mlcrec";
+
+const KIND_FULL: u8 = 0;
+const KIND_DELTA: u8 = 1;
+
+/// The values of one universe at a point in time of a recording
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFrame {
+    /// The time since the start of the recording
+    pub time: Duration,
+    pub universe: UniverseId,
+    pub values: [u8; UNIVERSE_SIZE],
+}
+
+/// Plays a recording back into the universes it was recorded from
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, JsonSchema)]
+pub struct ReplayRequest {
+    /// The name of the recording, without extension
+    pub file: String,
+    /// Starts over at the end of the recording until the replay is stopped
+    #[serde(default)]
+    pub looped: bool,
+}
+
+/// Writes frames to a recording, frames that did not change are skipped
+pub struct RecordingWriter<W: Write> {
+    writer: W,
+    last: HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    /// Writes the header, the recording starts at time 0
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        Ok(Self {
+            writer,
+            last: HashMap::new(),
+        })
+    }
+
+    /// Writes the frame if the values of the universe changed since the last frame written for it
+    pub fn write(
+        &mut self,
+        time: Duration,
+        universe: UniverseId,
+        values: &[u8; UNIVERSE_SIZE],
+    ) -> io::Result<()> {
+        let changes = match self.last.get(&universe) {
+            Some(last) => (0..UNIVERSE_SIZE)
+                .filter(|i| last[*i] != values[*i])
+                .collect::<Vec<_>>(),
+            None => (0..UNIVERSE_SIZE).collect(),
+        };
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut record = Vec::with_capacity(7 + UNIVERSE_SIZE);
+        record.extend_from_slice(&(time.as_millis() as u32).to_le_bytes());
+        record.extend_from_slice(&universe.0.to_le_bytes());
+        if 2 + changes.len() * 3 < UNIVERSE_SIZE {
+            record.push(KIND_DELTA);
+            record.extend_from_slice(&(changes.len() as u16).to_le_bytes());
+            for i in changes {
+                record.extend_from_slice(&(i as u16).to_le_bytes());
+                record.push(values[i]);
+            }
+        } else {
+            record.push(KIND_FULL);
+            record.extend_from_slice(values);
+        }
+
+        self.writer.write_all(&record)?;
+        self.last.insert(universe, *values);
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads the frames of a recording in the order they were recorded
+pub struct RecordingReader<R: Read> {
+    reader: R,
+    last: HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Checks the header of the recording
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not an MLC recording",
+            ));
+        }
+        if header[4] != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported recording version {}", header[4]),
+            ));
+        }
+
+        Ok(Self {
+            reader,
+            last: HashMap::new(),
+        })
+    }
+
+    /// The next frame, `None` at the end of the recording
+    pub fn next_frame(&mut self) -> io::Result<Option<RecordedFrame>> {
+        let mut head = [0; 7];
+        match self.reader.read_exact(&mut head) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let time =
+            Duration::from_millis(u32::from_le_bytes([head[0], head[1], head[2], head[3]]) as u64);
+        let universe = UniverseId(u16::from_le_bytes([head[4], head[5]]));
+
+        let values = match head[6] {
+            KIND_FULL => {
+                let mut values = [0; UNIVERSE_SIZE];
+                self.reader.read_exact(&mut values)?;
+                values
+            }
+            KIND_DELTA => {
+                let mut values = *self.last.get(&universe).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Delta without a full frame for universe {}", universe.0),
+                    )
+                })?;
+                let mut count = [0; 2];
+                self.reader.read_exact(&mut count)?;
+                for _ in 0..u16::from_le_bytes(count) {
+                    let mut change = [0; 3];
+                    self.reader.read_exact(&mut change)?;
+                    let index = u16::from_le_bytes([change[0], change[1]]) as usize;
+                    if let Some(v) = values.get_mut(index) {
+                        *v = change[2];
+                    }
+                }
+                values
+            }
+            kind => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown record kind {kind}"),
+                ))
+            }
+        };
+
+        self.last.insert(universe, values);
+        Ok(Some(RecordedFrame {
+            time,
+            universe,
+            values,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = io::Result<RecordedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_frame().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut a = [0; UNIVERSE_SIZE];
+        let b = [7; UNIVERSE_SIZE];
+
+        let mut writer = RecordingWriter::new(vec![]).unwrap();
+        writer.write(Duration::ZERO, UniverseId(1), &a).unwrap();
+        writer.write(Duration::ZERO, UniverseId(2), &b).unwrap();
+        // Unchanged, skipped
+        writer
+            .write(Duration::from_millis(25), UniverseId(1), &a)
+            .unwrap();
+        a[10] = 255;
+        writer
+            .write(Duration::from_millis(50), UniverseId(1), &a)
+            .unwrap();
+        let data = writer.writer;
+
+        // Header, two full records and one delta with a single change
+        assert_eq!(data.len(), 5 + 2 * (7 + UNIVERSE_SIZE) + 7 + 2 + 3);
+
+        let frames = RecordingReader::new(data.as_slice())
+            .unwrap()
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].universe, UniverseId(2));
+        assert_eq!(frames[1].values, b);
+        assert_eq!(frames[2].time, Duration::from_millis(50));
+        assert_eq!(frames[2].values, a);
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(RecordingReader::new(b"{\"a\": 1}".as_slice()).is_err());
+    }
+}
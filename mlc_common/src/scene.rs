@@ -0,0 +1,240 @@
+//! Scenes are snapshots of the output of universes or fixtures that can be recalled with a fade.
+//!
+//! The channels of a scene are grouped into tiles. Channels a fixture combines into a 16 or 24 bit value form one
+//! tile, so fades interpolate the combined value and move smoothly. Every other channel is a tile of its own.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+
+use crate::patched::{FixtureId, UniverseAddress, UniverseId};
+use crate::universe::{FixtureUniverse, UNIVERSE_SIZE};
+
+pub type SceneId = uuid::Uuid;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct Scene {
+    pub id: SceneId,
+    pub name: String,
+    pub tiles: Vec<SceneTile>,
+}
+
+/// Channels of a universe faded as one value
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct SceneTile {
+    pub universe: UniverseId,
+    /// Coarse first
+    pub channels: Vec<UniverseAddress>,
+    pub values: Vec<u8>,
+}
+
+impl SceneTile {
+    fn value(&self) -> u64 {
+        self.values.iter().fold(0, |v, b| (v << 8) | *b as u64)
+    }
+
+    fn with_value(&self, mut value: u64) -> SceneTile {
+        let mut values = vec![0; self.values.len()];
+        for v in values.iter_mut().rev() {
+            *v = (value & 0xff) as u8;
+            value >>= 8;
+        }
+        SceneTile {
+            universe: self.universe,
+            channels: self.channels.clone(),
+            values,
+        }
+    }
+
+    fn same_channels(&self, other: &SceneTile) -> bool {
+        self.universe == other.universe && self.channels == other.channels
+    }
+}
+
+/// Captures the current output into a new scene
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct SceneCapture {
+    pub name: String,
+    /// Universes captured with all of their channels
+    #[serde(default)]
+    pub universes: Vec<UniverseId>,
+    /// Fixtures captured with their channels
+    #[serde(default)]
+    pub fixtures: Vec<FixtureId>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct SceneRecall {
+    pub scene: SceneId,
+    /// The fade time in seconds
+    #[serde(default)]
+    pub fade: f64,
+}
+
+/// Shows the mix of two scenes, as set by a manual crossfader
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Clone, JsonSchema)]
+pub struct SceneCrossfade {
+    pub from: SceneId,
+    pub to: SceneId,
+    /// 0 shows `from`, 1 shows `to`
+    pub position: f64,
+}
+
+/// The tiles of the current output of the selected universes and fixtures
+pub fn capture(
+    request: &SceneCapture,
+    universes: &[FixtureUniverse],
+    current: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+) -> Result<Vec<SceneTile>, String> {
+    let mut selected: HashMap<UniverseId, [bool; UNIVERSE_SIZE]> = HashMap::new();
+    for u in &request.universes {
+        if !universes.iter().any(|fu| fu.id == *u) {
+            return Err(format!("Universe {} does not exist", u.0));
+        }
+        selected.insert(*u, [true; UNIVERSE_SIZE]);
+    }
+    for id in &request.fixtures {
+        let (universe, f) = universes
+            .iter()
+            .find_map(|u| u.fixtures.iter().find(|f| f.id == *id).map(|f| (u.id, f)))
+            .ok_or_else(|| format!("Fixture {id} is not patched"))?;
+        let channels = selected.entry(universe).or_insert([false; UNIVERSE_SIZE]);
+        let start = f.start_channel.i();
+        let end = (start + f.num_channels as usize).min(UNIVERSE_SIZE);
+        channels[start..end].fill(true);
+    }
+    if selected.is_empty() {
+        return Err("Nothing selected to capture".to_string());
+    }
+
+    let mut ids = selected.keys().copied().collect::<Vec<_>>();
+    ids.sort();
+    let mut tiles = vec![];
+    for universe in ids {
+        let channels = &selected[&universe];
+        let values = current
+            .get(&universe)
+            .ok_or_else(|| format!("Universe {} has no output", universe.0))?;
+
+        // Fine channels are captured with their coarse channel
+        let mut taken = [false; UNIVERSE_SIZE];
+        let fixture_tiles = universes
+            .iter()
+            .filter(|u| u.id == universe)
+            .flat_map(|u| &u.fixtures)
+            .flat_map(|f| &f.features)
+            .flat_map(|f| f.tiles())
+            .map(|t| t.faders())
+            .filter(|faders| faders.len() > 1 && faders.iter().all(|f| f.universe == universe));
+        for faders in fixture_tiles {
+            let channels_of_tile = faders.iter().map(|f| f.address).collect::<Vec<_>>();
+            if !channels_of_tile
+                .iter()
+                .all(|c| channels[c.i()] && !taken[c.i()])
+            {
+                continue;
+            }
+            for c in &channels_of_tile {
+                taken[c.i()] = true;
+            }
+            tiles.push(SceneTile {
+                universe,
+                values: channels_of_tile.iter().map(|c| values[c.i()]).collect(),
+                channels: channels_of_tile,
+            });
+        }
+
+        for c in (0..UNIVERSE_SIZE).filter(|c| channels[*c] && !taken[*c]) {
+            tiles.push(SceneTile {
+                universe,
+                channels: vec![UniverseAddress::from(c)],
+                values: vec![values[c]],
+            });
+        }
+    }
+
+    Ok(tiles)
+}
+
+/// The tiles of `to` faded from `from` by `t`, from 0 to 1.
+///
+/// Tiles only one of the scenes holds keep their value.
+pub fn fade(from: &[SceneTile], to: &[SceneTile], t: f64) -> Vec<SceneTile> {
+    let t = t.clamp(0.0, 1.0);
+    let mut tiles = to
+        .iter()
+        .map(|end| match from.iter().find(|s| s.same_channels(end)) {
+            Some(start) => {
+                let (a, b) = (start.value() as f64, end.value() as f64);
+                end.with_value((a + (b - a) * t).round() as u64)
+            }
+            None => end.clone(),
+        })
+        .collect::<Vec<_>>();
+    tiles.extend(
+        from.iter()
+            .filter(|s| !to.iter().any(|e| e.same_channels(s)))
+            .cloned(),
+    );
+    tiles
+}
+
+/// The tiles with the values of the output, to fade from
+pub fn current_tiles(
+    tiles: &[SceneTile],
+    current: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+) -> Vec<SceneTile> {
+    tiles
+        .iter()
+        .filter_map(|t| {
+            let values = current.get(&t.universe)?;
+            Some(SceneTile {
+                universe: t.universe,
+                channels: t.channels.clone(),
+                values: t.channels.iter().map(|c| values[c.i()]).collect(),
+            })
+        })
+        .collect()
+}
+
+/// The values of the tiles by universe, `None` for channels no tile holds
+pub fn frames(tiles: &[SceneTile]) -> HashMap<UniverseId, [Option<u8>; UNIVERSE_SIZE]> {
+    let mut frames = HashMap::new();
+    for t in tiles {
+        let frame = frames.entry(t.universe).or_insert([None; UNIVERSE_SIZE]);
+        for (c, v) in t.channels.iter().zip(&t.values) {
+            frame[c.i()] = Some(*v);
+        }
+    }
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tile(channels: &[u16], values: &[u8]) -> SceneTile {
+        SceneTile {
+            universe: UniverseId(1),
+            channels: channels.iter().map(|c| UniverseAddress::from(*c)).collect(),
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn fades_fine_channels_as_one_value() {
+        let from = [tile(&[0, 1], &[0, 0]), tile(&[2], &[100]), tile(&[3], &[7])];
+        let to = [tile(&[0, 1], &[1, 0]), tile(&[2], &[200])];
+
+        let half = fade(&from, &to, 0.5);
+        // 0x0000 to 0x0100 passes 0x0080 instead of jumping
+        assert_eq!(half[0].values, vec![0, 128]);
+        assert_eq!(half[1].values, vec![150]);
+        // Only in `from`, so it is held
+        assert_eq!(half[2].values, vec![7]);
+
+        assert_eq!(fade(&from, &to, 1.0)[..2], to);
+        let frame = &frames(&half)[&UniverseId(1)];
+        assert_eq!(&frame[..5], &[Some(0), Some(128), Some(150), Some(7), None]);
+    }
+}
@@ -2,13 +2,17 @@ use dioxus::prelude::*;
 use futures::{SinkExt, StreamExt};
 use gloo_net::websocket::Message;
 
+use mlc_common::highlight::HighlightRequest;
 use mlc_common::patched::feature::{FeatureSetRequest, FixtureFeatureType};
-use mlc_common::patched::PatchedFixture;
+use mlc_common::patched::{FixtureId, PatchedFixture};
 use mlc_common::utils::BoundedValue;
+use mlc_common::Info;
 
-use crate::utils::Overlay;
+use crate::utils::{Loading, Overlay};
 use crate::{icons, utils};
 
+use super::highlight;
+
 #[component]
 pub fn FixtureTester(info: PatchedFixture, onclose: EventHandler) -> Element {
     let features = info.features.iter().map(|f| f.name()).collect::<Vec<_>>();
@@ -32,6 +36,46 @@ pub fn FixtureTester(info: PatchedFixture, onclose: EventHandler) -> Element {
             }
         }
     });
+    // The testers start from the current values of the features
+    let mut current: Signal<Option<Vec<FeatureSetRequest>>> = use_signal(|| None);
+    let _readback = use_coroutine(|_: UnboundedReceiver<()>| {
+        let fix_id = info.id;
+        async move {
+            let ws = utils::ws(&format!("/runtime/feature/{}/values", fix_id)).await;
+
+            if let Ok(mut ws) = ws {
+                while let Some(Ok(msg)) = ws.next().await {
+                    let values = match msg {
+                        Message::Text(t) => serde_json::from_str(&t).ok(),
+                        Message::Bytes(b) => serde_json::from_slice(&b).ok(),
+                    };
+                    if let Some(values) = values {
+                        current.set(Some(values));
+                    }
+                }
+            } else {
+                log::error!("Failed to open feature values websocket for id {}", fix_id);
+            }
+        }
+    });
+    let id = info.id;
+    let mut highlighted = use_resource(|| utils::fetch::<Option<FixtureId>>("/runtime/highlight"));
+    let located = move || highlighted.read().as_ref().and_then(|r| r.as_ref().ok().copied()).flatten() == Some(id);
+    let info_signal = use_context::<Signal<Info>>();
+    use_effect(move || {
+        if info_signal() == Info::HighlightChanged {
+            highlighted.restart();
+        }
+    });
+
+    let initial = move |feature: FixtureFeatureType| {
+        current
+            .read()
+            .iter()
+            .flatten()
+            .find(|r| request_type(r) == Some(feature))
+            .cloned()
+    };
 
     rsx! {
         Overlay {
@@ -49,27 +93,48 @@ pub fn FixtureTester(info: PatchedFixture, onclose: EventHandler) -> Element {
                 span { class: "name", {info.name} }
                 " Id: "
                 span { class: "id", {info.id.to_string()} }
+                button {
+                    class: "locate",
+                    class: if located() { "sel" },
+                    onclick: move |_| {
+                        if located() {
+                            highlight(HighlightRequest::Off);
+                        } else {
+                            highlight(HighlightRequest::Fixture(id));
+                        }
+                    },
+                    "Locate"
+                }
             }
 
-            div { class: "features",
-                for feature in features {
-                    match feature {
-                        FixtureFeatureType::Dimmer => {rsx!{DimmerTester {
-                            updater: updater,
-                        }}}
-                        FixtureFeatureType::White => {rsx!{WhiteTester{
-                            updater: updater
-                        }}}
-                        FixtureFeatureType::Rgb => {rsx!{RgbTester{
-                            updater: updater
-                        }}}
-                        FixtureFeatureType::Rotation => {rsx!{"Rotation"}}
-                        FixtureFeatureType::PanTilt => {rsx!{PanTiltTester{
-                            updater: updater
-                        }}}
-                        FixtureFeatureType::Amber => {rsx!{AmberTester{
-                            updater: updater
-                        }}}
+            if current.read().is_none() {
+                Loading {}
+            } else {
+                div { class: "features",
+                    for feature in features {
+                        match feature {
+                            FixtureFeatureType::Dimmer => {rsx!{DimmerTester {
+                                updater: updater,
+                                initial: initial(feature),
+                            }}}
+                            FixtureFeatureType::White => {rsx!{WhiteTester{
+                                updater: updater,
+                                initial: initial(feature),
+                            }}}
+                            FixtureFeatureType::Rgb => {rsx!{RgbTester{
+                                updater: updater,
+                                initial: initial(feature),
+                            }}}
+                            FixtureFeatureType::Rotation => {rsx!{"Rotation"}}
+                            FixtureFeatureType::PanTilt => {rsx!{PanTiltTester{
+                                updater: updater,
+                                initial: initial(feature),
+                            }}}
+                            FixtureFeatureType::Amber => {rsx!{AmberTester{
+                                updater: updater,
+                                initial: initial(feature),
+                            }}}
+                        }
                     }
                 }
             }
@@ -78,12 +143,16 @@ pub fn FixtureTester(info: PatchedFixture, onclose: EventHandler) -> Element {
 }
 
 #[component]
-fn DimmerTester(updater: Coroutine<FeatureSetRequest>) -> Element {
+fn DimmerTester(updater: Coroutine<FeatureSetRequest>, initial: Option<FeatureSetRequest>) -> Element {
+    let initial = match initial {
+        Some(FeatureSetRequest::Dimmer { value }) => value.take() as f32,
+        _ => 0.0,
+    };
     rsx! {
         div { class: "feature-tester dimmer",
             h3 { "Dimmer" }
             utils::Slider {
-                initial: 0.0,
+                initial: initial,
                 onchange: move |v| {
                     updater
                         .send(FeatureSetRequest::Dimmer {
@@ -96,12 +165,16 @@ fn DimmerTester(updater: Coroutine<FeatureSetRequest>) -> Element {
 }
 
 #[component]
-fn WhiteTester(updater: Coroutine<FeatureSetRequest>) -> Element {
+fn WhiteTester(updater: Coroutine<FeatureSetRequest>, initial: Option<FeatureSetRequest>) -> Element {
+    let initial = match initial {
+        Some(FeatureSetRequest::White { value }) => value.take() as f32,
+        _ => 0.0,
+    };
     rsx! {
         div { class: "feature-tester dimmer",
             h3 { "White" }
             utils::Slider {
-                initial: 0.0,
+                initial: initial,
                 onchange: move |v| {
                     updater
                         .send(FeatureSetRequest::White {
@@ -114,12 +187,16 @@ fn WhiteTester(updater: Coroutine<FeatureSetRequest>) -> Element {
 }
 
 #[component]
-fn AmberTester(updater: Coroutine<FeatureSetRequest>) -> Element {
+fn AmberTester(updater: Coroutine<FeatureSetRequest>, initial: Option<FeatureSetRequest>) -> Element {
+    let initial = match initial {
+        Some(FeatureSetRequest::Amber { value }) => value.take() as f32,
+        _ => 0.0,
+    };
     rsx! {
         div { class: "feature-tester dimmer",
             h3 { "Amber" }
             utils::Slider {
-                initial: 0.0,
+                initial: initial,
                 onchange: move |v| {
                     updater
                         .send(FeatureSetRequest::White {
@@ -132,13 +209,19 @@ fn AmberTester(updater: Coroutine<FeatureSetRequest>) -> Element {
 }
 
 #[component]
-fn RgbTester(updater: Coroutine<FeatureSetRequest>) -> Element {
+fn RgbTester(updater: Coroutine<FeatureSetRequest>, initial: Option<FeatureSetRequest>) -> Element {
+    let initial = match initial {
+        Some(FeatureSetRequest::Rgb { red, green, blue }) => {
+            (red.take() as f32, green.take() as f32, blue.take() as f32)
+        }
+        _ => (0.0, 0.0, 0.0),
+    };
     rsx! {
         div { class: "feature-tester rgb",
             h3 { "Rgb" }
 
             utils::RgbWidget {
-                initial: (0.42, 0.420, 0.69),
+                initial: initial,
                 onchange: move |(r, g, b)| {
                     updater
                         .send(FeatureSetRequest::Rgb {
@@ -153,13 +236,17 @@ fn RgbTester(updater: Coroutine<FeatureSetRequest>) -> Element {
 }
 
 #[component]
-fn PanTiltTester(updater: Coroutine<FeatureSetRequest>) -> Element {
+fn PanTiltTester(updater: Coroutine<FeatureSetRequest>, initial: Option<FeatureSetRequest>) -> Element {
+    let initial = match initial {
+        Some(FeatureSetRequest::PanTilt { pan, tilt }) => (pan.take() as f32, tilt.take() as f32),
+        _ => (0.5, 0.5),
+    };
     rsx! {
         div { class: "feature-tester pan-tilt",
             h3 { "Pan/Tilt" }
 
             utils::PanTiltWidget {
-                initial: (0.5, 0.5),
+                initial: initial,
                 onchange: move |(p, t)| {
                     updater
                         .send(FeatureSetRequest::PanTilt {
@@ -171,3 +258,15 @@ fn PanTiltTester(updater: Coroutine<FeatureSetRequest>) -> Element {
         }
     }
 }
+
+fn request_type(request: &FeatureSetRequest) -> Option<FixtureFeatureType> {
+    match request {
+        FeatureSetRequest::Dimmer { .. } => Some(FixtureFeatureType::Dimmer),
+        FeatureSetRequest::Rgb { .. } => Some(FixtureFeatureType::Rgb),
+        FeatureSetRequest::White { .. } => Some(FixtureFeatureType::White),
+        FeatureSetRequest::Amber { .. } => Some(FixtureFeatureType::Amber),
+        FeatureSetRequest::Rotation { .. } => Some(FixtureFeatureType::Rotation),
+        FeatureSetRequest::PanTilt { .. } => Some(FixtureFeatureType::PanTilt),
+        FeatureSetRequest::GetAvailableFeatures => None,
+    }
+}
@@ -1,18 +1,28 @@
 use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::ops::Deref;
 use std::str::FromStr;
 
 use dioxus::html::input_data::MouseButton;
 use dioxus::prelude::*;
+use dioxus::web::WebEventExt;
 use futures::{SinkExt, StreamExt};
 use gloo_net::websocket::Message;
 
 use fixture_tester::FixtureTester;
-use mlc_common::endpoints::{EPConfigItem, EndPointConfig, Speed};
-use mlc_common::patched::{PatchedFixture, UniverseAddress, UniverseId};
+use mlc_common::endpoints::{
+    ArtNetNode, ArtNetPortAddress, ChannelMap, ChannelPatch, EPConfigItem, EndPointConfig, EndpointDevice, EndpointState, EndpointStatus,
+    FrameClock, MergeMode, OscConfig, PixelMapping, SacnSource, Speed,
+};
+use mlc_common::fader_stream::{Decoded, FaderDecoder, FaderFrame, FrameValues};
+use mlc_common::group::{FixtureGroup, GroupId};
+use mlc_common::highlight::HighlightRequest;
+use mlc_common::parked::{ParkRequest, ParkedChannel};
+use mlc_common::patched::{FixtureId, PatchedFixture, UniverseAddress, UniverseId};
+use mlc_common::rdm::{RdmAddressRequest, RdmDevice, RdmPatchCheck, RdmPatchReport, RdmPatchState};
 use mlc_common::universe::FixtureUniverse;
 use mlc_common::{
-    FaderUpdateRequest, FixtureInfo, Info, PatchResult, ProjectDefinition, ProjectSettings,
+    ClientId, FaderUpdateRequest, FixtureInfo, Info, PatchResult, ProjectDefinition, ProjectSettings,
     RuntimeUpdate,
 };
 
@@ -172,26 +182,39 @@ fn FaderPanel() -> Element {
             vec![]
         }
     });
+    let mut parked = use_resource(|| async move {
+        utils::fetch::<Vec<ParkedChannel>>("/runtime/parked")
+            .await
+            .unwrap_or_default()
+    });
     let info = use_context::<Signal<Info>>();
-    use_effect(move || {
-        if info() == Info::UniversesUpdated {
-            universes.restart();
-        }
+    use_effect(move || match info() {
+        Info::UniversesUpdated => universes.restart(),
+        Info::ParkedChanged => parked.restart(),
+        _ => {}
     });
 
     let mut current_values = use_signal(|| [0_u8; 512]);
+    let is_parked = move |i: usize| {
+        parked
+            .read()
+            .as_ref()
+            .is_some_and(|p| p.iter().any(|p| p.universe.0 == current_universe() && p.channel.i() == i))
+    };
 
     let mut started = use_signal(|| false);
+    let mut client: Signal<Option<ClientId>> = use_signal(|| None);
     let get = use_coroutine(|mut rx: UnboundedReceiver<u16>| async move {
         if started() {
             return;
         }
         started.set(true);
 
-        let ws_o = utils::ws("/runtime/fader-values/get").await;
+        let ws_o = utils::ws("/runtime/fader-values/get?binary=true&echo=false").await;
 
         if let Ok(get_ws) = ws_o {
             let mut get_ws = get_ws.fuse();
+            let mut decoder = FaderDecoder::default();
             loop {
                 futures::select! {
                     msg = rx.next() => {
@@ -200,14 +223,13 @@ fn FaderPanel() -> Element {
                         }
                     },
                     msg = get_ws.next() => {
-                        let d = match msg {
-                            Some(Ok(msg)) => {
-                                match msg {
-                                    Message::Text(t) => serde_json::from_str::<RuntimeUpdate>(&t).ok(),
-                                    Message::Bytes(b) => serde_json::from_str::<RuntimeUpdate>(
-                                        &String::from_utf8(b).unwrap(),
-                                    ).ok(),
+                        let frame = match msg {
+                            Some(Ok(Message::Bytes(b))) => ciborium::from_reader::<FaderFrame, _>(b.as_slice()).ok(),
+                            Some(Ok(Message::Text(t))) => {
+                                if let Ok(RuntimeUpdate::Connected { client: id }) = serde_json::from_str(&t) {
+                                    client.set(Some(id));
                                 }
+                                None
                             }
                             Some(Err(e)) => {
                                 let e: gloo_net::websocket::WebSocketError = e;
@@ -226,38 +248,35 @@ fn FaderPanel() -> Element {
                             }
                         };
 
-                        if let Some(update) = d {
-                            match update {
-                                RuntimeUpdate::ValueUpdated {
-                                    universe,
-                                    channel_index,
-                                    value,
-                                } => {
-                                    if current_universe.read().deref() == &universe.0 {
-                                        current_values.with_mut(|g| g[channel_index] = value);
-                                    }
-                                }
-                                RuntimeUpdate::ValuesUpdated {
-                                    universes,
-                                    channel_indexes,
-                                    values,
-                                } => {
-                                    current_values.with_mut(|g| {
-                                        for (i, index) in channel_indexes.iter().enumerate() {
-                                            if current_universe.read().deref() == &universes[i].0 {
-                                                g[*index] = values[i];
+                        if let Some(frame) = frame {
+                            let changes = match &frame.values {
+                                FrameValues::Delta(changes) => Some(changes.clone()),
+                                FrameValues::Key(_) => None,
+                            };
+                            match decoder.apply(frame) {
+                                Decoded::Updated(universe) if current_universe() == universe.0 => {
+                                    // Only the sent channels, the ones moved here are not echoed back
+                                    match changes {
+                                        Some(changes) => current_values.with_mut(|g| {
+                                            for (c, v) in changes {
+                                                if let Some(value) = g.get_mut(c as usize) {
+                                                    *value = v;
+                                                }
+                                            }
+                                        }),
+                                        None => {
+                                            if let Some(values) = decoder.values(&universe) {
+                                                current_values.set(*values);
                                             }
                                         }
-                                    });
-                                }
-                                RuntimeUpdate::Universe {
-                                    universe, values, ..
-                                } => {
-                                    if current_universe() == universe.0 {
-                                        current_values.set(values);
                                     }
                                 }
-                            };
+                                // Request a keyframe instead of waiting for the next periodic one
+                                Decoded::Missed(universe) => {
+                                    let _ = get_ws.send(Message::Text(universe.0.to_string())).await;
+                                }
+                                Decoded::Updated(_) | Decoded::Stale(_) => {}
+                            }
                         };
                     }
                 }
@@ -267,9 +286,20 @@ fn FaderPanel() -> Element {
         }
     });
     let set = use_coroutine(|mut rx: UnboundedReceiver<FaderUpdateRequest>| async move {
-        let ws = utils::ws("/runtime/fader-values/set").await;
+        // Connected on the first change, so the changes are authored by the client of the get socket
+        let first = rx.next().await;
+        let url = match client() {
+            Some(id) => format!("/runtime/fader-values/set?client={id}"),
+            None => "/runtime/fader-values/set".to_string(),
+        };
+        let ws = utils::ws(&url).await;
 
         if let Ok(mut ws) = ws {
+            if let Some(r) = first {
+                let _ = ws
+                    .send(Message::Text(serde_json::to_string(&r).unwrap()))
+                    .await;
+            }
             loop {
                 let m = rx.next().await;
                 if let Some(r) = m {
@@ -302,6 +332,30 @@ fn FaderPanel() -> Element {
                     }
                     None => {rsx!(Loading{})}
                 }
+                button {
+                    class: "release",
+                    title: "Release all faders and fixture features, so effects and defaults show again",
+                    onclick: move |_| {
+                        async move {
+                            if let Err(e) = utils::fetch_post::<String, _>("/runtime/programmer/release", ()).await {
+                                log::error!("Releasing the programmer failed: {e:?}");
+                            }
+                        }
+                    },
+                    "Release"
+                }
+                button {
+                    class: "release",
+                    title: "Unpark all parked channels",
+                    onclick: move |_| {
+                        async move {
+                            if let Err(e) = utils::fetch_post::<Vec<ParkedChannel>, _>("/runtime/parked", ParkRequest::UnparkAll).await {
+                                log::error!("Unparking failed: {e:?}");
+                            }
+                        }
+                    },
+                    "Unpark"
+                }
             }
             div { class: "faders",
                 {(0..512).map(|i| {
@@ -309,13 +363,29 @@ fn FaderPanel() -> Element {
                         Fader{
                         value: current_values.read()[i],
                         id: make_three_digit(i as u16),
+                        parked: is_parked(i),
                         onchange: move |v| {
+                                current_values.with_mut(|g| g[i] = v);
                                 set.send(FaderUpdateRequest{
                                     universe: UniverseId(*current_universe.read().deref()),
                                     channel: UniverseAddress::create(i as u16).expect("Must be"),
                                     value: v
                                 });
                         },
+                        onpark: move |_| {
+                            spawn(async move {
+                                let universe = UniverseId(current_universe());
+                                let channel = UniverseAddress::create(i as u16).expect("Must be");
+                                let request = if is_parked(i) {
+                                    ParkRequest::UnparkChannel { universe, channel }
+                                } else {
+                                    ParkRequest::Channel { universe, channel, value: current_values.read()[i] }
+                                };
+                                if let Err(e) = utils::fetch_post::<Vec<ParkedChannel>, _>("/runtime/parked", request).await {
+                                    log::error!("Parking failed: {e:?}");
+                                }
+                            });
+                        },
                     }
                     }
                 })}
@@ -324,14 +394,22 @@ fn FaderPanel() -> Element {
     }
 }
 
+/// A vertical fader, `onpark` shows a button to park the channel of the fader
 #[component]
-pub fn Fader(value: u8, id: String, onchange: EventHandler<u8>) -> Element {
+pub fn Fader(
+    value: u8,
+    id: String,
+    onchange: EventHandler<u8>,
+    #[props(default)] parked: bool,
+    onpark: Option<EventHandler<()>>,
+) -> Element {
     let mut val = use_signal(|| value);
     use_effect(use_reactive((&value,), move |(v,)| val.set(v)));
 
     let mut size_e = use_signal(|| None);
     rsx! {
         div { class: "fader-container",
+            class: if parked { "parked" } else { "" },
             div { class: "name", {id} }
 
             div {
@@ -379,6 +457,15 @@ pub fn Fader(value: u8, id: String, onchange: EventHandler<u8>) -> Element {
             }
 
             div { class: "value", {make_three_digit(val() as u16)} }
+
+            if let Some(onpark) = onpark {
+                button {
+                    class: "park",
+                    title: if parked { "Unpark" } else { "Park at the current value" },
+                    onclick: move |_| onpark.call(()),
+                    "P"
+                }
+            }
         }
     }
 }
@@ -620,6 +707,11 @@ fn UniverseExplorer() -> Element {
         }
     });
 
+    let mut highlighted = use_resource(|| utils::fetch::<Option<FixtureId>>("/runtime/highlight"));
+    let located = move || highlighted.read().as_ref().and_then(|r| r.as_ref().ok().copied()).flatten();
+    let mut groups = use_resource(|| utils::fetch::<Vec<FixtureGroup>>("/data/groups"));
+    let mut locate_group = use_signal::<Option<GroupId>>(|| None);
+
     let info = use_context::<Signal<Info>>();
     use_effect(move || match info() {
         Info::UniversePatchChanged(id) => {
@@ -630,6 +722,12 @@ fn UniverseExplorer() -> Element {
         Info::UniversesUpdated => {
             universes.restart();
         }
+        Info::HighlightChanged => {
+            highlighted.restart();
+        }
+        Info::GroupsChanged => {
+            groups.restart();
+        }
         _ => {}
     });
 
@@ -657,6 +755,42 @@ fn UniverseExplorer() -> Element {
                                 {id.0.to_string()}
                             }
                         }
+                        div { class: "locate",
+                            select {
+                                onchange: move |e| locate_group.set(serde_json::from_str(&e.value()).ok()),
+                                option { value: "null", "Locate in group" }
+                                for group in groups.read().as_ref().and_then(|r| r.as_ref().ok()).cloned().unwrap_or_default() {
+                                    option {
+                                        value: serde_json::to_string(&group.id).unwrap(),
+                                        selected: locate_group() == Some(group.id),
+                                        {group.name}
+                                    }
+                                }
+                            }
+                            button {
+                                disabled: locate_group().is_none(),
+                                onclick: move |_| {
+                                    if let Some(group) = locate_group() {
+                                        highlight(HighlightRequest::Previous(group));
+                                    }
+                                },
+                                "Previous"
+                            }
+                            button {
+                                disabled: locate_group().is_none(),
+                                onclick: move |_| {
+                                    if let Some(group) = locate_group() {
+                                        highlight(HighlightRequest::Next(group));
+                                    }
+                                },
+                                "Next"
+                            }
+                            button {
+                                disabled: located().is_none(),
+                                onclick: move |_| highlight(HighlightRequest::Off),
+                                "Off"
+                            }
+                        }
                     }
                     match universe.value()() {
                         Some(Some(data)) => {
@@ -666,16 +800,23 @@ fn UniverseExplorer() -> Element {
                                     for (i, channel) in data.channels.iter().cloned().enumerate() {
                                         match channel {
                                             Some(c) => {
+                                                let fixture_id = data.fixtures[c.fixture_index].id;
                                                 rsx! {
                                                     div {
                                                         class: "patched-channel {channel_type(data.fixtures[c.fixture_index].num_channels as usize, c.channel_index)}",
+                                                        class: if located() == Some(fixture_id) { "located" },
                                                         title: data.fixtures[c.fixture_index].name.clone(),
                                                         onclick: move |e| {
                                                             if let Some(MouseButton::Primary) = e.trigger_button(){
                                                                 detail_fixture_id.set(Some(c.fixture_index))
                                                             }
-                                                            if let Some(MouseButton::Secondary) = e.trigger_button(){
-                                                                log::info!("Right Click");
+                                                        },
+                                                        oncontextmenu: move |e| {
+                                                            e.web_event().prevent_default();
+                                                            if located() == Some(fixture_id) {
+                                                                highlight(HighlightRequest::Off);
+                                                            } else {
+                                                                highlight(HighlightRequest::Fixture(fixture_id));
                                                             }
                                                         },
                                                         if c.channel_index == 0 {
@@ -717,6 +858,15 @@ fn UniverseExplorer() -> Element {
     }
 }
 
+/// Locates a fixture, steps through a group or ends the highlight
+fn highlight(request: HighlightRequest) {
+    spawn(async move {
+        if let Err(e) = utils::fetch_post::<Option<FixtureId>, _>("/runtime/highlight", request).await {
+            log::error!("Failed to highlight: {e:?}");
+        }
+    });
+}
+
 fn channel_type(amount: usize, i: usize) -> &'static str {
     if i == 0 {
         if amount == 1 {
@@ -930,9 +1080,15 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
     });
 
     let mut transformed_config = use_signal(|| None);
+    let mut sacn_source = use_signal(SacnSource::default);
+    let mut frame_clock = use_signal(FrameClock::default);
+    let mut osc_config = use_signal(OscConfig::default);
     use_effect(move || {
         let r = config().map(|c| {
             c.map(|(us, ep_config)| {
+                sacn_source.set(ep_config.sacn.clone());
+                frame_clock.set(ep_config.clock);
+                osc_config.set(ep_config.osc.clone());
                 us.iter()
                     .map(|u| (*u, ep_config.endpoints.get(u).cloned().unwrap_or(vec![])))
                     .collect::<Vec<_>>()
@@ -941,6 +1097,24 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
         transformed_config.set(r);
     });
 
+    let mut artnet_nodes = use_resource(|| async move {
+        utils::fetch::<Vec<ArtNetNode>>("/runtime/artnet/nodes")
+            .await
+            .map_err(|e| {
+                log::error!("Error fetching Art-Net nodes: {:?}", e);
+            })
+            .unwrap_or(vec![])
+    });
+
+    let mut endpoint_states = use_resource(|| async move {
+        utils::fetch::<Vec<EndpointStatus>>("/runtime/endpoints/status")
+            .await
+            .map_err(|e| {
+                log::error!("Error fetching endpoint states: {:?}", e);
+            })
+            .unwrap_or(vec![])
+    });
+
     let mut toaster = use_context::<Signal<Toaster>>();
 
     let info = use_context::<Signal<Info>>();
@@ -949,6 +1123,16 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
             config.restart();
         }
     });
+    use_effect(move || {
+        if info() == Info::ArtNetNodesChanged {
+            artnet_nodes.restart();
+        }
+    });
+    use_effect(move || {
+        if info() == Info::EndpointStatesChanged {
+            endpoint_states.restart();
+        }
+    });
 
     rsx! {
         utils::Overlay {
@@ -976,6 +1160,12 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                     for (i, ep) in eps.into_iter().enumerate() {
                                         div {
                                             class: "endpoint",
+                                            EndpointStateIndicator {
+                                                status: endpoint_states()
+                                                    .unwrap_or_default()
+                                                    .into_iter()
+                                                    .find(|s| s.universe == u && s.index == i)
+                                            },
                                             div {
                                                 class: "endpoint-type",
                                                 div {
@@ -988,10 +1178,7 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                             let c = w.as_mut().expect("").as_mut().expect("");
                                                             for (uid, conf) in c {
                                                                 if *uid == u {
-                                                                    conf[i] = EPConfigItem::Sacn {
-                                                                        universe: 1,
-                                                                        speed: Speed::Medium,
-                                                                    };
+                                                                    conf[i] = EPConfigItem::default_sacn();
                                                                 }
                                                             }
                                                         }
@@ -1023,15 +1210,15 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                 },
                                                 div {
                                                     class: "artnet",
-                                                    class: if matches!(&ep, EPConfigItem::ArtNet) {"sel"},
+                                                    class: if matches!(&ep, EPConfigItem::ArtNet{..}) {"sel"},
                                                     title: "ArtNet",
                                                     onclick: make_type_closure(ep.clone(), move |_, ep| {
-                                                        if !matches!(ep, EPConfigItem::ArtNet) {
+                                                        if !matches!(ep, EPConfigItem::ArtNet{..}) {
                                                             let mut w = transformed_config.write();
                                                             let c = w.as_mut().expect("").as_mut().expect("");
                                                             for (uid, conf) in c {
                                                                 if *uid == u {
-                                                                    conf[i] = EPConfigItem::ArtNet;
+                                                                    conf[i] = EPConfigItem::default_artnet();
                                                                 }
                                                             }
                                                         }
@@ -1054,6 +1241,7 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                                     conf[i] = EPConfigItem::Usb {
                                                                         port: "COM1".to_string(),
                                                                         speed: Speed::Medium,
+                                                                        channel_map: None,
                                                                     };
                                                                 }
                                                             }
@@ -1063,6 +1251,149 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                         width: "1rem",
                                                         height: "1rem"
                                                     },
+                                                },
+                                                div {
+                                                    class: "usb-pro",
+                                                    class: if matches!(&ep, EPConfigItem::UsbPro{..}) {"sel"},
+                                                    title: "Enttec USB Pro",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::UsbPro{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::UsbPro {
+                                                                        port: "COM1".to_string(),
+                                                                        channel_map: None,
+                                                                    };
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::Plug{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "sacn-input",
+                                                    class: if matches!(&ep, EPConfigItem::SacnInput{..}) {"sel"},
+                                                    title: "sACN Input",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::SacnInput{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::default_sacn_input();
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::Download{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "artnet-input",
+                                                    class: if matches!(&ep, EPConfigItem::ArtNetInput{..}) {"sel"},
+                                                    title: "Art-Net Input",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::ArtNetInput{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::default_artnet_input();
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::ArrowDown{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "artnet-timecode",
+                                                    class: if matches!(&ep, EPConfigItem::ArtNetTimecode) {"sel"},
+                                                    title: "Art-Net Timecode",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::ArtNetTimecode) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::ArtNetTimecode;
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::Clock{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "ddp",
+                                                    class: if matches!(&ep, EPConfigItem::Ddp{..}) {"sel"},
+                                                    title: "DDP",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::Ddp{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::default_ddp();
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::Sparkles{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "opc",
+                                                    class: if matches!(&ep, EPConfigItem::Opc{..}) {"sel"},
+                                                    title: "Open Pixel Control",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::Opc{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::default_opc();
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::Blocks{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
+                                                },
+                                                div {
+                                                    class: "recorder",
+                                                    class: if matches!(&ep, EPConfigItem::Recorder{..}) {"sel"},
+                                                    title: "Recorder",
+                                                    onclick: make_type_closure(ep.clone(), move |_, ep| {
+                                                        if !matches!(ep, EPConfigItem::Recorder{..}) {
+                                                            let mut w = transformed_config.write();
+                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                            for (uid, conf) in c {
+                                                                if *uid == u {
+                                                                    conf[i] = EPConfigItem::default_recorder();
+                                                                }
+                                                            }
+                                                        }
+                                                    }),
+                                                    icons::CircleDot{
+                                                        width: "1rem",
+                                                        height: "1rem"
+                                                    },
                                                 }
                                             },
                                             div {
@@ -1075,99 +1406,104 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                             }
                                                         }
                                                     }
-                                                    EPConfigItem::ArtNet => {
-                                                        rsx! {
-                                                            p {
-                                                                "ArtNet (no config needed)",
-                                                            }
-                                                        }
-                                                    }
-                                                    EPConfigItem::Sacn{ universe, speed } => {
+                                                    EPConfigItem::ArtNet{ port_address, ref unicast, broadcast, interface, .. } => {
                                                         rsx! {
                                                             p {
-                                                                "sACN",
+                                                                "ArtNet",
                                                             },
                                                             div {
                                                                 class: "property",
                                                                 p {
-                                                                    "Universe:",
+                                                                    "Net/Sub/Uni:",
                                                                 },
-                                                                input {
-                                                                    r#type: "number",
-                                                                    value: universe as i64,
-                                                                    min: 1,
-                                                                    oninput: move |e| {
-                                                                        let mut w = transformed_config.write();
-                                                                        let c = w.as_mut().expect("").as_mut().expect("");
-                                                                        for (uid, conf) in c {
-                                                                            if *uid == u {
-                                                                                let item = conf.get_mut(i).expect("");
-                                                                                if let EPConfigItem::Sacn{universe, ..} = item {
-                                                                                    *universe = u16::from_str(&e.value()).unwrap_or(1).max(1);
-                                                                                    needs_update();
+                                                                div {
+                                                                    class: "port-address",
+                                                                    for (part, value, max) in [(0, port_address.net, 127), (1, port_address.subnet, 15), (2, port_address.universe, 15)] {
+                                                                        input {
+                                                                            r#type: "number",
+                                                                            value: value as i64,
+                                                                            min: 0,
+                                                                            max: max as i64,
+                                                                            oninput: move |e| {
+                                                                                let mut w = transformed_config.write();
+                                                                                let c = w.as_mut().expect("").as_mut().expect("");
+                                                                                for (uid, conf) in c {
+                                                                                    if *uid == u {
+                                                                                        let item = conf.get_mut(i).expect("");
+                                                                                        if let EPConfigItem::ArtNet{port_address, ..} = item {
+                                                                                            let v = u8::from_str(&e.value()).unwrap_or(0).min(max);
+                                                                                            match part {
+                                                                                                0 => port_address.net = v,
+                                                                                                1 => port_address.subnet = v,
+                                                                                                _ => port_address.universe = v,
+                                                                                            }
+                                                                                        }
+                                                                                    }
                                                                                 }
-                                                                            }
+                                                                            },
                                                                         }
-                                                                    },
+                                                                    }
                                                                 }
                                                             },
                                                             div {
                                                                 class: "property",
                                                                 p {
-                                                                    "Speed:",
+                                                                    "Node:",
                                                                 },
                                                                 select {
-                                                                    value: format!("\"{:?}\"", speed),
+                                                                    value: "",
                                                                     onchange: move |e| {
-                                                                        let mut w = transformed_config.write();
-                                                                        let c = w.as_mut().expect("").as_mut().expect("");
-                                                                        for (uid, conf) in c {
-                                                                            if *uid == u {
-                                                                                let item = conf.get_mut(i).expect("");
-                                                                                if let EPConfigItem::Sacn{universe: _, speed} = item {
-                                                                                    *speed = serde_json::from_str(&e.value()).unwrap_or(Speed::Medium);
+                                                                        let pick = serde_json::from_str::<(Ipv4Addr, ArtNetPortAddress)>(&e.value()).ok();
+                                                                        if let Some((ip, address)) = pick {
+                                                                            let mut w = transformed_config.write();
+                                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                                            for (uid, conf) in c {
+                                                                                if *uid == u {
+                                                                                    let item = conf.get_mut(i).expect("");
+                                                                                    if let EPConfigItem::ArtNet{port_address, unicast, ..} = item {
+                                                                                        *port_address = address;
+                                                                                        if !unicast.contains(&ip) {
+                                                                                            unicast.push(ip);
+                                                                                        }
+                                                                                    }
                                                                                 }
                                                                             }
                                                                         }
                                                                     },
                                                                     option {
-                                                                        value: "\"Fast\"",
-                                                                        "Fast"
-                                                                    },
-                                                                    option {
-                                                                        value: "\"Medium\"",
-                                                                        "Medium"
+                                                                        value: "",
+                                                                        "Pick discovered node"
                                                                     },
-                                                                    option {
-                                                                        value: "\"Slow\"",
-                                                                        "Slow"
+                                                                    for node in artnet_nodes().unwrap_or_default() {
+                                                                        for address in node.port_addresses.clone() {
+                                                                            option {
+                                                                                value: serde_json::to_string(&(node.ip, address)).unwrap_or_default(),
+                                                                                {format!("{} ({}) {}:{}:{}", node.short_name, node.ip, address.net, address.subnet, address.universe)}
+                                                                            }
+                                                                        }
                                                                     }
                                                                 }
-                                                            }
-                                                        }
-                                                    }
-                                                    EPConfigItem::Usb{ ref port, speed } => {
-                                                        rsx! {
-                                                            p {
-                                                                "sACN",
                                                             },
                                                             div {
                                                                 class: "property",
                                                                 p {
-                                                                    "Port:",
+                                                                    "Unicast:",
                                                                 },
                                                                 input {
                                                                     r#type: "text",
-                                                                    value: port.clone(),
-                                                                    oninput: move |e| {
+                                                                    placeholder: "2.0.0.10, 2.0.0.11",
+                                                                    value: unicast.iter().map(|ip| ip.to_string()).collect::<Vec<_>>().join(", "),
+                                                                    onchange: move |e| {
                                                                         let mut w = transformed_config.write();
                                                                         let c = w.as_mut().expect("").as_mut().expect("");
                                                                         for (uid, conf) in c {
                                                                             if *uid == u {
                                                                                 let item = conf.get_mut(i).expect("");
-                                                                                if let EPConfigItem::Usb{ port, ..} = item {
-                                                                                    *port = e.value();
-                                                                                    needs_update();
+                                                                                if let EPConfigItem::ArtNet{unicast, ..} = item {
+                                                                                    *unicast = e.value()
+                                                                                        .split(',')
+                                                                                        .filter_map(|ip| Ipv4Addr::from_str(ip.trim()).ok())
+                                                                                        .collect();
                                                                                 }
                                                                             }
                                                                         }
@@ -1177,44 +1513,700 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                                             div {
                                                                 class: "property",
                                                                 p {
-                                                                    "Speed:",
+                                                                    "Broadcast:",
                                                                 },
-                                                                select {
-                                                                    value: format!("\"{:?}\"", speed),
-                                                                    onchange: move |e| {
+                                                                utils::Checkbox {
+                                                                    init: broadcast.into(),
+                                                                    onchange: move |state: CheckboxState| {
                                                                         let mut w = transformed_config.write();
                                                                         let c = w.as_mut().expect("").as_mut().expect("");
                                                                         for (uid, conf) in c {
                                                                             if *uid == u {
                                                                                 let item = conf.get_mut(i).expect("");
-                                                                                if let EPConfigItem::Usb{ speed, ..} = item {
-                                                                                    *speed = serde_json::from_str(&e.value()).unwrap_or(Speed::Medium);
+                                                                                if let EPConfigItem::ArtNet{broadcast, ..} = item {
+                                                                                    *broadcast = state.into();
                                                                                 }
                                                                             }
                                                                         }
-                                                                    },
-                                                                    option {
-                                                                        value: "\"Fast\"",
-                                                                        "Fast"
-                                                                    },
-                                                                    option {
-                                                                        value: "\"Medium\"",
-                                                                        "Medium"
-                                                                    },
-                                                                    option {
+                                                                    }
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Interface:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    placeholder: "0.0.0.0",
+                                                                    value: interface.map(|ip| ip.to_string()).unwrap_or_default(),
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::ArtNet{interface, ..} = item {
+                                                                                    *interface = Ipv4Addr::from_str(e.value().trim()).ok();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::Sacn{ universe, speed, priority, ref unicast, sync_universe, .. } => {
+                                                        rsx! {
+                                                            p {
+                                                                "sACN",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Universe:",
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    value: universe as i64,
+                                                                    min: 1,
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Sacn{universe, ..} = item {
+                                                                                    *universe = u16::from_str(&e.value()).unwrap_or(1).max(1);
+                                                                                    needs_update();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Speed:",
+                                                                },
+                                                                select {
+                                                                    value: format!("\"{:?}\"", speed),
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Sacn{speed, ..} = item {
+                                                                                    *speed = serde_json::from_str(&e.value()).unwrap_or(Speed::Medium);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                    option {
+                                                                        value: "\"Fast\"",
+                                                                        "Fast"
+                                                                    },
+                                                                    option {
+                                                                        value: "\"Medium\"",
+                                                                        "Medium"
+                                                                    },
+                                                                    option {
                                                                         value: "\"Slow\"",
                                                                         "Slow"
+                                                                    }
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Priority:",
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    value: priority as i64,
+                                                                    min: 0,
+                                                                    max: 200,
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Sacn{priority, ..} = item {
+                                                                                    *priority = u8::from_str(&e.value()).unwrap_or(100).min(200);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Unicast:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    placeholder: "10.0.0.10, 10.0.0.11",
+                                                                    value: unicast.iter().map(|ip| ip.to_string()).collect::<Vec<_>>().join(", "),
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Sacn{unicast, ..} = item {
+                                                                                    *unicast = e.value()
+                                                                                        .split(',')
+                                                                                        .filter_map(|ip| Ipv4Addr::from_str(ip.trim()).ok())
+                                                                                        .collect();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Sync Universe:",
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    placeholder: "None",
+                                                                    value: sync_universe.map(|s| s.to_string()).unwrap_or_default(),
+                                                                    min: 1,
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Sacn{sync_universe, ..} = item {
+                                                                                    *sync_universe = u16::from_str(&e.value()).ok().filter(|s| *s > 0);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::Usb{ ref port, speed, .. } => {
+                                                        rsx! {
+                                                            p {
+                                                                "sACN",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Port:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    value: port.clone(),
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Usb{ port, ..} = item {
+                                                                                    *port = e.value();
+                                                                                    needs_update();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Speed:",
+                                                                },
+                                                                select {
+                                                                    value: format!("\"{:?}\"", speed),
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Usb{ speed, ..} = item {
+                                                                                    *speed = serde_json::from_str(&e.value()).unwrap_or(Speed::Medium);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                    option {
+                                                                        value: "\"Fast\"",
+                                                                        "Fast"
+                                                                    },
+                                                                    option {
+                                                                        value: "\"Medium\"",
+                                                                        "Medium"
+                                                                    },
+                                                                    option {
+                                                                        value: "\"Slow\"",
+                                                                        "Slow"
+                                                                    },
+                                                                    option {
+                                                                        value: "\"SuperFast\"",
+                                                                        "Super Fast"
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::UsbPro{ ref port, .. } => {
+                                                        rsx! {
+                                                            p {
+                                                                "Enttec USB Pro",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Port:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    value: port.clone(),
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::UsbPro{ port, .. } = item {
+                                                                                    *port = e.value();
+                                                                                    needs_update();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::SacnInput{ universe, merge, local_priority } => {
+                                                        rsx! {
+                                                            p {
+                                                                "sACN Input",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Universe:",
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    value: universe as i64,
+                                                                    min: 1,
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::SacnInput{universe, ..} = item {
+                                                                                    *universe = u16::from_str(&e.value()).unwrap_or(1).max(1);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            MergeModeSelect {
+                                                                mode: merge,
+                                                                allow_priority: true,
+                                                                onchange: move |mode| {
+                                                                    let mut w = transformed_config.write();
+                                                                    let c = w.as_mut().expect("").as_mut().expect("");
+                                                                    for (uid, conf) in c {
+                                                                        if *uid == u {
+                                                                            let item = conf.get_mut(i).expect("");
+                                                                            if let EPConfigItem::SacnInput{merge, ..} = item {
+                                                                                *merge = mode;
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            },
+                                                            if merge == MergeMode::Priority {
+                                                                div {
+                                                                    class: "property",
+                                                                    title: "Sources with a higher priority take over the universe",
+                                                                    p {
+                                                                        "Local Priority:",
+                                                                    },
+                                                                    input {
+                                                                        r#type: "number",
+                                                                        value: local_priority as i64,
+                                                                        min: 0,
+                                                                        max: 200,
+                                                                        oninput: move |e| {
+                                                                            let mut w = transformed_config.write();
+                                                                            let c = w.as_mut().expect("").as_mut().expect("");
+                                                                            for (uid, conf) in c {
+                                                                                if *uid == u {
+                                                                                    let item = conf.get_mut(i).expect("");
+                                                                                    if let EPConfigItem::SacnInput{local_priority, ..} = item {
+                                                                                        *local_priority = u8::from_str(&e.value()).unwrap_or(100).min(200);
+                                                                                    }
+                                                                                }
+                                                                            }
+                                                                        },
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::ArtNetInput{ port_address, merge } => {
+                                                        rsx! {
+                                                            p {
+                                                                "Art-Net Input",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Net / Sub / Uni:",
+                                                                },
+                                                                div {
+                                                                    class: "port-address",
+                                                                    for (part, value, max) in [(0, port_address.net, 0x7f), (1, port_address.subnet, 0x0f), (2, port_address.universe, 0x0f)] {
+                                                                        input {
+                                                                            r#type: "number",
+                                                                            value: value as i64,
+                                                                            min: 0,
+                                                                            max: max as i64,
+                                                                            oninput: move |e| {
+                                                                                let mut w = transformed_config.write();
+                                                                                let c = w.as_mut().expect("").as_mut().expect("");
+                                                                                for (uid, conf) in c {
+                                                                                    if *uid == u {
+                                                                                        let item = conf.get_mut(i).expect("");
+                                                                                        if let EPConfigItem::ArtNetInput{port_address, ..} = item {
+                                                                                            let v = u8::from_str(&e.value()).unwrap_or(0).min(max);
+                                                                                            match part {
+                                                                                                0 => port_address.net = v,
+                                                                                                1 => port_address.subnet = v,
+                                                                                                _ => port_address.universe = v,
+                                                                                            }
+                                                                                        }
+                                                                                    }
+                                                                                }
+                                                                            },
+                                                                        }
+                                                                    }
+                                                                }
+                                                            },
+                                                            MergeModeSelect {
+                                                                mode: merge,
+                                                                allow_priority: false,
+                                                                onchange: move |mode| {
+                                                                    let mut w = transformed_config.write();
+                                                                    let c = w.as_mut().expect("").as_mut().expect("");
+                                                                    for (uid, conf) in c {
+                                                                        if *uid == u {
+                                                                            let item = conf.get_mut(i).expect("");
+                                                                            if let EPConfigItem::ArtNetInput{merge, ..} = item {
+                                                                                *merge = mode;
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::Ddp{ destination, mapping, .. } => {
+                                                        rsx! {
+                                                            p {
+                                                                "DDP",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Destination:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    value: destination.to_string(),
+                                                                    onchange: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Ddp{destination, ..} = item {
+                                                                                    if let Ok(ip) = Ipv4Addr::from_str(&e.value()) {
+                                                                                        *destination = ip;
+                                                                                    }
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            PixelMappingProperties {
+                                                                mapping,
+                                                                onchange: move |m| {
+                                                                    let mut w = transformed_config.write();
+                                                                    let c = w.as_mut().expect("").as_mut().expect("");
+                                                                    for (uid, conf) in c {
+                                                                        if *uid == u {
+                                                                            let item = conf.get_mut(i).expect("");
+                                                                            if let EPConfigItem::Ddp{mapping, ..} = item {
+                                                                                *mapping = m;
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::Opc{ ref address, channel, mapping, .. } => {
+                                                        rsx! {
+                                                            p {
+                                                                "Open Pixel Control",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Server:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    value: address.clone(),
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Opc{address, ..} = item {
+                                                                                    *address = e.value();
+                                                                                    needs_update();
+                                                                                }
+                                                                            }
+                                                                        }
                                                                     },
-                                                                    option {
-                                                                        value: "\"SuperFast\"",
-                                                                        "Super Fast"
+                                                                }
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                p {
+                                                                    "Channel:",
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    value: channel as i64,
+                                                                    min: 0,
+                                                                    max: 255,
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Opc{channel, ..} = item {
+                                                                                    *channel = u8::from_str(&e.value()).unwrap_or(0);
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            },
+                                                            PixelMappingProperties {
+                                                                mapping,
+                                                                onchange: move |m| {
+                                                                    let mut w = transformed_config.write();
+                                                                    let c = w.as_mut().expect("").as_mut().expect("");
+                                                                    for (uid, conf) in c {
+                                                                        if *uid == u {
+                                                                            let item = conf.get_mut(i).expect("");
+                                                                            if let EPConfigItem::Opc{mapping, ..} = item {
+                                                                                *mapping = m;
+                                                                            }
+                                                                        }
                                                                     }
                                                                 }
                                                             }
                                                         }
                                                     }
+                                                    EPConfigItem::ArtNetTimecode => {
+                                                        rsx! {
+                                                            p {
+                                                                "Art-Net Timecode",
+                                                            },
+                                                            p {
+                                                                class: "hint",
+                                                                "Playing effects follow the received timecode and pause when it stops",
+                                                            }
+                                                        }
+                                                    }
+                                                    EPConfigItem::Recorder{ ref file } => {
+                                                        rsx! {
+                                                            p {
+                                                                "Recorder",
+                                                            },
+                                                            div {
+                                                                class: "property",
+                                                                title: "Recorders with the same name write into one recording, every start of the endpoints adds one named after its start time",
+                                                                p {
+                                                                    "Recording:",
+                                                                },
+                                                                input {
+                                                                    r#type: "text",
+                                                                    value: file.clone(),
+                                                                    oninput: move |e| {
+                                                                        let mut w = transformed_config.write();
+                                                                        let c = w.as_mut().expect("").as_mut().expect("");
+                                                                        for (uid, conf) in c {
+                                                                            if *uid == u {
+                                                                                let item = conf.get_mut(i).expect("");
+                                                                                if let EPConfigItem::Recorder{file} = item {
+                                                                                    *file = e.value();
+                                                                                    needs_update();
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    },
+                                                                }
+                                                            }
+                                                        }
+                                                    }
                                                 }}
                                             },
+                                            if let Some(channel_map) = ep.channel_map().cloned() {
+                                                div {
+                                                    class: "channel-map",
+                                                    div {
+                                                        class: "property",
+                                                        title: "Rearranges the output, channels without a patch are dropped",
+                                                        p {
+                                                            "Channel Map:",
+                                                        },
+                                                        utils::Checkbox {
+                                                            init: channel_map.is_some().into(),
+                                                            onchange: move |state: CheckboxState| {
+                                                                let enabled: bool = state.into();
+                                                                edit_channel_map(transformed_config, u, i, |map| {
+                                                                    *map = enabled.then(|| ChannelMap {
+                                                                        patches: vec![ChannelPatch::default()],
+                                                                    });
+                                                                });
+                                                            }
+                                                        }
+                                                    },
+                                                    if let Some(map) = channel_map {
+                                                        div {
+                                                            class: "channel-patch header",
+                                                            p { "Universe" },
+                                                            p { "From" },
+                                                            p { "To" },
+                                                            p { "Count" },
+                                                        },
+                                                        for (pi, patch) in map.patches.into_iter().enumerate() {
+                                                            div {
+                                                                class: "channel-patch",
+                                                                input {
+                                                                    r#type: "number",
+                                                                    title: "The universe the channels are taken from, empty is the universe of the endpoint",
+                                                                    placeholder: "Own",
+                                                                    value: patch.universe.map(|u| u.0.to_string()).unwrap_or_default(),
+                                                                    min: 1,
+                                                                    oninput: move |e| {
+                                                                        let universe = u16::from_str(&e.value()).ok().map(UniverseId);
+                                                                        edit_channel_map(transformed_config, u, i, |map| {
+                                                                            if let Some(p) = map.as_mut().and_then(|m| m.patches.get_mut(pi)) {
+                                                                                p.universe = universe;
+                                                                            }
+                                                                        });
+                                                                    },
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    title: "The first channel that is taken",
+                                                                    value: patch.from as i64 + 1,
+                                                                    min: 1,
+                                                                    max: 512,
+                                                                    oninput: move |e| {
+                                                                        let from = u16::from_str(&e.value()).unwrap_or(1).clamp(1, 512) - 1;
+                                                                        edit_channel_map(transformed_config, u, i, |map| {
+                                                                            if let Some(p) = map.as_mut().and_then(|m| m.patches.get_mut(pi)) {
+                                                                                p.from = from;
+                                                                            }
+                                                                        });
+                                                                    },
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    title: "The output channel the first channel is written to",
+                                                                    value: patch.to as i64 + 1,
+                                                                    min: 1,
+                                                                    max: 512,
+                                                                    oninput: move |e| {
+                                                                        let to = u16::from_str(&e.value()).unwrap_or(1).clamp(1, 512) - 1;
+                                                                        edit_channel_map(transformed_config, u, i, |map| {
+                                                                            if let Some(p) = map.as_mut().and_then(|m| m.patches.get_mut(pi)) {
+                                                                                p.to = to;
+                                                                            }
+                                                                        });
+                                                                    },
+                                                                },
+                                                                input {
+                                                                    r#type: "number",
+                                                                    title: "How many channels are copied",
+                                                                    value: patch.length as i64,
+                                                                    min: 0,
+                                                                    max: 512,
+                                                                    oninput: move |e| {
+                                                                        let length = u16::from_str(&e.value()).unwrap_or(0).min(512);
+                                                                        edit_channel_map(transformed_config, u, i, |map| {
+                                                                            if let Some(p) = map.as_mut().and_then(|m| m.patches.get_mut(pi)) {
+                                                                                p.length = length;
+                                                                            }
+                                                                        });
+                                                                    },
+                                                                },
+                                                                button {
+                                                                    class: "icon",
+                                                                    title: "Remove patch",
+                                                                    onclick: move |_| {
+                                                                        edit_channel_map(transformed_config, u, i, |map| {
+                                                                            if let Some(m) = map.as_mut() {
+                                                                                m.patches.remove(pi);
+                                                                            }
+                                                                        });
+                                                                    },
+                                                                    icons::Minus {
+                                                                        width: "1rem",
+                                                                        height: "1rem",
+                                                                    }
+                                                                }
+                                                            }
+                                                        },
+                                                        button {
+                                                            class: "icon add-patch-btn",
+                                                            title: "Add patch",
+                                                            onclick: move |_| {
+                                                                edit_channel_map(transformed_config, u, i, |map| {
+                                                                    if let Some(m) = map.as_mut() {
+                                                                        m.patches.push(ChannelPatch::default());
+                                                                    }
+                                                                });
+                                                            },
+                                                            icons::Plus {
+                                                                width: "1rem",
+                                                                height: "1rem",
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            },
                                             button {
                                                 class: "icon delete-btn",
                                                 onclick: move |_| {
@@ -1251,6 +2243,80 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                             }
                         }
             
+                        div {
+                            class: "output-settings",
+                            div {
+                                class: "property",
+                                title: "How often the output is sent per second",
+                                p {
+                                    "Frame Rate:",
+                                },
+                                input {
+                                    r#type: "number",
+                                    value: frame_clock.read().rate as i64,
+                                    min: 1,
+                                    max: 1000,
+                                    oninput: move |e| {
+                                        frame_clock.write().rate = u16::from_str(&e.value()).unwrap_or(40).clamp(1, 1000);
+                                    },
+                                }
+                            }
+                            div {
+                                class: "property",
+                                title: "Milliseconds after which unchanged universes are sent again",
+                                p {
+                                    "Keep-alive:",
+                                },
+                                input {
+                                    r#type: "number",
+                                    value: frame_clock.read().keep_alive as i64,
+                                    min: 0,
+                                    oninput: move |e| {
+                                        frame_clock.write().keep_alive = u32::from_str(&e.value()).unwrap_or(1000);
+                                    },
+                                }
+                            }
+                            div {
+                                class: "property",
+                                title: format!("CID: {}", sacn_source.read().cid),
+                                p {
+                                    "sACN Source Name:",
+                                },
+                                input {
+                                    r#type: "text",
+                                    value: sacn_source.read().name.clone(),
+                                    oninput: move |e| {
+                                        sacn_source.write().name = e.value();
+                                    },
+                                }
+                            }
+                            div {
+                                class: "property",
+                                title: "Lets OSC tools control effects, faders and fixtures",
+                                p {
+                                    "OSC:",
+                                },
+                                utils::Checkbox {
+                                    init: osc_config.read().enabled.into(),
+                                    onchange: move |state: CheckboxState| {
+                                        osc_config.write().enabled = state.into();
+                                    }
+                                },
+                                input {
+                                    r#type: "number",
+                                    title: "UDP port",
+                                    value: osc_config.read().port as i64,
+                                    min: 1,
+                                    max: 65535,
+                                    oninput: move |e| {
+                                        osc_config.write().port = u16::from_str(&e.value()).unwrap_or(8000);
+                                    },
+                                }
+                            }
+                        }
+
+                        RdmDevices {}
+
                         div {
                             class: "btns",
                             button {
@@ -1266,6 +2332,9 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                             }
                                             let ep_config = EndPointConfig {
                                                 endpoints: map,
+                                                sacn: sacn_source(),
+                                                clock: frame_clock(),
+                                                osc: osc_config(),
                                             };
                                             let r = utils::fetch_post::<String, _>("/runtime/endpoints/set", ep_config).await;
                                             if r.is_ok() {
@@ -1283,6 +2352,22 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
                                     onclose.call(())
                                 },
                                 "Cancel"
+                            },
+                            button {
+                                title: "Poll the network for Art-Net nodes",
+                                onclick: move |_| {
+                                    async move {
+                                        let r = utils::fetch::<Vec<ArtNetNode>>("/runtime/artnet/discover").await;
+                                        match r {
+                                            Ok(nodes) => {
+                                                toaster.info("Art-Net discovery", format!("Found {} Art-Net nodes", nodes.len()));
+                                                artnet_nodes.restart();
+                                            }
+                                            Err(e) => log::error!("Art-Net discovery failed: {e:?}"),
+                                        }
+                                    }
+                                },
+                                "Discover Art-Net"
                             }
                         }
                     }
@@ -1296,6 +2381,272 @@ fn EndPointMapping(onclose: EventHandler) -> Element {
     }
 }
 
+#[component]
+fn RdmDevices() -> Element {
+    let mut report = use_resource(|| async move {
+        utils::fetch::<RdmPatchReport>("/runtime/artnet/rdm/patch")
+            .await
+            .map_err(|e| {
+                log::error!("Error fetching RDM patch check: {:?}", e);
+            })
+            .unwrap_or_default()
+    });
+
+    let mut toaster = use_context::<Signal<Toaster>>();
+
+    let info = use_context::<Signal<Info>>();
+    use_effect(move || {
+        if info() == Info::RdmDevicesChanged || info() == Info::EndpointConfigChanged {
+            report.restart();
+        }
+    });
+
+    let r = report().unwrap_or_default();
+    rsx! {
+        div {
+            class: "rdm-devices",
+            div {
+                class: "rdm-header",
+                p {
+                    "RDM Devices",
+                },
+                button {
+                    title: "Read the RDM devices behind all running Art-Net outputs",
+                    onclick: move |_| {
+                        async move {
+                            match utils::fetch::<Vec<RdmDevice>>("/runtime/artnet/rdm/discover").await {
+                                Ok(devices) => {
+                                    toaster.info("RDM discovery", format!("Found {} RDM devices", devices.len()));
+                                }
+                                Err(e) => {
+                                    log::error!("RDM discovery failed: {e:?}");
+                                    toaster.error("RDM discovery failed", "RDM devices are found through running Art-Net outputs. See Backend output for more Information");
+                                }
+                            }
+                        }
+                    },
+                    "Discover RDM"
+                }
+            }
+            for check in r.devices {
+                div {
+                    class: "rdm-device",
+                    class: rdm_state(&check).0,
+                    title: rdm_state(&check).1,
+                    p {
+                        class: "uid",
+                        title: format!("Node {}, Art-Net {:?}", check.device.node, check.device.port_address),
+                        {check.device.uid.to_string()}
+                    },
+                    p {
+                        {format!("{} {}", check.device.manufacturer, check.device.model)}
+                    },
+                    p {
+                        title: "Personality",
+                        {format!("{}/{} {}", check.device.personality, check.device.personality_count, check.device.personality_description)}
+                    },
+                    if check.device.dmx_start_address.is_some() {
+                        input {
+                            r#type: "number",
+                            title: format!("Start address, {} channels", check.device.footprint),
+                            value: check.device.dmx_start_address.unwrap_or(1) as i64,
+                            min: 1,
+                            max: 512,
+                            onchange: move |e| {
+                                let uid = check.device.uid;
+                                async move {
+                                    let Ok(address) = u16::from_str(&e.value()) else {
+                                        return;
+                                    };
+                                    let r = utils::fetch_post::<RdmDevice, _>("/runtime/artnet/rdm/address", RdmAddressRequest { uid, address }).await;
+                                    if r.is_err() {
+                                        toaster.error("RDM address change failed", format!("Failed to set the start address of {uid}. See Backend output for more Information"));
+                                    }
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+            for missing in r.missing {
+                div {
+                    class: "rdm-device missing",
+                    title: "No RDM device answered at the address of this fixture",
+                    p {
+                        {format!("Universe {}", missing.universe.0)}
+                    },
+                    p {
+                        {missing.name}
+                    },
+                    p {
+                        {format!("Address {}", missing.address)}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn rdm_state(check: &RdmPatchCheck) -> (&'static str, String) {
+    let fixture = check.fixture.as_ref().map(|(_, name)| name.as_str()).unwrap_or_default();
+    match &check.state {
+        RdmPatchState::Matched => ("matched", format!("Patched as {fixture}")),
+        RdmPatchState::FootprintMismatch { patched } => (
+            "mismatch",
+            format!("{fixture} uses {patched} channels, the device {}", check.device.footprint),
+        ),
+        RdmPatchState::ModelMismatch => ("mismatch", format!("{fixture} is patched at the address of this device")),
+        RdmPatchState::Unpatched => ("unpatched", "No fixture is patched at the address of this device".to_string()),
+        RdmPatchState::NoUniverse => ("unpatched", "No universe is output to the Art-Net port of this device".to_string()),
+    }
+}
+
+#[component]
+fn EndpointStateIndicator(status: Option<EndpointStatus>) -> Element {
+    let (class, title) = match status {
+        None => ("unknown", "Not running, apply the config to start it".to_string()),
+        Some(s) => {
+            let since = s.since.format("%H:%M:%S");
+            let restarts = if s.restarts > 0 {
+                format!(", restarted {} times", s.restarts)
+            } else {
+                String::new()
+            };
+            let device = match &s.device {
+                Some(EndpointDevice::UsbPro(d)) => format!(
+                    "\nSerial number {:08X}, firmware {}, {} packets/s",
+                    d.serial_number,
+                    d.firmware_version(),
+                    d.refresh_rate
+                ),
+                None => String::new(),
+            };
+            match s.state {
+                EndpointState::Starting => ("starting", format!("Starting since {since}{restarts}")),
+                EndpointState::Running => ("running", format!("Running since {since}{restarts}{device}")),
+                EndpointState::Error(e) => ("error", format!("{e} ({since}{restarts})")),
+                EndpointState::Stopped => ("stopped", format!("Stopped at {since}")),
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: "endpoint-state",
+            class: class,
+            title: title,
+        }
+    }
+}
+
+#[component]
+fn MergeModeSelect(mode: MergeMode, allow_priority: bool, onchange: EventHandler<MergeMode>) -> Element {
+    rsx! {
+        div {
+            class: "property",
+            p {
+                "Merge:",
+            },
+            select {
+                value: format!("\"{:?}\"", mode),
+                onchange: move |e| {
+                    onchange.call(serde_json::from_str(&e.value()).unwrap_or_default());
+                },
+                option {
+                    value: "\"Htp\"",
+                    title: "Highest takes precedence",
+                    "HTP"
+                },
+                option {
+                    value: "\"Ltp\"",
+                    title: "Latest takes precedence",
+                    "LTP"
+                },
+                if allow_priority {
+                    option {
+                        value: "\"Priority\"",
+                        "Priority"
+                    }
+                }
+            }
+        }
+    }
+}
+
+type UniverseEndpoints = Option<Option<Vec<(UniverseId, Vec<EPConfigItem>)>>>;
+
+/// Edits the channel map of the endpoint at index `i` of universe `u`
+fn edit_channel_map(
+    mut config: Signal<UniverseEndpoints>,
+    u: UniverseId,
+    i: usize,
+    f: impl FnOnce(&mut Option<ChannelMap>),
+) {
+    let mut w = config.write();
+    let c = w.as_mut().expect("").as_mut().expect("");
+    if let Some((_, conf)) = c.iter_mut().find(|(uid, _)| *uid == u) {
+        if let Some(map) = conf.get_mut(i).and_then(|item| item.channel_map_mut()) {
+            f(map);
+        }
+    }
+}
+
+#[component]
+fn PixelMappingProperties(mapping: PixelMapping, onchange: EventHandler<PixelMapping>) -> Element {
+    rsx! {
+        div {
+            class: "property",
+            title: "The first channel of the universe that is sent",
+            p {
+                "Start:",
+            },
+            input {
+                r#type: "number",
+                value: mapping.start as i64 + 1,
+                min: 1,
+                max: 512,
+                oninput: move |e| {
+                    let start = u16::from_str(&e.value()).unwrap_or(1).clamp(1, 512) - 1;
+                    onchange.call(PixelMapping { start, ..mapping });
+                },
+            }
+        },
+        div {
+            class: "property",
+            title: "How many channels are sent, 3 per RGB pixel",
+            p {
+                "Channels:",
+            },
+            input {
+                r#type: "number",
+                value: mapping.length as i64,
+                min: 0,
+                max: 512,
+                oninput: move |e| {
+                    let length = u16::from_str(&e.value()).unwrap_or(0).min(512);
+                    onchange.call(PixelMapping { length, ..mapping });
+                },
+            }
+        },
+        div {
+            class: "property",
+            title: "The byte in the pixel data of the controller the first channel is written to",
+            p {
+                "Offset:",
+            },
+            input {
+                r#type: "number",
+                value: mapping.offset as i64,
+                min: 0,
+                oninput: move |e| {
+                    let offset = u32::from_str(&e.value()).unwrap_or(0);
+                    onchange.call(PixelMapping { offset, ..mapping });
+                },
+            }
+        }
+    }
+}
+
 fn make_type_closure<F, E, T>(
     ep: EPConfigItem,
     mut closure: F,
@@ -832,6 +832,71 @@ pub fn Usb(props: IconProps) -> Element {
     }
 }
 
+pub fn Plug(props: IconProps) -> Element {
+    let width = props.width.unwrap_or("1.25rem".to_string());
+    let height = props.height.unwrap_or("1.25rem".to_string());
+    rsx! {
+        svg {
+            "viewBox": "0 0 24 24",
+            "stroke-linejoin": "round",
+            width,
+            height,
+            "xmlns": "http://www.w3.org/2000/svg",
+            "stroke": "currentColor",
+            "stroke-width": "2",
+            "fill": "none",
+            "stroke-linecap": "round",
+            class: "lucide lucide-plug",
+            path { "d": "M12 22v-5" }
+            path { "d": "M9 8V2" }
+            path { "d": "M15 8V2" }
+            path { "d": "M18 8v5a4 4 0 0 1-4 4h-4a4 4 0 0 1-4-4V8Z" }
+        }
+    }
+}
+
+pub fn Clock(props: IconProps) -> Element {
+    let width = props.width.unwrap_or("1.25rem".to_string());
+    let height = props.height.unwrap_or("1.25rem".to_string());
+    rsx! {
+        svg {
+            "viewBox": "0 0 24 24",
+            "stroke-linejoin": "round",
+            width,
+            height,
+            "xmlns": "http://www.w3.org/2000/svg",
+            "stroke": "currentColor",
+            "stroke-width": "2",
+            "fill": "none",
+            "stroke-linecap": "round",
+            class: "lucide lucide-clock",
+            circle { "cx": "12", "cy": "12", "r": "10" }
+            polyline { "points": "12 6 12 12 16 14" }
+        }
+    }
+}
+
+pub fn CircleDot(props: IconProps) -> Element {
+    let width = props.width.unwrap_or("1.25rem".to_string());
+    let height = props.height.unwrap_or("1.25rem".to_string());
+    rsx! {
+        svg {
+            "viewBox": "0 0 24 24",
+            "stroke-linejoin": "round",
+            width,
+            height,
+            "xmlns": "http://www.w3.org/2000/svg",
+            "stroke": "currentColor",
+            "stroke-width": "2",
+            "fill": "none",
+            "stroke-linecap": "round",
+            class: "lucide lucide-circle-dot",
+            circle { "cx": "12", "cy": "12", "r": "10" }
+            circle { "cx": "12", "cy": "12", "r": "1" }
+        }
+    }
+}
+
 pub fn Diamond(props: IconProps) -> Element {
     let width = props.width.unwrap_or("1.25rem".to_string());
     let height = props.height.unwrap_or("1.25rem".to_string());
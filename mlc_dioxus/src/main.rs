@@ -187,7 +187,17 @@ fn provide_info() {
                             "The Endpoint configuration was changed!",
                         );
                     }
+                    Info::ArtNetNodesChanged => {}
+                    Info::RdmDevicesChanged => {}
+                    Info::ParkedChanged => {}
+                    Info::MergePoliciesChanged => {}
+                    Info::DefaultsChanged => {}
+                    Info::CurvesChanged => {}
+                    Info::EndpointStatesChanged => {}
                     Info::EffectListChanged => {}
+                    Info::ScenesChanged => {}
+                    Info::GroupsChanged => {}
+                    Info::HighlightChanged => {}
                     Info::None => {}
                 }
             }
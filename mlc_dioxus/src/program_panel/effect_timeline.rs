@@ -411,6 +411,7 @@ fn CreateTrackDetailFeature(
                                     resolution: Duration::milliseconds(resolution().max(5)),
                                     feature: feature_type,
                                     fixtures,
+                                    groups: vec![],
                                     detail: FeatureTrackDetail::empty_from_feature_type(&feature_type),
                                 }),
                             );
@@ -55,6 +55,7 @@ pub fn ProgramPanel() -> Element {
                                             looping: effect.looping,
                                             duration: effect.duration,
                                             tracks: effect.tracks,
+                                            timecode_start: effect.timecode_start,
                                         }.to_msg().unwrap()).await;
                                     }
                                     EHRequest::Create(name) => {
@@ -585,6 +586,28 @@ fn EffectInfo() -> Element {
                                 }
                             }
                         }
+                        div {
+                            class: "property",
+                            title: "Where the effect starts while effects chase Art-Net timecode (mm:ss.mmm)",
+                            p {
+                                "Timecode Start"
+                            },
+                            input {
+                                r#type: "text",
+                                value: effect.timecode_start.effect_format(),
+                                onchange: move |v| {
+                                    if let Some(start) = parse_effect_duration(&v.value()) {
+                                        {
+                                            let mut w = current_effect.write();
+                                            if let Some(w) = &mut *w {
+                                                w.timecode_start = start;
+                                            }
+                                        }
+                                        effect_invalidator.send(EffectInvalidate);
+                                    }
+                                },
+                            }
+                        }
                     }
                 }
             }
@@ -592,6 +615,19 @@ fn EffectInfo() -> Element {
     }
 }
 
+/// Parses `hh:mm:ss.mmm`, `mm:ss.mmm` or `ss.mmm`
+fn parse_effect_duration(s: &str) -> Option<Duration> {
+    let mut parts = s.trim().rsplit(':');
+    let seconds = parts.next()?.parse::<f64>().ok()?;
+    let minutes = parts.next().map(|m| m.parse::<i64>().ok()).unwrap_or(Some(0))?;
+    let hours = parts.next().map(|h| h.parse::<i64>().ok()).unwrap_or(Some(0))?;
+    if parts.next().is_some() || seconds < 0.0 || minutes < 0 || hours < 0 {
+        return None;
+    }
+
+    Some(Duration::hours(hours) + Duration::minutes(minutes) + Duration::milliseconds((seconds * 1000.0).round() as i64))
+}
+
 #[component]
 fn to_visualized_effect_name(name: String) -> Element {
     let paths = use_memo(use_reactive!(|name| {
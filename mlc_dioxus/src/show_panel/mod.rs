@@ -3,13 +3,17 @@ use futures::{select, SinkExt, StreamExt};
 use gloo_net::websocket::Message;
 use mlc_common::{
     effect::{
-        player::{EffectPlayerMsg, EffectPlayerRequest},
+        player::{EffectPlayerMsg, EffectPlayerRequest, PlayingEffect, SeekPosition},
         EffectId,
     },
+    masters::{MasterRequest, Masters},
+    patched::UniverseId,
+    scene::{SceneCapture, SceneCrossfade, SceneId, SceneRecall},
     utils::{
         bounds::{One, Zero},
         BoundedValue,
     },
+    Info,
 };
 
 use crate::{
@@ -22,6 +26,8 @@ pub fn ShowPanel() -> Element {
     rsx! {
         div { class: "show-panel",
             div { class: "effect-player panel", EffectPlayer {} }
+            div { class: "scenes panel", ScenePanel {} }
+            div { class: "masters panel", MasterPanel {} }
         }
     }
 }
@@ -37,6 +43,7 @@ fn EffectPlayer() -> Element {
     let mut playing_effects: Signal<Vec<EffectId>> = use_signal(Vec::new);
     let mut effect_progresses: Signal<Vec<(EffectId, BoundedValue<f32, Zero, One>)>> =
         use_signal(Vec::new);
+    let mut effect_states: Signal<Vec<PlayingEffect>> = use_signal(Vec::new);
     let effect_player = use_coroutine(
         |mut rx: UnboundedReceiver<EffectPlayerRequest>| async move {
             let ws = utils::ws("/effects/effectPlayer").await;
@@ -82,6 +89,7 @@ fn EffectPlayer() -> Element {
                                                 playing_effects.set(effects);
                                             },
                                             EffectPlayerMsg::EffectProgresses(updates) => effect_progresses.set(updates),
+                                            EffectPlayerMsg::EffectStates(states) => effect_states.set(states),
                                         }
                                     }
                                     None => break,
@@ -128,6 +136,61 @@ fn EffectPlayer() -> Element {
                                         _ => BoundedValue::create(0.0)
                                     }
                                 }
+                                if let Some(state) = effect_states.read().iter().find(|s| s.effect == e.1).copied() {
+                                    div { class: "effect-controls",
+                                        button {
+                                            onclick: move |_| {
+                                                effect_player.send(if state.paused {
+                                                    EffectPlayerRequest::Resume {effect: e.1}
+                                                } else {
+                                                    EffectPlayerRequest::Pause {effect: e.1}
+                                                });
+                                            },
+                                            if state.paused { "Resume" } else { "Pause" }
+                                        }
+                                        label { "Seek" }
+                                        input {
+                                            r#type: "range",
+                                            min: 0,
+                                            max: 1000,
+                                            oninput: move |ev| {
+                                                if let Ok(v) = ev.value().parse::<f64>() {
+                                                    effect_player.send(EffectPlayerRequest::Seek {
+                                                        effect: e.1,
+                                                        position: SeekPosition::Fraction(BoundedValue::create(v / 1000.0)),
+                                                    });
+                                                }
+                                            }
+                                        }
+                                        label { "Rate" }
+                                        input {
+                                            r#type: "number",
+                                            min: 0,
+                                            step: 0.1,
+                                            value: "{state.rate}",
+                                            onchange: move |ev| {
+                                                if let Ok(rate) = ev.value().parse::<f64>() {
+                                                    effect_player.send(EffectPlayerRequest::SetRate { effect: e.1, rate });
+                                                }
+                                            }
+                                        }
+                                        label { "Intensity" }
+                                        input {
+                                            r#type: "range",
+                                            min: 0,
+                                            max: 100,
+                                            value: (state.intensity.take() * 100.0).round(),
+                                            oninput: move |ev| {
+                                                if let Ok(v) = ev.value().parse::<f64>() {
+                                                    effect_player.send(EffectPlayerRequest::SetIntensity {
+                                                        effect: e.1,
+                                                        intensity: BoundedValue::create(v / 100.0),
+                                                    });
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
                             }
                         }
                     }
@@ -139,3 +202,257 @@ fn EffectPlayer() -> Element {
         }
     }
 }
+
+#[component]
+fn MasterPanel() -> Element {
+    let mut universes = use_resource(move || async move {
+        utils::fetch::<Vec<UniverseId>>("/data/universes")
+            .await
+            .ok()
+            .unwrap_or_else(Vec::new)
+    });
+    let info = use_context::<Signal<Info>>();
+    use_effect(move || {
+        if info() == Info::UniversesUpdated {
+            universes.restart();
+        }
+    });
+
+    let mut masters = use_signal(Masters::default);
+    let master_ws = use_coroutine(|mut rx: UnboundedReceiver<MasterRequest>| async move {
+        let ws = utils::ws("/runtime/masters/ws").await;
+        match ws {
+            Ok(ws) => {
+                let mut ws = ws.fuse();
+                loop {
+                    select! {
+                        msg = rx.next() => {
+                            if let Some(msg) = msg {
+                                let _ = ws.send(msg.to_msg().unwrap()).await;
+                            }
+                        }
+                        msg = ws.next() => {
+                            let m = match msg {
+                                Some(Ok(Message::Text(t))) => serde_json::from_str::<Masters>(&t).ok(),
+                                Some(Ok(Message::Bytes(b))) => serde_json::from_slice::<Masters>(&b).ok(),
+                                Some(Err(e)) => {
+                                    log::error!("Websocket error: {e:?}");
+                                    None
+                                }
+                                None => None,
+                            };
+
+                            match m {
+                                Some(m) => masters.set(m),
+                                None => break,
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => log::error!("Failed to connect to masters: {e:?}"),
+        }
+    });
+
+    rsx! {
+        button {
+            class: "blackout",
+            class: if masters.read().blackout { "active" } else { "" },
+            onclick: move |_| {
+                let blackout = masters.read().blackout;
+                master_ws.send(MasterRequest::Blackout(!blackout));
+            },
+            if masters.read().blackout {
+                "Blackout active"
+            } else {
+                "Blackout"
+            }
+        }
+        div { class: "master grand",
+            p { "Grand Master" }
+            input {
+                r#type: "range",
+                min: 0,
+                max: 255,
+                value: masters.read().grand as f64,
+                oninput: move |e| {
+                    if let Ok(v) = e.value().parse::<u8>() {
+                        master_ws.send(MasterRequest::Grand(v));
+                    }
+                }
+            }
+            p { class: "percent", {percent(masters.read().grand)} }
+        }
+        match universes() {
+            Some(universes) => rsx! {
+                for universe in universes {
+                    div { class: "master",
+                        p { "Universe {universe.0}" }
+                        input {
+                            r#type: "range",
+                            min: 0,
+                            max: 255,
+                            value: masters.read().universes.get(&universe).copied().unwrap_or(255) as f64,
+                            oninput: move |e| {
+                                if let Ok(v) = e.value().parse::<u8>() {
+                                    master_ws.send(MasterRequest::Universe { universe, value: Some(v) });
+                                }
+                            }
+                        }
+                        p { class: "percent",
+                            {percent(masters.read().universes.get(&universe).copied().unwrap_or(255))}
+                        }
+                    }
+                }
+            },
+            None => rsx! {
+                Loading {}
+            },
+        }
+    }
+}
+
+#[component]
+fn ScenePanel() -> Element {
+    let mut scenes = use_resource(move || async move {
+        utils::fetch::<Vec<(String, SceneId)>>("/runtime/scenes")
+            .await
+            .ok()
+            .unwrap_or_else(Vec::new)
+    });
+    let mut universes = use_resource(move || async move {
+        utils::fetch::<Vec<UniverseId>>("/data/universes")
+            .await
+            .ok()
+            .unwrap_or_else(Vec::new)
+    });
+    let info = use_context::<Signal<Info>>();
+    use_effect(move || match info() {
+        Info::ScenesChanged => scenes.restart(),
+        Info::UniversesUpdated => universes.restart(),
+        _ => {}
+    });
+
+    let mut name = use_signal(String::new);
+    let mut fade = use_signal(|| 0.0f64);
+    let mut crossfade_a: Signal<Option<SceneId>> = use_signal(|| None);
+    let mut crossfade_b: Signal<Option<SceneId>> = use_signal(|| None);
+
+    rsx! {
+        div { class: "scene-controls",
+            input {
+                r#type: "text",
+                placeholder: "Scene name",
+                value: "{name}",
+                oninput: move |e| name.set(e.value()),
+            }
+            button {
+                onclick: move |_| {
+                    let capture = SceneCapture {
+                        name: name(),
+                        universes: universes().unwrap_or_default(),
+                        fixtures: vec![],
+                    };
+                    spawn(async move {
+                        match utils::fetch_post::<SceneId, _>("/runtime/scenes/capture", capture).await {
+                            Ok(_) => name.set(String::new()),
+                            Err(e) => log::error!("Failed to capture scene: {e:?}"),
+                        }
+                    });
+                },
+                "Capture"
+            }
+            p { "Fade" }
+            input {
+                r#type: "number",
+                min: 0,
+                step: 0.1,
+                value: fade(),
+                oninput: move |e| {
+                    if let Ok(v) = e.value().parse::<f64>() {
+                        fade.set(v.max(0.0));
+                    }
+                }
+            }
+            button {
+                class: "release",
+                onclick: move |_| {
+                    spawn(async move {
+                        let _ = utils::fetch_post::<String, _>("/runtime/scenes/release", ()).await;
+                    });
+                },
+                "Release"
+            }
+        }
+        match scenes() {
+            Some(scenes) => rsx! {
+                div { class: "scene-list",
+                    for (scene_name, id) in scenes.clone() {
+                        div { class: "scene",
+                            p { {scene_name} }
+                            button {
+                                onclick: move |_| {
+                                    let recall = SceneRecall { scene: id, fade: fade() };
+                                    spawn(async move {
+                                        let _ = utils::fetch_post::<String, _>("/runtime/scenes/recall", recall).await;
+                                    });
+                                },
+                                "Go"
+                            }
+                            button {
+                                class: "delete",
+                                onclick: move |_| {
+                                    spawn(async move {
+                                        let _ = utils::fetch_post::<String, _>("/runtime/scenes/delete", id).await;
+                                    });
+                                },
+                                "Delete"
+                            }
+                        }
+                    }
+                }
+                div { class: "crossfader",
+                    select {
+                        onchange: move |e| crossfade_a.set(serde_json::from_str(&e.value()).ok()),
+                        option { value: "", "A" }
+                        for (scene_name, id) in scenes.clone() {
+                            option { value: serde_json::to_string(&id).unwrap_or_default(), {scene_name} }
+                        }
+                    }
+                    input {
+                        r#type: "range",
+                        min: 0,
+                        max: 1000,
+                        value: 0,
+                        disabled: crossfade_a().is_none() || crossfade_b().is_none(),
+                        oninput: move |e| {
+                            let (Some(from), Some(to)) = (crossfade_a(), crossfade_b()) else {
+                                return;
+                            };
+                            if let Ok(v) = e.value().parse::<f64>() {
+                                let crossfade = SceneCrossfade { from, to, position: v / 1000.0 };
+                                spawn(async move {
+                                    let _ = utils::fetch_post::<String, _>("/runtime/scenes/crossfade", crossfade).await;
+                                });
+                            }
+                        }
+                    }
+                    select {
+                        onchange: move |e| crossfade_b.set(serde_json::from_str(&e.value()).ok()),
+                        option { value: "", "B" }
+                        for (scene_name, id) in scenes {
+                            option { value: serde_json::to_string(&id).unwrap_or_default(), {scene_name} }
+                        }
+                    }
+                }
+            },
+            None => rsx! {
+                Loading {}
+            },
+        }
+    }
+}
+
+fn percent(value: u8) -> String {
+    format!("{:.0}%", value as f32 / 255.0 * 100.0)
+}
@@ -18,6 +18,7 @@ use rocket_okapi::{openapi, openapi_get_routes_spec, JsonSchema, OpenApiFromRequ
 use rocket_ws::WebSocket;
 use uuid::Uuid;
 
+use mlc_common::group::{FixtureGroup, GroupId, GroupRequest};
 use mlc_common::patched::UniverseId;
 use mlc_common::universe::FixtureUniverse;
 use mlc_common::{
@@ -27,6 +28,7 @@ use mlc_common::{
 use mlc_common::{FixtureInfo, Info};
 
 use crate::fixture::UniverseIdParam;
+use crate::runtime::effects::player::EffectPlayerHandle;
 use crate::{
     fixture::{self},
     module::Module,
@@ -322,6 +324,44 @@ async fn all_with_feature(
     Ok(Json(result))
 }
 
+/// # Groups 'get'
+/// Returns the fixture groups of the project, their fixtures in order
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Data Serving")]
+#[get("/groups")]
+async fn get_groups(project: &State<ProjectHandle>, _g: ProjectGuard) -> Json<Vec<FixtureGroup>> {
+    Json(project.get_groups().await)
+}
+
+/// # Groups 'edit'
+/// Creates, renames, refills or deletes a fixture group and returns the id of the group.
+///
+/// Only patched fixtures can be members of a group. Effects using the group are baked again.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Data Serving")]
+#[post("/groups", data = "<data>")]
+async fn edit_group(
+    project: &State<ProjectHandle>,
+    info: &State<Sender<Info>>,
+    effect_handler: &State<EffectPlayerHandle>,
+    data: Json<GroupRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<GroupId>, BadRequest<String>> {
+    project
+        .edit_group(
+            data.into_inner(),
+            info,
+            &mut effect_handler.inner().clone().cmd_sender,
+        )
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
 fn get_routes() -> (Vec<Route>, OpenApi) {
     openapi_get_routes_spec![
         gen_info,
@@ -333,7 +373,9 @@ fn get_routes() -> (Vec<Route>, OpenApi) {
         patch_fixture,
         get_universe,
         get_features_by_fixtures,
-        all_with_feature
+        all_with_feature,
+        get_groups,
+        edit_group
     ]
 }
 
@@ -1,7 +1,8 @@
 use crate::fixture::fixture_parser::units::{Brightness, RotationAngle, Unit};
 use mlc_common::config::{
     ColorIntensity, DmxRange, FixtureCapability, FixtureCapabilityCommon, FixtureChannel,
-    FixtureMode, FixtureType, Intensity, Matrix, Percentage, ValueResolution,
+    FixtureMode, FixtureType, Intensity, Matrix, Percentage, ShutterEffect, ShutterStrobe,
+    ValueResolution,
 };
 use serde_json::{Map, Value};
 use std::{collections::HashMap, ops::Range};
@@ -464,13 +465,22 @@ fn parse_detail_capability(raw_cap: &Map<String, Value>) -> Option<FixtureCapabi
                 None
             }
         }
+        "ShutterStrobe" => match raw_cap.get("shutterEffect") {
+            Some(raw_effect) => Some(FixtureCapability::ShutterStrobe(ShutterStrobe {
+                shutter_effect: serde_json::from_value(raw_effect.clone())
+                    .unwrap_or(ShutterEffect::Other),
+            })),
+            None => {
+                println!("No shutterEffect specified");
+                Some(FixtureCapability::Unimplemented)
+            }
+        },
         "Pan" => Unit::<RotationAngle>::parse(raw_cap, false).map(FixtureCapability::Pan),
         "Tilt" => Unit::<RotationAngle>::parse(raw_cap, false).map(FixtureCapability::Tilt),
         "Effect"
         | "ColorPreset"
         | "EffectDuration"
         | "EffectSpeed"
-        | "ShutterStrobe"
         | "PrismRotation"
         | "SoundSensitivity"
         | "Zoom"
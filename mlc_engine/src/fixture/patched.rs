@@ -1,85 +1,112 @@
 pub mod feature {
     use feature_tile_to_raw as to_raw;
+    use mlc_common::curve::{FeatureCurves, OutputCurve};
     use mlc_common::fixture::FaderAddress;
     use mlc_common::patched::feature::{
-        Dimmer, FeatureSetRequest, FeatureTile, FixtureFeature, PanTilt, Rgb, Rotation,
+        Dimmer, FeatureSetRequest, FeatureTile, FixtureFeature, FixtureFeatureType, PanTilt, Rgb,
+        Rotation,
     };
 
     use crate::runtime::{RuntimeData, ToFaderValue};
 
     pub trait ApplyFeature {
-        async fn apply(&self, req: FeatureSetRequest, runtime_data: &RuntimeData);
+        /// The channel values that apply the request, empty if the fixture lacks the feature
+        fn values(&self, req: FeatureSetRequest, curves: &FeatureCurves)
+            -> Vec<(FaderAddress, u8)>;
+
+        async fn apply(
+            &self,
+            req: FeatureSetRequest,
+            curves: &FeatureCurves,
+            runtime_data: &RuntimeData,
+        ) {
+            set_values(self.values(req, curves), runtime_data).await;
+        }
     }
 
     impl ApplyFeature for Vec<FixtureFeature> {
-        async fn apply(&self, req: FeatureSetRequest, runtime_data: &RuntimeData) {
+        fn values(
+            &self,
+            req: FeatureSetRequest,
+            curves: &FeatureCurves,
+        ) -> Vec<(FaderAddress, u8)> {
             match req {
-                FeatureSetRequest::Dimmer { value } => {
-                    if let Some(d) = find_dimmer(self) {
-                        update_values(&[(d.dimmer, value.take())], runtime_data).await;
+                FeatureSetRequest::Dimmer { value } => match find_dimmer(self) {
+                    Some(d) => {
+                        let curve = curves.get(FixtureFeatureType::Dimmer);
+                        tile_values(&[(d.dimmer, value.take())], curve)
                     }
-                }
-                FeatureSetRequest::White { value } => {
-                    if let Some(d) = find_white(self) {
-                        update_values(&[(d.dimmer, value.take())], runtime_data).await;
+                    None => vec![],
+                },
+                FeatureSetRequest::White { value } => match find_white(self) {
+                    Some(d) => {
+                        let curve = curves.get(FixtureFeatureType::White);
+                        tile_values(&[(d.dimmer, value.take())], curve)
                     }
-                }
-                FeatureSetRequest::Amber { value } => {
-                    if let Some(d) = find_amber(self) {
-                        update_values(&[(d.dimmer, value.take())], runtime_data).await;
+                    None => vec![],
+                },
+                FeatureSetRequest::Amber { value } => match find_amber(self) {
+                    Some(d) => {
+                        let curve = curves.get(FixtureFeatureType::Amber);
+                        tile_values(&[(d.dimmer, value.take())], curve)
                     }
-                }
-                FeatureSetRequest::Rgb { red, green, blue } => {
-                    if let Some(rgb) = find_rgb(self) {
-                        update_values(
-                            &[
-                                (rgb.red, red.take()),
-                                (rgb.blue, blue.take()),
-                                (rgb.green, green.take()),
-                            ],
-                            runtime_data,
-                        )
-                        .await;
-                    }
-                }
-                FeatureSetRequest::Rotation { value } => {
-                    if let Some(rot) = find_rotation(self) {
+                    None => vec![],
+                },
+                FeatureSetRequest::Rgb { red, green, blue } => match find_rgb(self) {
+                    Some(rgb) => tile_values(
+                        &[
+                            (rgb.red, red.take()),
+                            (rgb.blue, blue.take()),
+                            (rgb.green, green.take()),
+                        ],
+                        curves.get(FixtureFeatureType::Rgb),
+                    ),
+                    None => vec![],
+                },
+                FeatureSetRequest::Rotation { value } => match find_rotation(self) {
+                    Some(rot) => {
+                        let curve = curves.get(FixtureFeatureType::Rotation);
                         if value > 0.0 {
-                            update_values(&[(rot.cw, value.abs())], runtime_data).await;
-                        }
-                        if value < 0.0 {
-                            update_values(&[(rot.ccw, value.abs())], runtime_data).await;
+                            tile_values(&[(rot.cw, value.abs())], curve)
+                        } else if value < 0.0 {
+                            tile_values(&[(rot.ccw, value.abs())], curve)
+                        } else {
+                            vec![]
                         }
                     }
-                }
-                FeatureSetRequest::PanTilt { pan, tilt } => {
-                    if let Some(pantilt) = find_pantilt(self) {
-                        update_values(
-                            &[(pantilt.pan, pan.take()), (pantilt.tilt, tilt.take())],
-                            runtime_data,
-                        )
-                        .await;
-                    }
-                }
+                    None => vec![],
+                },
+                FeatureSetRequest::PanTilt { pan, tilt } => match find_pantilt(self) {
+                    Some(pantilt) => tile_values(
+                        &[(pantilt.pan, pan.take()), (pantilt.tilt, tilt.take())],
+                        curves.get(FixtureFeatureType::PanTilt),
+                    ),
+                    None => vec![],
+                },
                 FeatureSetRequest::GetAvailableFeatures => {
-                    eprintln!("Something is not working with your code dumb ass")
+                    eprintln!("Something is not working with your code dumb ass");
+                    vec![]
                 }
             }
         }
     }
 
-    async fn update_values(ts: &[(FeatureTile, f64)], runtime: &RuntimeData) {
+    fn tile_values(ts: &[(FeatureTile, f64)], curve: &OutputCurve) -> Vec<(FaderAddress, u8)> {
+        ts.iter()
+            .flat_map(|(tile, raw_v)| to_raw(tile, raw_v, curve))
+            .collect()
+    }
+
+    /// Sets the channel values in the programmer in one batch
+    pub async fn set_values(values: Vec<(FaderAddress, u8)>, runtime: &RuntimeData) {
         let mut universes = vec![];
         let mut channels = vec![];
-        let mut values = vec![];
-
-        for (tile, raw_v) in ts {
-            let v = to_raw(tile, raw_v);
-            for (f, v) in v {
-                universes.push(f.universe);
-                channels.push(f.address);
-                values.push(v);
-            }
+        let mut raw = vec![];
+
+        for (f, v) in values {
+            universes.push(f.universe);
+            channels.push(f.address);
+            raw.push(v);
         }
 
         if universes.is_empty() {
@@ -88,12 +115,12 @@ pub mod feature {
 
         if universes.len() == 1 {
             runtime
-                .set_value(universes[0], channels[0], values[0])
+                .set_value(universes[0], channels[0], raw[0], 0)
                 .await;
             return;
         }
 
-        runtime.set_values(universes, channels, values).await;
+        runtime.set_values(universes, channels, raw, 0).await;
     }
 
     fn find_dimmer(features: &[FixtureFeature]) -> Option<Dimmer> {
@@ -162,7 +189,13 @@ pub mod feature {
         None
     }
 
-    pub fn feature_tile_to_raw(tile: &FeatureTile, val: &f64) -> Vec<(FaderAddress, u8)> {
+    /// The DMX values of the tile for a value from 0 to 1, shaped by the curve of the feature
+    pub fn feature_tile_to_raw(
+        tile: &FeatureTile,
+        val: &f64,
+        curve: &OutputCurve,
+    ) -> Vec<(FaderAddress, u8)> {
+        let val = &curve.apply(*val);
         match tile {
             FeatureTile::Single { fader, range, .. } => {
                 let v = val.to_fader_value_range(range);
@@ -8,9 +8,15 @@ use rocket::{
 };
 
 use mlc_common::config::FixtureType;
+use mlc_common::curve::{self, CurveAssignment, CurveRequest};
+use mlc_common::defaults::{self, DefaultRequest, DefaultValue};
 use mlc_common::effect::Effect;
-use mlc_common::endpoints::EndPointConfig;
-use mlc_common::patched::UniverseId;
+use mlc_common::endpoints::{EndPointConfig, SacnSource};
+use mlc_common::group::{self, FixtureGroup, GroupId, GroupRequest};
+use mlc_common::merge_policy::{self, MergePolicy, MergePolicyRequest};
+use mlc_common::parked::{self, ParkRequest, ParkedChannel};
+use mlc_common::patched::{FixtureId, FixtureSelection, UniverseId};
+use mlc_common::scene::{self, Scene, SceneCapture, SceneId};
 use mlc_common::universe::FixtureUniverse;
 use mlc_common::{Info, ProjectDefinition, ProjectSettings};
 
@@ -41,6 +47,29 @@ pub(crate) struct ProjectI {
 
     #[serde(default)]
     pub(crate) endpoints: EndPointConfig,
+
+    /// Channels fixed to a value until they are unparked
+    #[serde(default)]
+    pub(crate) parked: Vec<ParkedChannel>,
+
+    /// Channels the playbacks merge with a policy other than the default one
+    #[serde(default)]
+    pub(crate) merge_policies: Vec<MergePolicy>,
+
+    /// The values channels fall back to instead of 0
+    #[serde(default)]
+    pub(crate) defaults: Vec<DefaultValue>,
+
+    /// Response curves of fixture features and channels
+    #[serde(default)]
+    pub(crate) curves: Vec<CurveAssignment>,
+
+    #[serde(default)]
+    pub(crate) scenes: Vec<Scene>,
+
+    /// Named fixture groups, only ever containing patched fixtures
+    #[serde(default)]
+    pub(crate) groups: Vec<FixtureGroup>,
 }
 
 #[derive(Debug, Clone)]
@@ -156,6 +185,20 @@ impl ProjectHandle {
             Err("Failed loading data")?;
         }
 
+        {
+            // Groups and parked channels can refer to fixtures that were unpatched since they were saved
+            let mut data = self.project.lock().await;
+            let patched = data.patched_fixtures();
+            group::prune(&mut data.groups, &patched);
+            let universes = data.universes.values().cloned().collect::<Vec<_>>();
+            parked::follow_patch(&mut data.parked, &universes);
+
+            // Projects from before the sACN identity get one, which is kept with the next save
+            if !data.endpoints.sacn.is_assigned() {
+                data.endpoints.sacn = SacnSource::default();
+            }
+        }
+
         runtime.adapt(self, true).await;
         let _ = effect_handler.send(EffectPlayerCmd::EffectsChanged).await;
         send!(info, Info::ProjectLoaded);
@@ -214,6 +257,7 @@ impl ProjectHandle {
                 .await
                 .is_some()
             {
+                self.patch_changed(info, runtime).await;
                 return Some(());
             }
         }
@@ -234,9 +278,10 @@ impl ProjectHandle {
             send!(info, Info::UniversesUpdated);
             runtime.adapt(self, false).await;
 
-            return self
-                .try_patch_to_universe(fixture.clone(), mode_index, new_id, info)
-                .await;
+            self.try_patch_to_universe(fixture.clone(), mode_index, new_id, info)
+                .await?;
+            self.patch_changed(info, runtime).await;
+            return Some(());
         }
 
         None
@@ -261,6 +306,28 @@ impl ProjectHandle {
         }
     }
 
+    /// Keeps the groups and parked channels in line with the patch and applies it to the output
+    async fn patch_changed(&self, info: &Sender<Info>, runtime: &RuntimeData) {
+        let (groups_changed, parked) = {
+            let mut data = self.project.lock().await;
+            let patched = data.patched_fixtures();
+            let groups_changed = group::prune(&mut data.groups, &patched);
+            let universes = data.universes.values().cloned().collect::<Vec<_>>();
+            let parked =
+                parked::follow_patch(&mut data.parked, &universes).then(|| data.parked.clone());
+            (groups_changed, parked)
+        };
+
+        if groups_changed {
+            send!(info, Info::GroupsChanged);
+        }
+        if let Some(parked) = parked {
+            runtime.set_parked(&parked).await;
+            send!(info, Info::ParkedChanged);
+        }
+        runtime.patch_changed(self).await;
+    }
+
     pub async fn get_settings(&self) -> ProjectSettings {
         let data = self.project.lock().await;
         data.settings.clone()
@@ -287,11 +354,200 @@ impl ProjectHandle {
         let data = self.project.lock().await;
         data.endpoints.clone()
     }
-    pub async fn set_endpoint_config(&self, config: EndPointConfig) {
+    pub async fn set_endpoint_config(&self, mut config: EndPointConfig) {
         let mut data = self.project.lock().await;
+        if !config.sacn.is_assigned() {
+            config.sacn = data.endpoints.sacn.clone();
+        }
         data.endpoints = config;
     }
 
+    /// The patched fixtures of the selection, in order
+    pub async fn selection_fixtures(
+        &self,
+        selection: &FixtureSelection,
+    ) -> Result<Vec<FixtureId>, String> {
+        let data = self.project.lock().await;
+        let patched = data.patched_fixtures();
+        let fixtures = match selection {
+            FixtureSelection::Fixtures(fixtures) => fixtures.clone(),
+            FixtureSelection::Group(id) => data
+                .groups
+                .iter()
+                .find(|g| g.id == *id)
+                .map(|g| g.fixtures.clone())
+                .ok_or_else(|| format!("Group {id} does not exist"))?,
+        };
+        Ok(fixtures
+            .into_iter()
+            .filter(|f| patched.contains(f))
+            .collect())
+    }
+
+    pub async fn get_groups(&self) -> Vec<FixtureGroup> {
+        let data = self.project.lock().await;
+        data.groups.clone()
+    }
+
+    /// Creates, changes or deletes a group and rebakes the effects using it
+    pub async fn edit_group(
+        &self,
+        request: GroupRequest,
+        info: &Sender<Info>,
+        effect_handler: &mut rocket::futures::channel::mpsc::Sender<EffectPlayerCmd>,
+    ) -> Result<GroupId, String> {
+        let id = {
+            let mut data = self.project.lock().await;
+            let patched = data.patched_fixtures();
+            group::apply(&mut data.groups, request, &patched)?
+        };
+
+        let _ = effect_handler.send(EffectPlayerCmd::GroupsChanged).await;
+        send!(info, Info::GroupsChanged);
+        Ok(id)
+    }
+
+    pub async fn get_parked(&self) -> Vec<ParkedChannel> {
+        let data = self.project.lock().await;
+        data.parked.clone()
+    }
+
+    /// Parks or unparks channels and applies them to the output
+    pub async fn park(
+        &self,
+        request: ParkRequest,
+        info: &Sender<Info>,
+        runtime: &RuntimeData,
+    ) -> Result<Vec<ParkedChannel>, String> {
+        let current = runtime.initial_states().await;
+        let parked = {
+            let mut data = self.project.lock().await;
+            let universes = data.universes.values().cloned().collect::<Vec<_>>();
+            parked::park(&mut data.parked, request, &universes, &current)?;
+            data.parked.clone()
+        };
+
+        runtime.set_parked(&parked).await;
+        send!(info, Info::ParkedChanged);
+        Ok(parked)
+    }
+
+    pub async fn get_merge_policies(&self) -> Vec<MergePolicy> {
+        let data = self.project.lock().await;
+        data.merge_policies.clone()
+    }
+
+    /// Sets or resets the merge policy of a channel and applies it to the output
+    pub async fn set_merge_policy(
+        &self,
+        request: MergePolicyRequest,
+        info: &Sender<Info>,
+        runtime: &RuntimeData,
+    ) -> Result<Vec<MergePolicy>, String> {
+        let policies = {
+            let mut data = self.project.lock().await;
+            let universes = data.universes.keys().copied().collect::<Vec<_>>();
+            merge_policy::apply(&mut data.merge_policies, request, &universes)?;
+            data.merge_policies.clone()
+        };
+
+        runtime.set_merge_policies(&policies).await;
+        send!(info, Info::MergePoliciesChanged);
+        Ok(policies)
+    }
+
+    pub async fn get_defaults(&self) -> Vec<DefaultValue> {
+        let data = self.project.lock().await;
+        data.defaults.clone()
+    }
+
+    /// Sets or resets the default of a channel and applies it to the output
+    pub async fn set_default(
+        &self,
+        request: DefaultRequest,
+        info: &Sender<Info>,
+        runtime: &RuntimeData,
+    ) -> Result<Vec<DefaultValue>, String> {
+        let values = {
+            let mut data = self.project.lock().await;
+            let universes = data.universes.keys().copied().collect::<Vec<_>>();
+            defaults::apply(&mut data.defaults, request, &universes)?;
+            data.defaults.clone()
+        };
+
+        runtime.set_defaults(&values).await;
+        send!(info, Info::DefaultsChanged);
+        Ok(values)
+    }
+
+    pub async fn get_curves(&self) -> Vec<CurveAssignment> {
+        let data = self.project.lock().await;
+        data.curves.clone()
+    }
+
+    /// Assigns or removes a curve and applies the channel curves to the output.
+    /// Effects have to be baked again for feature curves to show in them.
+    pub async fn assign_curve(
+        &self,
+        request: CurveRequest,
+        info: &Sender<Info>,
+        runtime: &RuntimeData,
+    ) -> Result<Vec<CurveAssignment>, String> {
+        let curves = {
+            let mut data = self.project.lock().await;
+            curve::assign(&mut data.curves, request)?;
+            data.curves.clone()
+        };
+
+        runtime.set_curves(&curves).await;
+        send!(info, Info::CurvesChanged);
+        Ok(curves)
+    }
+
+    pub async fn get_scenes(&self) -> Vec<(String, SceneId)> {
+        let data = self.project.lock().await;
+        data.scenes.iter().map(|s| (s.name.clone(), s.id)).collect()
+    }
+
+    pub async fn get_scene(&self, id: &SceneId) -> Option<Scene> {
+        let data = self.project.lock().await;
+        data.scenes.iter().find(|s| s.id == *id).cloned()
+    }
+
+    /// Stores the current output of the selected universes and fixtures as a new scene
+    pub async fn capture_scene(
+        &self,
+        request: SceneCapture,
+        info: &Sender<Info>,
+        runtime: &RuntimeData,
+    ) -> Result<SceneId, String> {
+        let current = runtime.initial_states().await;
+        let mut data = self.project.lock().await;
+        let universes = data.universes.values().cloned().collect::<Vec<_>>();
+        let scene = Scene {
+            id: SceneId::new_v4(),
+            tiles: scene::capture(&request, &universes, &current)?,
+            name: request.name,
+        };
+        let id = scene.id;
+        data.scenes.push(scene);
+
+        send!(info, Info::ScenesChanged);
+        Ok(id)
+    }
+
+    pub async fn delete_scene(&self, id: &SceneId, info: &Sender<Info>) -> Result<(), String> {
+        let mut data = self.project.lock().await;
+        let len = data.scenes.len();
+        data.scenes.retain(|s| s.id != *id);
+        if data.scenes.len() == len {
+            return Err(format!("Scene {id} does not exist"));
+        }
+
+        send!(info, Info::ScenesChanged);
+        Ok(())
+    }
+
     pub async fn close(&self) {
         let mut l = self.lock().await;
         *l = ProjectI::default();
@@ -303,6 +559,15 @@ impl ProjectHandle {
     }
 }
 
+impl ProjectI {
+    pub(crate) fn patched_fixtures(&self) -> Vec<FixtureId> {
+        self.universes
+            .values()
+            .flat_map(|u| u.fixtures.iter().map(|f| f.id))
+            .collect()
+    }
+}
+
 impl Default for ProjectHandle {
     fn default() -> Self {
         Self {
@@ -323,6 +588,12 @@ impl Default for ProjectI {
             universes: s,
             settings: ProjectSettings { save_on_quit: true },
             endpoints: EndPointConfig::default(),
+            parked: Vec::new(),
+            merge_policies: Vec::new(),
+            defaults: Vec::new(),
+            curves: Vec::new(),
+            scenes: Vec::new(),
+            groups: Vec::new(),
             effects: Vec::new(),
             binary: false,
         }
@@ -333,10 +604,17 @@ fn get_project_dirs() -> Option<directories::ProjectDirs> {
     directories::ProjectDirs::from("de", "pixelboystm", "mlc_engine")
 }
 
-pub fn make_path(name: &str, extension: Option<&str>) -> Option<PathBuf> {
+/// The directory projects and recordings are stored in, created if it does not exist yet
+pub fn data_dir() -> Option<PathBuf> {
     get_project_dirs().map(|d| {
         let dir = d.data_dir();
         std::fs::create_dir_all(dir).unwrap();
+        dir.to_owned()
+    })
+}
+
+pub fn make_path(name: &str, extension: Option<&str>) -> Option<PathBuf> {
+    data_dir().map(|dir| {
         dir.join(if let Some(ext) = extension {
             format!("{}.{}", name, ext)
         } else {
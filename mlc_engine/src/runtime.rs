@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     str::FromStr,
     sync::Arc,
     time::Duration,
@@ -8,14 +8,16 @@ use std::{
 use rocket::{
     futures::{SinkExt, StreamExt},
     get, post,
+    response::status::BadRequest,
     serde::json::Json,
     tokio::{
         select,
         sync::{
             broadcast::{self, Receiver, Sender},
-            Mutex,
+            watch, Mutex,
         },
-        time::sleep,
+        task::JoinHandle,
+        time::{interval, sleep, Instant, MissedTickBehavior},
     },
     Shutdown, State,
 };
@@ -25,27 +27,198 @@ use rocket_okapi::{openapi, openapi_get_routes_spec};
 use rocket_ws::{Message, WebSocket};
 
 use mlc_common::config::{DmxRange, Percentage, Value, ValueResolution};
-use mlc_common::endpoints::EndPointConfig;
-use mlc_common::patched::feature::{FeatureSetRequest, FixtureFeature};
-use mlc_common::patched::{UniverseAddress, UniverseId};
+use mlc_common::curve::{channel_tables, CurveAssignment, CurveRequest, FeatureCurves};
+use mlc_common::defaults::{default_values, DefaultRequest, DefaultValue};
+use mlc_common::endpoints::{
+    ArtNetNode, EPConfigItem, EndPointConfig, EndpointStatus, FrameClock, MergeMode,
+};
+use mlc_common::fixture::FaderAddress;
+use mlc_common::highlight::HighlightRequest;
+use mlc_common::masters::{MasterRequest, Masters};
+use mlc_common::merge_policy::{policy_modes, MergePolicy, MergePolicyRequest};
+use mlc_common::parked::{parked_values, ParkRequest, ParkedChannel};
+use mlc_common::patched::feature::{FeatureSetRequest, FixtureFeature, SelectionFeatureRequest};
+use mlc_common::patched::{FixtureId, UniverseAddress, UniverseId};
+use mlc_common::rdm::{check_patch, RdmAddressRequest, RdmDevice, RdmPatchReport};
+use mlc_common::recording::{ReplayRequest, EXTENSION};
+use mlc_common::scene::{
+    self, current_tiles, SceneCapture, SceneCrossfade, SceneId, SceneRecall, SceneTile,
+};
 use mlc_common::universe::UNIVERSE_SIZE;
-use mlc_common::{FaderUpdateRequest, Info, RuntimeUpdate};
+use mlc_common::{ClientId, ClientInfo, FaderUpdateRequest, Info, RuntimeUpdate};
 
-use crate::fixture::feature::ApplyFeature;
-use crate::runtime::endpoints::CreateEndpoints;
-use crate::{data_serving::ProjectGuard, module::Module, project::ProjectHandle, send};
+use crate::fixture::feature::{self, ApplyFeature};
+use crate::runtime::endpoints::{
+    recording_path, ArtNetDiscovery, ArtNetSockets, ArtNetTimecode, CreateEndpoints,
+    EndpointHealthTable,
+};
+use crate::{
+    data_serving::ProjectGuard,
+    module::Module,
+    project::{self, ProjectHandle},
+    send,
+};
 
-use self::{effects::EffectModule, endpoints::EndpointData};
+use self::{
+    channel_map::ApplyChannelMap,
+    clients::Clients,
+    effects::{
+        player::{EffectPlayerCmd, EffectPlayerHandle},
+        EffectModule,
+    },
+    endpoints::{EndpointData, EndpointSender, Frame},
+    layers::{Layer, LayerStack, Playback, DEFAULTS_STAMP},
+    merge::InputLayer,
+    osc::OscModule,
+};
 
+mod channel_map;
+mod clients;
 pub mod effects;
 pub mod endpoints;
+mod fader_stream;
+mod highlight;
+mod layers;
+mod merge;
+mod osc;
+mod replay;
+mod scenes;
 
 #[derive(Debug)]
 struct RuntimeI {
-    universe_values: HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+    /// The source layers of every universe, the input endpoints are merged in between
+    layers: HashMap<UniverseId, LayerStack>,
+    /// Counts all writes into the source layers, so LTP can tell which layer changed a channel last
+    stamp: u64,
     //TODO: Only one Sender needed
-    end_points: HashMap<UniverseId, Vec<Sender<EndpointData>>>,
+    end_points: HashMap<UniverseId, Vec<EndpointSender>>,
+    /// Frames received by input endpoints, keyed by the id of the input
+    inputs: HashMap<UniverseId, BTreeMap<uuid::Uuid, InputLayer>>,
     sender: Sender<RuntimeUpdate>,
+    artnet: ArtNetSockets,
+    health: EndpointHealthTable,
+    /// Universes that changed since the last tick of the frame clock
+    dirty: HashSet<UniverseId>,
+    /// Scale the intensity channels of the output, the faders keep showing the unscaled values
+    masters: watch::Sender<Masters>,
+    /// The lookup tables of the channel curves of every universe
+    curves: HashMap<UniverseId, Vec<(usize, [u8; 256])>>,
+    clock: Option<JoinHandle<()>>,
+    /// The recording that is currently played back
+    replay: Option<JoinHandle<()>>,
+    /// The fade of the scene that is currently recalled
+    scene_fade: Option<JoinHandle<()>>,
+    /// The fixture that is located, its values are in the highlight layer
+    highlighted: Option<FixtureId>,
+}
+
+impl RuntimeI {
+    /// The output value of the channel, all source layers and inputs of the universe merged
+    fn merged_value(&self, universe: &UniverseId, channel: usize) -> Option<u8> {
+        self.merged_source(universe, channel)
+            .map(|(value, _)| value)
+    }
+
+    /// The output value of the channel and the layer it comes from, `None` if an input endpoint or no layer sets it
+    fn merged_source(&self, universe: &UniverseId, channel: usize) -> Option<(u8, Option<Layer>)> {
+        let stack = self.layers.get(universe)?;
+        Some(
+            stack.source(channel, |local| match self.inputs.get(universe) {
+                Some(layers) => layers.values().fold(local, |v, l| l.merge(channel, v)),
+                None => local,
+            }),
+        )
+    }
+
+    /// The channels of the universe the replay is in control of
+    fn replayed_channels(&self, universe: &UniverseId) -> Vec<usize> {
+        let replay = Layer::Playback(Playback::Replay);
+        if !self.layers.get(universe).is_some_and(|s| s.holds(replay)) {
+            return vec![];
+        }
+        (0..UNIVERSE_SIZE)
+            .filter(|c| {
+                self.merged_source(universe, *c)
+                    .is_some_and(|(_, layer)| layer == Some(replay))
+            })
+            .collect()
+    }
+
+    fn merged_values(&self, universe: &UniverseId) -> Option<[u8; UNIVERSE_SIZE]> {
+        self.layers.get(universe)?;
+        Some(std::array::from_fn(|i| {
+            self.merged_value(universe, i).unwrap_or_default()
+        }))
+    }
+
+    /// Writes a channel of a source layer, returns `false` if the universe does not exist
+    fn write(&mut self, layer: Layer, universe: UniverseId, channel: usize, value: u8) -> bool {
+        let Some(stack) = self.layers.get_mut(&universe) else {
+            return false;
+        };
+        self.stamp += 1;
+        // Touching a fader takes the channel back from LTP inputs even if the value stays the same
+        if stack.set(layer, channel, value, self.stamp) || layer == Layer::Programmer {
+            self.release_inputs(&universe, channel);
+        }
+        true
+    }
+
+    /// Replaces all values of a source layer in the universe, `None` releases the channel
+    fn replace(
+        &mut self,
+        layer: Layer,
+        universe: UniverseId,
+        values: &[Option<u8>; UNIVERSE_SIZE],
+    ) {
+        let Some(before) = self.merged_values(&universe) else {
+            return;
+        };
+        self.stamp += 1;
+        let stamp = self.stamp;
+        let changed = self
+            .layers
+            .get_mut(&universe)
+            .expect("Merged above")
+            .replace(layer, values, stamp);
+        for channel in changed {
+            self.release_inputs(&universe, channel);
+        }
+        self.send_merge_changes(universe, &before);
+    }
+
+    fn release_inputs(&mut self, universe: &UniverseId, channel: usize) {
+        if let Some(layers) = self.inputs.get_mut(universe) {
+            for l in layers.values_mut() {
+                l.release(channel);
+            }
+        }
+    }
+
+    /// Sends every channel whose merged value differs from `before` to the ui and marks the universe for output
+    fn send_merge_changes(&mut self, universe: UniverseId, before: &[u8; UNIVERSE_SIZE]) {
+        let Some(after) = self.merged_values(&universe) else {
+            return;
+        };
+
+        let changed = (0..UNIVERSE_SIZE)
+            .filter(|i| before[*i] != after[*i])
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return;
+        }
+
+        send!(
+            self.sender,
+            RuntimeUpdate::ValuesUpdated {
+                universes: vec![universe; changed.len()],
+                values: changed.iter().map(|i| after[*i]).collect(),
+                channel_indexes: changed,
+                author: 0,
+            }
+        );
+        self.dirty.insert(universe);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -54,29 +227,69 @@ pub struct RuntimeData {
 }
 
 impl RuntimeData {
-    fn new(sender: Sender<RuntimeUpdate>) -> RuntimeData {
+    fn new(
+        sender: Sender<RuntimeUpdate>,
+        artnet: ArtNetSockets,
+        health: EndpointHealthTable,
+    ) -> RuntimeData {
         RuntimeData {
             inner: Arc::new(Mutex::new(RuntimeI {
-                universe_values: HashMap::new(),
+                layers: HashMap::new(),
+                stamp: 0,
                 end_points: HashMap::new(),
+                inputs: HashMap::new(),
                 sender,
+                artnet,
+                health,
+                dirty: HashSet::new(),
+                masters: watch::Sender::new(Masters::default()),
+                curves: HashMap::new(),
+                clock: None,
+                replay: None,
+                scene_fade: None,
+                highlighted: None,
             })),
         }
     }
     pub async fn adapt(&self, project: &ProjectHandle, clear: bool) {
         let mut data = self.inner.lock().await;
 
+        if clear {
+            if let Some(replay) = data.replay.take() {
+                replay.abort();
+            }
+            if let Some(fade) = data.scene_fade.take() {
+                fade.abort();
+            }
+            data.highlighted = None;
+        }
+
         {
             // Adapt Universes
-            let verses = data.universe_values.clone();
-            data.universe_values.clear();
+            let parked = project.get_parked().await;
+            let defaults = project.get_defaults().await;
+            let policies = project.get_merge_policies().await;
+            let curves = project.get_curves().await;
+            let mut stacks = std::mem::take(&mut data.layers);
+            data.curves.clear();
             for universe in project.get_universes().await {
-                let values = if !clear && verses.contains_key(&universe) {
-                    *verses.get(&universe).expect("Testet")
-                } else {
-                    [0; UNIVERSE_SIZE]
+                let mut stack = match stacks.remove(&universe) {
+                    Some(stack) if !clear => stack,
+                    _ => LayerStack::default(),
                 };
-                data.universe_values.insert(universe, values);
+                stack.set_intensity(intensity_channels(universe, project).await);
+                stack.set_policies(policy_modes(&policies, universe));
+                stack.replace(
+                    Layer::Defaults,
+                    &default_values(&defaults, universe),
+                    DEFAULTS_STAMP,
+                );
+                data.stamp += 1;
+                stack.replace(Layer::Parked, &parked_values(&parked, universe), data.stamp);
+                data.layers.insert(universe, stack);
+                data.curves
+                    .insert(universe, channel_tables(&curves, universe));
+                let values = data.merged_values(&universe).expect("Inserted");
                 send!(
                     data.sender,
                     RuntimeUpdate::Universe {
@@ -91,67 +304,139 @@ impl RuntimeData {
         {
             // Adapt Endpoints
             let c = project.get_endpoint_config().await;
+            if let Some(clock) = data.clock.take() {
+                clock.abort();
+            }
             for v in data.end_points.values() {
                 for vs in v {
-                    send!(vs, EndpointData::Exit);
+                    send!(vs.tx, EndpointData::Exit);
                 }
             }
             sleep(Duration::from_millis(800)).await; // To allow port freeing
-            let t = c.create_endpoints().await;
+            data.inputs.clear();
+            data.health.reset().await;
+            let t = c.create_endpoints(&data.artnet, &data.health, self).await;
             data.end_points = t;
-            for (id, v) in &data.end_points {
-                if let Some(i) = data.universe_values.get(id) {
-                    for vs in v {
-                        send!(vs, EndpointData::Entire { values: *i });
+            let universes = data.end_points.keys().copied().collect::<Vec<_>>();
+            data.dirty.extend(universes);
+            data.clock = Some(rocket::tokio::spawn(run_clock(self.clone(), c.clock)));
+        }
+    }
+
+    /// Updates which channels playbacks merge HTP after fixtures were patched
+    pub async fn patch_changed(&self, project: &ProjectHandle) {
+        let mut data = self.inner.lock().await;
+        for (universe, stack) in data.layers.iter_mut() {
+            stack.set_intensity(intensity_channels(*universe, project).await);
+        }
+    }
+
+    /// Sends the current values of every universe to its endpoints, called on every tick of the frame clock
+    async fn tick(&self, refresh: bool) {
+        let mut data = self.inner.lock().await;
+        let dirty = std::mem::take(&mut data.dirty);
+        let masters = data.masters.borrow().clone();
+        let frames = data
+            .layers
+            .iter()
+            .filter_map(|(u, stack)| {
+                let merged = data.merged_values(u)?;
+                let mut values = merged;
+                masters.scale(u, stack.intensity_tiles(), &mut values);
+                for (channel, table) in data.curves.get(u).into_iter().flatten() {
+                    values[*channel] = table[values[*channel] as usize];
+                }
+                // Replays were recorded from the output, so the masters and curves are already in them
+                for channel in data.replayed_channels(u) {
+                    values[channel] = merged[channel];
+                }
+                // Parked channels are neither scaled by the masters nor shaped by curves
+                if let Some(parked) = stack.values(Layer::Parked) {
+                    for (v, p) in values.iter_mut().zip(parked) {
+                        if let Some(p) = p {
+                            *v = *p;
+                        }
                     }
                 }
+                Some((*u, values))
+            })
+            .collect::<HashMap<_, _>>();
+
+        for (universe, points) in &data.end_points {
+            let Some(values) = frames.get(universe) else {
+                continue;
+            };
+            for p in points {
+                let frame = match &p.channel_map {
+                    Some(map) => Frame {
+                        values: map.apply(*universe, &frames),
+                        dirty: map.is_dirty(*universe, &dirty),
+                        refresh,
+                    },
+                    None => Frame {
+                        values: *values,
+                        dirty: dirty.contains(universe),
+                        refresh,
+                    },
+                };
+                send!(p.tx, EndpointData::Frame(frame));
             }
         }
     }
 
-    pub async fn set_value(&self, universe: UniverseId, channel: UniverseAddress, value: u8) {
+    /// Sets a channel in the programmer, which stays in control of it until the programmer is released.
+    ///
+    /// The update is sent as authored by `author`, unless the output differs from the value it set,
+    /// e.g. because the channel is parked. Then the author needs the update as much as every other client.
+    pub async fn set_value(
+        &self,
+        universe: UniverseId,
+        channel: UniverseAddress,
+        value: u8,
+        author: ClientId,
+    ) {
         let mut data = self.inner.lock().await;
 
-        let values = data.universe_values.get_mut(&universe);
-        if let Some(values) = values {
-            let index: u16 = channel.into();
-            values[index as usize] = value;
+        let index: usize = channel.into();
+        if data.write(Layer::Programmer, universe, index, value) {
+            let merged = data.merged_value(&universe, index).unwrap_or(value);
             send!(
                 data.sender,
                 RuntimeUpdate::ValueUpdated {
                     universe,
-                    channel_index: index as usize,
-                    value,
+                    channel_index: index,
+                    value: merged,
+                    author: if merged == value { author } else { 0 },
                 }
             );
-            self.update_endpoints(universe, channel, value, &data.end_points)
-                .await;
+            data.dirty.insert(universe);
         } else {
             println!("No Values");
         }
     }
+    /// Sets channels in the programmer, see [`RuntimeData::set_value`]
     pub async fn set_values(
         &self,
         universes: Vec<UniverseId>,
         channels: Vec<UniverseAddress>,
         values: Vec<u8>,
+        author: ClientId,
     ) {
         let mut data = self.inner.lock().await;
 
         let mut u_u = vec![];
         let mut c_u = vec![];
         let mut v_u = vec![];
+        let mut as_set = true;
 
         for i in 0..universes.len() {
-            let values_u = data.universe_values.get_mut(&universes[i]);
-            if let Some(values_u) = values_u {
-                let index: u16 = channels[i].into();
-                values_u[index as usize] = values[i];
+            let index: usize = channels[i].into();
+            if data.write(Layer::Programmer, universes[i], index, values[i]) {
+                let merged = data.merged_value(&universes[i], index).unwrap_or(values[i]);
+                as_set &= merged == values[i];
                 u_u.push(universes[i]);
                 c_u.push(channels[i]);
-                v_u.push(values[i]);
-                // self.update_endpoints(universe, channel, value, &data.end_points)
-                //     .await;
+                v_u.push(merged);
             }
         }
 
@@ -160,92 +445,311 @@ impl RuntimeData {
             RuntimeUpdate::ValuesUpdated {
                 universes: u_u.clone(),
                 channel_indexes: c_u.iter().map(|i| (*i).into()).collect(),
-                values: v_u.clone()
+                values: v_u.clone(),
+                author: if as_set { author } else { 0 },
             }
         );
-        self.update_endpoints_batch(u_u, c_u, v_u, &data.end_points)
-            .await;
+        data.dirty.extend(u_u);
+    }
+
+    pub async fn subscribe(&self) -> Receiver<RuntimeUpdate> {
+        let data = self.inner.lock().await;
+
+        data.sender.subscribe()
+    }
+
+    pub async fn initial_states(&self) -> HashMap<UniverseId, [u8; UNIVERSE_SIZE]> {
+        let data = self.inner.lock().await;
+        data.layers
+            .keys()
+            .filter_map(|u| data.merged_values(u).map(|v| (*u, v)))
+            .collect()
+    }
+    pub async fn get_universe_values(&self, universe: &UniverseId) -> Option<[u8; UNIVERSE_SIZE]> {
+        let data = self.inner.lock().await;
+        data.merged_values(universe)
+    }
+
+    /// Merges a frame received by the input endpoint `input` into `universe`
+    pub async fn input_frame(
+        &self,
+        universe: UniverseId,
+        input: uuid::Uuid,
+        mode: MergeMode,
+        local_priority: u8,
+        values: &[u8; UNIVERSE_SIZE],
+        priority: u8,
+    ) {
+        let mut data = self.inner.lock().await;
+        let Some(before) = data.merged_values(&universe) else {
+            return;
+        };
+
+        data.inputs
+            .entry(universe)
+            .or_default()
+            .entry(input)
+            .or_insert_with(|| InputLayer::new(mode, local_priority))
+            .update(values, priority);
+        data.send_merge_changes(universe, &before);
+    }
+
+    /// Plays a frame of a recording into the replay layer of the universe
+    async fn replay_frame(&self, universe: UniverseId, values: &[u8; UNIVERSE_SIZE]) {
+        let mut data = self.inner.lock().await;
+        data.replace(
+            Layer::Playback(Playback::Replay),
+            universe,
+            &values.map(Some),
+        );
+    }
+
+    /// Replaces the values of a playback in all universes, channels it no longer sends are released
+    pub async fn set_playback(&self, playback: Playback, values: &HashMap<FaderAddress, u8>) {
+        self.set_layer(Layer::Playback(playback), values).await;
     }
 
-    async fn update_endpoints(
+    /// Shows the values of a located fixture above everything but the parked channels.
+    /// `None` ends the highlight and every fixture returns to what is below it.
+    pub async fn set_highlight(
         &self,
-        verse_id: UniverseId,
-        index: UniverseAddress,
-        value: u8,
-        endpoints: &HashMap<UniverseId, Vec<Sender<EndpointData>>>,
+        fixture: Option<FixtureId>,
+        values: &HashMap<FaderAddress, u8>,
     ) {
-        let point = endpoints.get(&verse_id);
-        if let Some(point) = point {
-            for v in point {
-                send!(
-                    v,
-                    EndpointData::Single {
-                        channel: index,
-                        value
-                    }
-                );
+        self.set_layer(Layer::Highlight, values).await;
+        self.inner.lock().await.highlighted = fixture;
+    }
+
+    pub async fn highlighted(&self) -> Option<FixtureId> {
+        self.inner.lock().await.highlighted
+    }
+
+    async fn set_layer(&self, layer: Layer, values: &HashMap<FaderAddress, u8>) {
+        let mut frames = HashMap::new();
+        for (fader, value) in values {
+            frames
+                .entry(fader.universe)
+                .or_insert([None; UNIVERSE_SIZE])[usize::from(fader.address)] = Some(*value);
+        }
+
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            match frames.get(&universe) {
+                Some(frame) => data.replace(layer, universe, frame),
+                None if data.layers[&universe].holds(layer) => {
+                    data.replace(layer, universe, &[None; UNIVERSE_SIZE])
+                }
+                None => {}
             }
         }
     }
 
-    async fn update_endpoints_batch(
-        &self,
-        verse_ids: Vec<UniverseId>,
-        indexs: Vec<UniverseAddress>,
-        values: Vec<u8>,
-        endpoints: &HashMap<UniverseId, Vec<Sender<EndpointData>>>,
-    ) {
-        let mut map: HashMap<UniverseId, Vec<(UniverseAddress, u8)>> = HashMap::new();
-        for (i, verse_id) in verse_ids.into_iter().enumerate() {
-            if let Entry::Vacant(_) = map.entry(verse_id) {
-                map.insert(verse_id, vec![(indexs[i], values[i])]);
-            } else {
-                map.get_mut(&verse_id)
-                    .expect("Checked")
-                    .push((indexs[i], values[i]));
+    /// Releases a source layer in all universes, so the layers below show again
+    pub async fn release(&self, layer: Layer) {
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            let Some(before) = data.merged_values(&universe) else {
+                continue;
+            };
+            if data
+                .layers
+                .get_mut(&universe)
+                .expect("Listed above")
+                .release(layer)
+            {
+                data.send_merge_changes(universe, &before);
             }
         }
-        for verse_id in map.keys() {
-            let point = endpoints.get(verse_id);
-            if let Some(point) = point {
-                let cs: Vec<_> = map
-                    .get(verse_id)
-                    .expect("Checked")
-                    .iter()
-                    .map(|i| i.0)
-                    .collect();
-                let vs: Vec<_> = map
-                    .get(verse_id)
-                    .expect("Checked")
-                    .iter()
-                    .map(|i| i.1)
-                    .collect();
-                for v in point {
-                    send!(
-                        v,
-                        EndpointData::Multiple {
-                            channels: cs.clone(),
-                            values: vs.clone()
-                        }
-                    );
+    }
+
+    /// Replaces the parked channels of every universe
+    pub async fn set_parked(&self, parked: &[ParkedChannel]) {
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            data.replace(Layer::Parked, universe, &parked_values(parked, universe));
+        }
+    }
+
+    /// Replaces the defaults of every universe, the input endpoints keep the channels they hold
+    pub async fn set_defaults(&self, defaults: &[DefaultValue]) {
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            let before = data.merged_values(&universe).expect("Universe exists");
+            data.layers
+                .get_mut(&universe)
+                .expect("Universe exists")
+                .replace(
+                    Layer::Defaults,
+                    &default_values(defaults, universe),
+                    DEFAULTS_STAMP,
+                );
+            data.send_merge_changes(universe, &before);
+        }
+    }
+
+    /// Replaces the merge policies of every universe
+    pub async fn set_merge_policies(&self, policies: &[MergePolicy]) {
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            let before = data.merged_values(&universe).expect("Universe exists");
+            data.layers
+                .get_mut(&universe)
+                .expect("Universe exists")
+                .set_policies(policy_modes(policies, universe));
+            data.send_merge_changes(universe, &before);
+        }
+    }
+
+    /// Replaces the channel curves of every universe
+    pub async fn set_curves(&self, curves: &[CurveAssignment]) {
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in &universes {
+            data.curves
+                .insert(*universe, channel_tables(curves, *universe));
+        }
+        data.dirty.extend(universes);
+    }
+
+    pub async fn masters(&self) -> Masters {
+        self.inner.lock().await.masters.borrow().clone()
+    }
+
+    pub async fn subscribe_masters(&self) -> watch::Receiver<Masters> {
+        self.inner.lock().await.masters.subscribe()
+    }
+
+    /// Changes one of the masters and returns all of them.
+    /// Fails for masters of universes that do not exist.
+    pub async fn set_master(&self, request: MasterRequest) -> Result<Masters, String> {
+        let mut data = self.inner.lock().await;
+        if let MasterRequest::Universe { universe, .. } = &request {
+            if !data.layers.contains_key(universe) {
+                return Err(format!("Universe {} does not exist", universe.0));
+            }
+        }
+
+        data.masters.send_modify(|m| m.apply(request));
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        data.dirty.extend(universes);
+        let masters = data.masters.borrow().clone();
+        Ok(masters)
+    }
+
+    /// Starts playing back the recording, a replay that is still running is stopped
+    pub async fn start_replay(&self, name: &str, looped: bool) -> Result<(), String> {
+        let path = recording_path(name).ok_or(format!("Invalid recording name {name}"))?;
+        let frames = rocket::tokio::task::spawn_blocking(move || replay::load(&path))
+            .await
+            .map_err(|e| e.to_string())??;
+
+        let mut data = self.inner.lock().await;
+        if let Some(replay) = data.replay.take() {
+            replay.abort();
+        }
+        data.replay = Some(rocket::tokio::spawn(replay::play(
+            self.clone(),
+            frames,
+            looped,
+        )));
+        Ok(())
+    }
+
+    /// Stops the current replay, the universes fall back to what is below the replay
+    pub async fn stop_replay(&self) {
+        if let Some(replay) = self.inner.lock().await.replay.take() {
+            replay.abort();
+        }
+        self.release(Layer::Playback(Playback::Replay)).await;
+    }
+
+    /// Shows the tiles in the scene layer, channels they do not hold are released
+    async fn show_scene(&self, tiles: &[SceneTile]) {
+        let layer = Layer::Playback(Playback::Scenes);
+        let frames = scene::frames(tiles);
+        let mut data = self.inner.lock().await;
+        let universes = data.layers.keys().copied().collect::<Vec<_>>();
+        for universe in universes {
+            match frames.get(&universe) {
+                Some(frame) => data.replace(layer, universe, frame),
+                None if data.layers[&universe].holds(layer) => {
+                    data.replace(layer, universe, &[None; UNIVERSE_SIZE])
                 }
+                None => {}
             }
         }
     }
 
-    pub async fn subscribe(&self) -> Receiver<RuntimeUpdate> {
-        let data = self.inner.lock().await;
+    /// Fades from the current output to the tiles, replacing the scene that is shown
+    pub async fn recall_scene(&self, tiles: Vec<SceneTile>, fade: Duration) {
+        if fade.is_zero() {
+            self.stop_scene_fade().await;
+            self.show_scene(&tiles).await;
+            return;
+        }
 
-        data.sender.subscribe()
+        // The fade that is running keeps going until the new one replaces it, so the output does not jump meanwhile
+        let from = current_tiles(&tiles, &self.initial_states().await);
+        let handle = rocket::tokio::spawn(scenes::fade(self.clone(), from, tiles, fade));
+        self.replace_scene_fade(Some(handle)).await;
     }
 
-    pub async fn initial_states(&self) -> HashMap<UniverseId, [u8; UNIVERSE_SIZE]> {
-        let data = self.inner.lock().await;
-        data.universe_values.clone()
+    /// Shows the mix of two scenes at the position of a crossfader
+    pub async fn crossfade_scenes(&self, from: &[SceneTile], to: &[SceneTile], position: f64) {
+        self.stop_scene_fade().await;
+        self.show_scene(&scene::fade(from, to, position)).await;
     }
-    pub async fn get_universe_values(&self, universe: &UniverseId) -> Option<[u8; UNIVERSE_SIZE]> {
-        let data = self.inner.lock().await;
-        data.universe_values.get(universe).copied()
+
+    /// Releases the scene that is shown, the universes fall back to what is below it
+    pub async fn release_scene(&self) {
+        self.stop_scene_fade().await;
+        self.release(Layer::Playback(Playback::Scenes)).await;
+    }
+
+    async fn stop_scene_fade(&self) {
+        self.replace_scene_fade(None).await;
+    }
+
+    /// Swaps the running scene fade and aborts the one it replaced, so concurrent recalls never leave a fade running
+    async fn replace_scene_fade(&self, fade: Option<JoinHandle<()>>) {
+        let replaced = std::mem::replace(&mut self.inner.lock().await.scene_fade, fade);
+        if let Some(replaced) = replaced {
+            replaced.abort();
+        }
+    }
+
+    /// Removes the frame of an input that stopped receiving, the universe falls back to its local values
+    pub async fn input_lost(&self, universe: UniverseId, input: uuid::Uuid) {
+        let mut data = self.inner.lock().await;
+        let Some(before) = data.merged_values(&universe) else {
+            return;
+        };
+
+        if let Some(layers) = data.inputs.get_mut(&universe) {
+            if layers.remove(&input).is_some() {
+                data.send_merge_changes(universe, &before);
+            }
+        }
+    }
+}
+
+/// The single clock all DMX output is sent on, so the output rate does not depend on how many values change
+async fn run_clock(runtime: RuntimeData, clock: FrameClock) {
+    let mut ticker = interval(clock.period());
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut last_refresh = Instant::now();
+    loop {
+        ticker.tick().await;
+        let refresh = last_refresh.elapsed() >= clock.keep_alive_duration();
+        if refresh {
+            last_refresh = Instant::now();
+        }
+        runtime.tick(refresh).await;
     }
 }
 
@@ -258,21 +762,68 @@ impl Module for RuntimeModule {
         spec: &mut OpenApi,
     ) -> rocket::Rocket<rocket::Build> {
         let (tx, rx) = broadcast::channel::<RuntimeUpdate>(512);
+        let info = app
+            .state::<Sender<Info>>()
+            .expect("Info sender is managed by the MainModule")
+            .clone();
+        let artnet = ArtNetDiscovery::new(info.clone());
+        let health = EndpointHealthTable::new(info);
+        let timecode = ArtNetTimecode::default();
+        let sockets = ArtNetSockets::new(artnet.clone(), timecode.clone());
 
         let (routes, s) = openapi_get_routes_spec![
             get_value_updates,
             set_value,
+            get_clients,
             get_endpoint_config,
             set_endpoint_config,
-            set_feature
+            set_feature,
+            set_selection_features,
+            get_feature_values,
+            get_artnet_nodes,
+            discover_artnet_nodes,
+            get_rdm_devices,
+            discover_rdm_devices,
+            set_rdm_address,
+            check_rdm_patch,
+            get_endpoint_states,
+            get_recordings,
+            start_replay,
+            stop_replay,
+            release_programmer,
+            get_scenes,
+            capture_scene,
+            delete_scene,
+            recall_scene,
+            crossfade_scenes,
+            release_scene,
+            get_highlight,
+            set_highlight,
+            get_parked,
+            park,
+            get_merge_policies,
+            set_merge_policy,
+            get_defaults,
+            set_default,
+            get_curves,
+            set_curve,
+            get_masters,
+            set_master,
+            masters_ws
         ];
         merge_specs(spec, &"/runtime".to_string(), &s).expect("Failed merging OpenApi");
 
         let app = app
             .manage(rx)
-            .manage(RuntimeData::new(tx))
+            .manage(Clients::default())
+            .manage(artnet.clone())
+            .manage(health.clone())
+            .manage(timecode.clone())
+            .manage(sockets.clone())
+            .manage(RuntimeData::new(tx, sockets, health))
             .mount("/runtime", routes);
-        EffectModule.setup(app, spec)
+        let app = EffectModule.setup(app, spec);
+        OscModule.setup(app, spec)
     }
 }
 
@@ -281,45 +832,80 @@ impl Module for RuntimeModule {
 ///
 /// Send a UniverseId to get an exclusive update of that specified Universe.
 ///
+/// Connect with `?binary=true` to receive CBOR encoded [`mlc_common::fader_stream::FaderFrame`]s instead:
+/// per universe sequence numbers, delta frames of the changed channels and periodic keyframes to resync with.
+///
+/// The first message is always a JSON [`RuntimeUpdate::Connected`] with the id of the client.
+/// Pass it to `/fader-values/set` to author changes and connect with `?echo=false` to not receive them back.
+/// `name` is shown in the list of connected clients.
+///
 /// [Guarded][`ProjectGuard`]
 #[openapi(tag = "Runtime")]
-#[get("/fader-values/get")]
-async fn get_value_updates(
-    runtime: &State<RuntimeData>,
+#[get("/fader-values/get?<binary>&<echo>&<name>")]
+#[allow(clippy::too_many_arguments)]
+async fn get_value_updates<'a>(
+    runtime: &'a State<RuntimeData>,
+    clients: &'a State<Clients>,
     ws: WebSocket,
     mut shutdown: Shutdown,
+    binary: Option<bool>,
+    echo: Option<bool>,
+    name: Option<String>,
     _g: ProjectGuard,
-) -> rocket_ws::Channel<'_> {
+) -> rocket_ws::Channel<'a> {
+    let client = clients.connect(name);
+    let echo = echo.unwrap_or(true);
+    let connected = Message::text(
+        serde_json::to_string(&RuntimeUpdate::Connected { client: client.id }).unwrap(),
+    );
+
+    if binary.unwrap_or_default() {
+        return ws.channel(move |mut stream| {
+            Box::pin(async move {
+                stream.send(connected).await?;
+                fader_stream::binary(runtime, stream, shutdown, &client, echo).await
+            })
+        });
+    }
+
     let mut rx = runtime.subscribe().await;
     let init = runtime.initial_states().await;
 
     ws.channel(move |mut stream| {
         Box::pin(async move {
+            stream.send(connected).await?;
             for key in init.keys() {
-                stream.send(rocket_ws::Message::text(serde_json::to_string(&RuntimeUpdate::Universe { universe: *key, values: *init.get(key).expect("In for each"), author: 0 }).unwrap())).await.unwrap();
+                stream.send(rocket_ws::Message::text(serde_json::to_string(&RuntimeUpdate::Universe { universe: *key, values: *init.get(key).expect("In for each"), author: 0 }).unwrap())).await?;
             }
 
             loop {
                 select! {
                     Ok(msg) = rx.recv() => {
-                        let _ = stream.send(rocket_ws::Message::text(serde_json::to_string(&msg).unwrap())).await;
+                        if !echo && msg.author() == client.id {
+                            continue;
+                        }
+                        if stream.send(rocket_ws::Message::text(serde_json::to_string(&msg).unwrap())).await.is_err() {
+                            break;
+                        }
                     },
-                    Some(msg) = stream.next() => {
-                    if let Ok(msg) = msg {
+                    msg = stream.next() => match msg {
+                        Some(Ok(msg)) if !msg.is_close() => {
                             if let Some(req) = decode_msg(&msg) {
                                 let data = runtime.get_universe_values(&req).await;
                                 if let Some(data) = data {
-                                    stream.send(rocket_ws::Message::text(serde_json::to_string(&RuntimeUpdate::Universe { universe: req, values: data, author: 0 }).unwrap())).await.unwrap();
+                                    stream.send(rocket_ws::Message::text(serde_json::to_string(&RuntimeUpdate::Universe { universe: req, values: data, author: 0 }).unwrap())).await?;
                                 }
                             }
-                    }
-                },
+                        }
+                        _ => break,
+                    },
                     _ = &mut shutdown => {
                         break;
                     }
                 }
             }
 
+            drop(client);
             Ok(())
         })
     })
@@ -342,39 +928,58 @@ fn decode_msg<'a, T: serde::Deserialize<'a>>(msg: &'a Message) -> Option<T> {
 /// # Fader values 'set'
 /// Send [`mlc_common::FaderUpdateRequest`] to set one or multiple Dmx Channels directly
 ///
+/// The changes are authored by `client`, the id received from `/fader-values/get`.
+/// Without it the connection is listed as a client of its own.
+///
 /// [Guarded][`ProjectGuard`]
 #[openapi(tag = "Runtime")]
-#[get("/fader-values/set")]
-async fn set_value(
-    runtime: &State<RuntimeData>,
+#[get("/fader-values/set?<client>")]
+async fn set_value<'a>(
+    runtime: &'a State<RuntimeData>,
+    clients: &'a State<Clients>,
     ws: WebSocket,
     mut shutdown: Shutdown,
+    client: Option<ClientId>,
     _g: ProjectGuard,
-) -> rocket_ws::Channel<'_> {
+) -> rocket_ws::Channel<'a> {
     let rd = runtime;
+    let own = client.is_none().then(|| clients.connect(None));
+    let author = client.or(own.as_ref().map(|c| c.id)).unwrap_or_default();
 
     ws.channel(move |mut stream| {
         Box::pin(async move {
             loop {
                 select! {
-                    Some(msg) = stream.next() => {
-                    if let Ok(msg) = msg {
+                    msg = stream.next() => match msg {
+                        Some(Ok(msg)) if !msg.is_close() => {
                             if let Some(req) = decode_msg::<FaderUpdateRequest>(&msg) {
-                                rd.set_value(req.universe, req.channel, req.value).await;
+                                rd.set_value(req.universe, req.channel, req.value, author).await;
                             }
-                    }
-                },
+                        }
+                        _ => break,
+                    },
                     _ = &mut shutdown => {
                         break;
                     },
                 }
             }
 
+            drop(own);
             Ok(())
         })
     })
 }
 
+/// # Clients
+/// The clients connected to the fader value websockets
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/clients")]
+async fn get_clients(clients: &State<Clients>, _g: ProjectGuard) -> Json<Vec<ClientInfo>> {
+    Json(clients.list())
+}
+
 /// # Endpoints get
 /// Returns the current [EndpointConfig][`mlc_common::endpoints::EndPointConfig`] of the project
 ///
@@ -408,50 +1013,615 @@ async fn set_endpoint_config(
     Json("ok")
 }
 
-/// # Set Feature
-/// Opens a WebSocket to a specific patched fixture. To manually control its features.
+/// # Art-Net nodes
+/// Returns all Art-Net nodes that answered an ArtPoll recently
 ///
-/// See [FeatureSetRequest][`mlc_common::patched::feature::FeatureSetRequest`]
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/artnet/nodes")]
+async fn get_artnet_nodes(
+    artnet: &State<ArtNetDiscovery>,
+    _g: ProjectGuard,
+) -> Json<Vec<ArtNetNode>> {
+    Json(artnet.nodes().await)
+}
+
+/// # Art-Net discover
+/// Sends an ArtPoll and waits a short while for replies before returning the updated node list.
+///
+/// The poll goes out through the socket the Art-Net endpoints share, if one is running.
 ///
 /// [Guarded][`ProjectGuard`]
 #[openapi(tag = "Runtime")]
-#[get("/feature/<fix_id>")]
-async fn set_feature<'a>(
-    ws: WebSocket,
-    mut shutdown: Shutdown,
-    fix_id: &'a str,
-    runtime: &'a State<RuntimeData>,
-    project: &'a State<ProjectHandle>,
+#[get("/artnet/discover")]
+async fn discover_artnet_nodes(
+    sockets: &State<ArtNetSockets>,
     _g: ProjectGuard,
-) -> rocket_ws::Channel<'a> {
-    let id = uuid::Uuid::from_str(fix_id);
+) -> Json<Vec<ArtNetNode>> {
+    if let Err(e) = sockets.discover(None).await {
+        eprintln!("[ARTNET] {e}");
+    }
+    Json(sockets.discovery().nodes().await)
+}
 
-    async fn get_features(id: uuid::Uuid, project: &ProjectHandle) -> Option<Vec<FixtureFeature>> {
-        let universes = project.get_universes().await;
-        for universe in universes {
-            let u = project.get_universe(&universe).await.expect("Queried");
-            let fs = &u.fixtures;
-            for f in fs {
-                if f.id == id {
-                    return Some(f.features.clone());
-                }
+/// # RDM devices
+/// Returns the RDM devices found by the last RDM discovery
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/artnet/rdm/devices")]
+async fn get_rdm_devices(
+    artnet: &State<ArtNetDiscovery>,
+    _g: ProjectGuard,
+) -> Json<Vec<RdmDevice>> {
+    Json(artnet.rdm().devices().await)
+}
+
+/// # RDM discover
+/// Requests the table of devices from the nodes of every running Art-Net endpoint
+/// and reads manufacturer, model, start address and personality of every device.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/artnet/rdm/discover")]
+async fn discover_rdm_devices(
+    artnet: &State<ArtNetDiscovery>,
+    _g: ProjectGuard,
+) -> Result<Json<Vec<RdmDevice>>, BadRequest<String>> {
+    artnet.rdm().discover().await.map_err(BadRequest)?;
+    Ok(Json(artnet.rdm().devices().await))
+}
+
+/// # RDM start address
+/// Sets the DMX start address of a discovered RDM device
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/artnet/rdm/address", data = "<data>")]
+async fn set_rdm_address(
+    artnet: &State<ArtNetDiscovery>,
+    data: Json<RdmAddressRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<RdmDevice>, BadRequest<String>> {
+    artnet
+        .rdm()
+        .set_start_address(data.uid, data.address)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # RDM patch check
+/// Compares the discovered RDM devices to the fixtures patched in the universes their Art-Net ports output.
+/// Lists devices without a fixture, with a different model or footprint and fixtures without a device.
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/artnet/rdm/patch")]
+async fn check_rdm_patch(
+    artnet: &State<ArtNetDiscovery>,
+    project: &State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> Json<RdmPatchReport> {
+    let mut outputs = HashMap::new();
+    for (universe, items) in project.get_endpoint_config().await.endpoints {
+        for item in items {
+            if let EPConfigItem::ArtNet { port_address, .. } = item {
+                outputs.insert(port_address, universe);
             }
         }
+    }
 
-        None
+    let mut universes = vec![];
+    for id in project.get_universes().await {
+        if let Ok(u) = project.get_universe(&id).await {
+            universes.push(u);
+        }
     }
 
-    ws.channel(move |mut stream| {
-        Box::pin(async move {
-            if let Ok(id) = id {
-                if let Some(fs) = get_features(id, project).await {
-                    let r = (runtime.inner()).clone();
-                    loop {
-                        select! {
-                            pot_msg = stream.next() => {
-                                if let Some(msg) = pot_msg {
-                                    if let Ok(msg) = msg {
-                                        if msg.is_close() {
+    let devices = artnet.rdm().devices().await;
+    Json(check_patch(&devices, &outputs, &universes))
+}
+
+/// # Endpoint states
+/// Returns the state of every endpoint of the current endpoint config,
+/// including the last error, how often a failed endpoint was restarted
+/// and what it read back from its device, like the serial number and parameters of a DMX USB Pro widget.
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/endpoints/status")]
+async fn get_endpoint_states(
+    health: &State<EndpointHealthTable>,
+    _g: ProjectGuard,
+) -> Json<Vec<EndpointStatus>> {
+    Json(health.states().await)
+}
+
+/// # Recordings
+/// Returns the names of all recordings made by recorder endpoints
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/recordings")]
+async fn get_recordings(_g: ProjectGuard) -> Json<Vec<String>> {
+    let Some(dir) = project::data_dir() else {
+        return Json(vec![]);
+    };
+
+    let mut recordings = std::fs::read_dir(dir)
+        .map(|iter| {
+            iter.flatten()
+                .map(|f| f.path())
+                .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == EXTENSION))
+                .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    recordings.sort();
+    Json(recordings)
+}
+
+/// # Replay start
+/// Plays a recording back into the universes it was recorded from, with its original timing.
+/// The replayed frames play in a playback layer under the programmer. They were recorded from the output,
+/// so the masters and channel curves are not applied to them a second time. A running replay is stopped first.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/replay/start", data = "<data>")]
+async fn start_replay(
+    runtime: &State<RuntimeData>,
+    data: Json<ReplayRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<&'static str>, BadRequest<String>> {
+    runtime
+        .start_replay(&data.file, data.looped)
+        .await
+        .map_err(BadRequest)?;
+    Ok(Json("ok"))
+}
+
+/// # Replay stop
+/// Stops the current replay, the universes fall back to what is below the replay
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/replay/stop")]
+async fn stop_replay(runtime: &State<RuntimeData>, _g: ProjectGuard) -> Json<&'static str> {
+    runtime.stop_replay().await;
+    Json("ok")
+}
+
+/// # Programmer release
+/// Releases all channels set through faders and fixture features, so playbacks and defaults show again
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/programmer/release")]
+async fn release_programmer(runtime: &State<RuntimeData>, _g: ProjectGuard) -> Json<&'static str> {
+    runtime.release(Layer::Programmer).await;
+    Json("ok")
+}
+
+/// # Scenes 'get'
+/// The names and ids of all scenes
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/scenes")]
+async fn get_scenes(
+    project: &State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> Json<Vec<(String, SceneId)>> {
+    Json(project.get_scenes().await)
+}
+
+/// # Scene capture
+/// Stores the current output of the selected universes and fixtures as a new scene and returns its id.
+/// Fine channels are stored with their coarse channel, so recalling the scene fades them smoothly.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/scenes/capture", data = "<data>")]
+async fn capture_scene(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    info: &State<Sender<Info>>,
+    data: Json<SceneCapture>,
+    _g: ProjectGuard,
+) -> Result<Json<SceneId>, BadRequest<String>> {
+    project
+        .capture_scene(data.into_inner(), info, runtime)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Scene delete
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/scenes/delete", data = "<data>")]
+async fn delete_scene(
+    project: &State<ProjectHandle>,
+    info: &State<Sender<Info>>,
+    data: Json<SceneId>,
+    _g: ProjectGuard,
+) -> Result<Json<&'static str>, BadRequest<String>> {
+    project
+        .delete_scene(&data, info)
+        .await
+        .map_err(BadRequest)?;
+    Ok(Json("ok"))
+}
+
+/// # Scene recall
+/// Fades from the current output to the scene within the fade time. The scene replaces the one shown before.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/scenes/recall", data = "<data>")]
+async fn recall_scene(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    data: Json<SceneRecall>,
+    _g: ProjectGuard,
+) -> Result<Json<&'static str>, BadRequest<String>> {
+    let scene = project
+        .get_scene(&data.scene)
+        .await
+        .ok_or(BadRequest(format!("Scene {} does not exist", data.scene)))?;
+    let fade = Duration::try_from_secs_f64(data.fade)
+        .map_err(|_| BadRequest(format!("Invalid fade time {}", data.fade)))?;
+    runtime.recall_scene(scene.tiles, fade).await;
+    Ok(Json("ok"))
+}
+
+/// # Scene crossfade
+/// Shows the mix of two scenes at the position of a manual crossfader
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/scenes/crossfade", data = "<data>")]
+async fn crossfade_scenes(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    data: Json<SceneCrossfade>,
+    _g: ProjectGuard,
+) -> Result<Json<&'static str>, BadRequest<String>> {
+    let mut scenes = vec![];
+    for id in [data.from, data.to] {
+        let scene = project
+            .get_scene(&id)
+            .await
+            .ok_or(BadRequest(format!("Scene {id} does not exist")))?;
+        scenes.push(scene.tiles);
+    }
+    runtime
+        .crossfade_scenes(&scenes[0], &scenes[1], data.position)
+        .await;
+    Ok(Json("ok"))
+}
+
+/// # Scene release
+/// Releases the scene that is shown, the universes fall back to what is below it
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/scenes/release")]
+async fn release_scene(runtime: &State<RuntimeData>, _g: ProjectGuard) -> Json<&'static str> {
+    runtime.release_scene().await;
+    Json("ok")
+}
+
+/// # Highlight 'get'
+/// The fixture that is located, `None` if no fixture is highlighted
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/highlight")]
+async fn get_highlight(runtime: &State<RuntimeData>, _g: ProjectGuard) -> Json<Option<FixtureId>> {
+    Json(runtime.highlighted().await)
+}
+
+/// # Highlight 'set'
+/// Locates a fixture, steps through the members of a group or ends the highlight.
+/// Returns the fixture that is highlighted now.
+///
+/// The fixture shines at full intensity in open white with pan and tilt centered, every other fixture is dimmed.
+/// Only parked channels stay in control. Ending the highlight returns every fixture to its previous state.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/highlight", data = "<data>")]
+async fn set_highlight(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    info: &State<Sender<Info>>,
+    data: Json<HighlightRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Option<FixtureId>>, BadRequest<String>> {
+    highlight::apply(data.into_inner(), project, runtime, info)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Parked channels 'get'
+/// The channels that are parked at a fixed value. Nothing but unparking changes them,
+/// they are not scaled by the masters either.
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/parked")]
+async fn get_parked(project: &State<ProjectHandle>, _g: ProjectGuard) -> Json<Vec<ParkedChannel>> {
+    Json(project.get_parked().await)
+}
+
+/// # Parked channels 'set'
+/// Parks or unparks a channel or all channels of a fixture and returns all parked channels.
+/// The parked channels are saved with the project.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/parked", data = "<data>")]
+async fn park(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    info: &State<Sender<Info>>,
+    data: Json<ParkRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Vec<ParkedChannel>>, BadRequest<String>> {
+    project
+        .park(data.into_inner(), info, runtime)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Merge policies 'get'
+/// The channels the playbacks merge with a policy of their own
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/merge-policies")]
+async fn get_merge_policies(
+    project: &State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> Json<Vec<MergePolicy>> {
+    Json(project.get_merge_policies().await)
+}
+
+/// # Merge policies 'set'
+/// Sets or resets the policy the playbacks merge a channel with and returns all merge policies.
+/// Channels without a policy merge HTP for intensity and LTP for everything else.
+/// The merge policies are saved with the project.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/merge-policies", data = "<data>")]
+async fn set_merge_policy(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    info: &State<Sender<Info>>,
+    data: Json<MergePolicyRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Vec<MergePolicy>>, BadRequest<String>> {
+    project
+        .set_merge_policy(data.into_inner(), info, runtime)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Defaults 'get'
+/// The values channels fall back to when nothing else holds them
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/defaults")]
+async fn get_defaults(project: &State<ProjectHandle>, _g: ProjectGuard) -> Json<Vec<DefaultValue>> {
+    Json(project.get_defaults().await)
+}
+
+/// # Defaults 'set'
+/// Sets or resets the value a channel falls back to, like the home position of pan and tilt, and returns all defaults.
+/// Channels without a default fall back to 0. The defaults are saved with the project.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/defaults", data = "<data>")]
+async fn set_default(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    info: &State<Sender<Info>>,
+    data: Json<DefaultRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Vec<DefaultValue>>, BadRequest<String>> {
+    project
+        .set_default(data.into_inner(), info, runtime)
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Curves 'get'
+/// The response curves assigned to fixture features and channels
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/curves")]
+async fn get_curves(
+    project: &State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> Json<Vec<CurveAssignment>> {
+    Json(project.get_curves().await)
+}
+
+/// # Curves 'set'
+/// Assigns a response curve to a fixture feature or a channel, or removes it, and returns all curves.
+///
+/// Feature curves shape the value of the feature before it is mapped onto its channels,
+/// for direct control and in effects. Channel curves shape the output of the channel.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/curves", data = "<data>")]
+async fn set_curve(
+    project: &State<ProjectHandle>,
+    runtime: &State<RuntimeData>,
+    effect_player: &State<EffectPlayerHandle>,
+    info: &State<Sender<Info>>,
+    data: Json<CurveRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Vec<CurveAssignment>>, BadRequest<String>> {
+    let curves = project
+        .assign_curve(data.into_inner(), info, runtime)
+        .await
+        .map_err(BadRequest)?;
+    let _ = effect_player
+        .cmd_sender
+        .clone()
+        .send(EffectPlayerCmd::CurvesChanged)
+        .await;
+    Ok(Json(curves))
+}
+
+/// # Masters 'get'
+/// The grand master, the blackout and the masters of single universes
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/masters")]
+async fn get_masters(runtime: &State<RuntimeData>, _g: ProjectGuard) -> Json<Masters> {
+    Json(runtime.masters().await)
+}
+
+/// # Masters 'set'
+/// Changes the grand master, the blackout or the master of a universe and returns all masters.
+/// Only the intensity channels of the output are scaled.
+///
+/// On Failure: BadRequest is returned with an error String
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[post("/masters", data = "<data>")]
+async fn set_master(
+    runtime: &State<RuntimeData>,
+    data: Json<MasterRequest>,
+    _g: ProjectGuard,
+) -> Result<Json<Masters>, BadRequest<String>> {
+    runtime
+        .set_master(data.into_inner())
+        .await
+        .map(Json)
+        .map_err(BadRequest)
+}
+
+/// # Masters WebSocket
+/// Upgrades to a WebSocket which receives the [`Masters`] on connect and whenever one of them changes.
+///
+/// Send a [`MasterRequest`] to change a master.
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/masters/ws")]
+async fn masters_ws(
+    runtime: &State<RuntimeData>,
+    ws: WebSocket,
+    mut shutdown: Shutdown,
+    _g: ProjectGuard,
+) -> rocket_ws::Channel<'_> {
+    let mut rx = runtime.subscribe_masters().await;
+
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            let masters = rx.borrow_and_update().clone();
+            let _ = stream.send(rocket_ws::Message::text(serde_json::to_string(&masters).unwrap())).await;
+
+            loop {
+                select! {
+                    Ok(()) = rx.changed() => {
+                        let masters = rx.borrow_and_update().clone();
+                        let _ = stream.send(rocket_ws::Message::text(serde_json::to_string(&masters).unwrap())).await;
+                    },
+                    msg = stream.next() => {
+                        match msg {
+                            Some(Ok(msg)) if !msg.is_close() => {
+                                if let Some(req) = decode_msg::<MasterRequest>(&msg) {
+                                    if let Err(e) = runtime.set_master(req).await {
+                                        eprintln!("Failed setting master: {e}");
+                                    }
+                                }
+                            }
+                            _ => break,
+                        }
+                    },
+                    _ = &mut shutdown => {
+                        break;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// # Set Feature
+/// Opens a WebSocket to a specific patched fixture. To manually control its features.
+///
+/// See [FeatureSetRequest][`mlc_common::patched::feature::FeatureSetRequest`]
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/feature/<fix_id>")]
+async fn set_feature<'a>(
+    ws: WebSocket,
+    mut shutdown: Shutdown,
+    fix_id: &'a str,
+    runtime: &'a State<RuntimeData>,
+    project: &'a State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> rocket_ws::Channel<'a> {
+    let id = uuid::Uuid::from_str(fix_id);
+
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            if let Ok(id) = id {
+                if let Some(fs) = fixture_features(id, project).await {
+                    let r = (runtime.inner()).clone();
+                    loop {
+                        select! {
+                            pot_msg = stream.next() => {
+                                if let Some(msg) = pot_msg {
+                                    if let Ok(msg) = msg {
+                                        if msg.is_close() {
                                             break;
                                         }
 
@@ -459,7 +1629,8 @@ async fn set_feature<'a>(
                                             if let FeatureSetRequest::GetAvailableFeatures = fsr {
                                                 stream.send(rocket_ws::Message::text(serde_json::to_string(&fs.iter().map(|s| s.name()).collect::<Vec<_>>()).unwrap())).await.unwrap();
                                             } else {
-                                                fs.apply(fsr, &r).await;
+                                                let curves = FeatureCurves::of(&project.get_curves().await, id);
+                                                fs.apply(fsr, &curves, &r).await;
                                             }
                                         }
                                     }
@@ -479,6 +1650,209 @@ async fn set_feature<'a>(
     })
 }
 
+/// # Set Features of a selection
+/// Opens a WebSocket to control the features of many fixtures at once, applied to all of them in one batch.
+///
+/// See [SelectionFeatureRequest][`mlc_common::patched::feature::SelectionFeatureRequest`]
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/features")]
+async fn set_selection_features<'a>(
+    ws: WebSocket,
+    mut shutdown: Shutdown,
+    runtime: &'a State<RuntimeData>,
+    project: &'a State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> rocket_ws::Channel<'a> {
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            loop {
+                select! {
+                    msg = stream.next() => match msg {
+                        Some(Ok(msg)) if !msg.is_close() => {
+                            if let Some(req) = decode_msg(&msg) {
+                                if let Err(e) = apply_selection(req, project, runtime).await {
+                                    eprintln!("Failed to set features of selection: {e}");
+                                }
+                            }
+                        }
+                        _ => break,
+                    },
+                    _ = &mut shutdown => break,
+                }
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// Applies the request to every fixture of the selection and sets all values in one batch
+async fn apply_selection(
+    request: SelectionFeatureRequest,
+    project: &ProjectHandle,
+    runtime: &RuntimeData,
+) -> Result<(), String> {
+    let fixtures = project.selection_fixtures(&request.selection).await?;
+    let requests = request.spread(fixtures.len())?;
+    let curves = project.get_curves().await;
+
+    let mut values = vec![];
+    for (id, req) in fixtures.into_iter().zip(requests) {
+        let features = fixture_features(id, project)
+            .await
+            .ok_or_else(|| format!("Fixture {id} is not patched"))?;
+        values.extend(features.values(req, &FeatureCurves::of(&curves, id)));
+    }
+    feature::set_values(values, runtime).await;
+    Ok(())
+}
+
+/// How often the read back feature values are sent at most
+const FEATURE_READBACK_INTERVAL: Duration = Duration::from_millis(40);
+
+/// # Feature values
+/// Opens a WebSocket that streams the current values of the features of a patched fixture, read back from the output.
+///
+/// Sends a list of [FeatureSetRequest][`mlc_common::patched::feature::FeatureSetRequest`]s, one per feature,
+/// on connect and whenever the values change.
+///
+/// [Guarded][`ProjectGuard`]
+#[openapi(tag = "Runtime")]
+#[get("/feature/<fix_id>/values")]
+async fn get_feature_values<'a>(
+    ws: WebSocket,
+    mut shutdown: Shutdown,
+    fix_id: &'a str,
+    runtime: &'a State<RuntimeData>,
+    project: &'a State<ProjectHandle>,
+    _g: ProjectGuard,
+) -> rocket_ws::Channel<'a> {
+    let id = uuid::Uuid::from_str(fix_id);
+
+    ws.channel(move |mut stream| {
+        Box::pin(async move {
+            let Ok(id) = id else {
+                return Ok(());
+            };
+            let Some(features) = fixture_features(id, project).await else {
+                return Ok(());
+            };
+            let universes = features
+                .iter()
+                .flat_map(|f| f.tiles())
+                .flat_map(|t| t.faders())
+                .map(|f| f.universe)
+                .collect::<HashSet<_>>();
+
+            let mut rx = runtime.subscribe().await;
+            let mut flush = interval(FEATURE_READBACK_INTERVAL);
+            flush.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            let mut dirty = true;
+            let mut last = None;
+            loop {
+                select! {
+                    _ = flush.tick(), if dirty => {
+                        dirty = false;
+                        let mut output = HashMap::new();
+                        for u in &universes {
+                            if let Some(values) = runtime.get_universe_values(u).await {
+                                output.insert(*u, values);
+                            }
+                        }
+                        let curves = FeatureCurves::of(&project.get_curves().await, id);
+                        let values = features
+                            .iter()
+                            .filter_map(|f| f.read(&output, &curves))
+                            .collect::<Vec<_>>();
+                        if last.as_ref() != Some(&values) {
+                            stream.send(Message::text(serde_json::to_string(&values).unwrap())).await?;
+                            last = Some(values);
+                        }
+                    }
+                    update = rx.recv() => match update {
+                        Ok(update) => dirty |= update.universes().iter().any(|u| universes.contains(u)),
+                        Err(broadcast::error::RecvError::Lagged(_)) => dirty = true,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    msg = stream.next() => match msg {
+                        Some(Ok(msg)) if !msg.is_close() => {}
+                        _ => break,
+                    },
+                    _ = &mut shutdown => break,
+                }
+            }
+
+            Ok(())
+        })
+    })
+}
+
+/// The features of the patched fixture with the id
+async fn fixture_features(id: uuid::Uuid, project: &ProjectHandle) -> Option<Vec<FixtureFeature>> {
+    let universes = project.get_universes().await;
+    for universe in universes {
+        let u = project.get_universe(&universe).await.expect("Queried");
+        let fs = &u.fixtures;
+        for f in fs {
+            if f.id == id {
+                return Some(f.features.clone());
+            }
+        }
+    }
+
+    None
+}
+
+/// The channels of the universe that control the intensity of a fixture, grouped by tile with the coarse channel first.
+///
+/// That is the dimmer of every fixture, or the color channels of fixtures without one.
+/// Playbacks merge them HTP and the masters scale them.
+async fn intensity_channels(universe: UniverseId, project: &ProjectHandle) -> Vec<Vec<usize>> {
+    let Ok(u) = project.get_universe(&universe).await else {
+        return vec![];
+    };
+
+    u.fixtures
+        .iter()
+        .flat_map(|f| {
+            let dimmers = f
+                .features
+                .iter()
+                .filter_map(|f| match f {
+                    FixtureFeature::Dimmer(d) => Some(&d.dimmer),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            if !dimmers.is_empty() {
+                return dimmers;
+            }
+
+            f.features
+                .iter()
+                .filter(|f| {
+                    matches!(
+                        f,
+                        FixtureFeature::Rgb(_)
+                            | FixtureFeature::White(_)
+                            | FixtureFeature::Amber(_)
+                    )
+                })
+                .flat_map(FixtureFeature::tiles)
+                .collect()
+        })
+        .map(|tile| {
+            tile.faders()
+                .into_iter()
+                .filter(|f| f.universe == universe)
+                .map(|f| f.address.into())
+                .collect::<Vec<usize>>()
+        })
+        .filter(|tile| !tile.is_empty())
+        .collect()
+}
+
 pub trait ToFaderValue {
     fn to_fader_value_range(&self, range: &DmxRange) -> u8;
     fn to_fader_value_range_fine(&self, range: &DmxRange) -> (u8, u8);
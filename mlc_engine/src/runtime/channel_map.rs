@@ -0,0 +1,109 @@
+use std::collections::{HashMap, HashSet};
+
+use mlc_common::endpoints::ChannelMap;
+use mlc_common::patched::UniverseId;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+pub trait ApplyChannelMap {
+    /// The output of an endpoint of `universe`, `frames` holds the values of every universe
+    fn apply(
+        &self,
+        universe: UniverseId,
+        frames: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+    ) -> [u8; UNIVERSE_SIZE];
+
+    /// Whether any universe the output is taken from changed
+    fn is_dirty(&self, universe: UniverseId, dirty: &HashSet<UniverseId>) -> bool;
+}
+
+impl ApplyChannelMap for ChannelMap {
+    fn apply(
+        &self,
+        universe: UniverseId,
+        frames: &HashMap<UniverseId, [u8; UNIVERSE_SIZE]>,
+    ) -> [u8; UNIVERSE_SIZE] {
+        let mut out = [0; UNIVERSE_SIZE];
+        for p in &self.patches {
+            let Some(values) = frames.get(&p.universe.unwrap_or(universe)) else {
+                continue;
+            };
+
+            let from = (p.from as usize).min(UNIVERSE_SIZE);
+            let to = (p.to as usize).min(UNIVERSE_SIZE);
+            let length = (p.length as usize)
+                .min(UNIVERSE_SIZE - from)
+                .min(UNIVERSE_SIZE - to);
+            out[to..to + length].copy_from_slice(&values[from..from + length]);
+        }
+        out
+    }
+
+    fn is_dirty(&self, universe: UniverseId, dirty: &HashSet<UniverseId>) -> bool {
+        self.patches
+            .iter()
+            .any(|p| dirty.contains(&p.universe.unwrap_or(universe)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mlc_common::endpoints::ChannelPatch;
+
+    use super::*;
+
+    fn frames() -> HashMap<UniverseId, [u8; UNIVERSE_SIZE]> {
+        let mut a = [0; UNIVERSE_SIZE];
+        let mut b = [0; UNIVERSE_SIZE];
+        for i in 0..UNIVERSE_SIZE {
+            a[i] = i as u8;
+            b[i] = 255 - i as u8;
+        }
+        HashMap::from([(UniverseId(1), a), (UniverseId(2), b)])
+    }
+
+    #[test]
+    fn offset_and_drop() {
+        let map = ChannelMap {
+            patches: vec![ChannelPatch {
+                universe: None,
+                from: 0,
+                to: 100,
+                length: 10,
+            }],
+        };
+        let out = map.apply(UniverseId(1), &frames());
+
+        assert_eq!(&out[100..110], &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(out[99], 0);
+        assert_eq!(out[110], 0);
+        assert_eq!(out[5], 0);
+    }
+
+    #[test]
+    fn merge_universes() {
+        let map = ChannelMap {
+            patches: vec![
+                ChannelPatch {
+                    universe: None,
+                    from: 0,
+                    to: 0,
+                    length: 256,
+                },
+                ChannelPatch {
+                    universe: Some(UniverseId(2)),
+                    from: 0,
+                    to: 256,
+                    length: 512,
+                },
+            ],
+        };
+        let out = map.apply(UniverseId(1), &frames());
+
+        assert_eq!(out[255], 255);
+        assert_eq!(out[256], 255);
+        assert_eq!(out[511], 0);
+
+        assert!(map.is_dirty(UniverseId(1), &HashSet::from([UniverseId(2)])));
+        assert!(!map.is_dirty(UniverseId(1), &HashSet::from([UniverseId(3)])));
+    }
+}
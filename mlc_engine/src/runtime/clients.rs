@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Local;
+
+use mlc_common::{ClientId, ClientInfo};
+
+/// The clients connected to the fader value websockets
+#[derive(Debug, Clone, Default)]
+pub struct Clients {
+    inner: Arc<Mutex<ClientsI>>,
+}
+
+#[derive(Debug, Default)]
+struct ClientsI {
+    last: ClientId,
+    connected: BTreeMap<ClientId, ClientInfo>,
+}
+
+impl Clients {
+    /// Registers a new client, it is listed until the returned [`Client`] is dropped
+    pub fn connect(&self, name: Option<String>) -> Client {
+        let mut data = self.inner.lock().expect("Not poisoned");
+        data.last += 1;
+        let id = data.last;
+        data.connected.insert(
+            id,
+            ClientInfo {
+                id,
+                name,
+                connected: Local::now(),
+            },
+        );
+
+        Client {
+            id,
+            clients: self.clone(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<ClientInfo> {
+        let data = self.inner.lock().expect("Not poisoned");
+        data.connected.values().cloned().collect()
+    }
+}
+
+/// A connected client, disconnects when dropped
+#[derive(Debug)]
+pub struct Client {
+    pub id: ClientId,
+    clients: Clients,
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        if let Ok(mut data) = self.clients.inner.lock() {
+            data.connected.remove(&self.id);
+        }
+    }
+}
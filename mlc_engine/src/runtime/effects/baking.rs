@@ -9,16 +9,27 @@ use rocket::tokio::task::JoinHandle;
 use tap::Tap;
 
 use feature_tile_to_raw as to_raw;
+use mlc_common::curve::{CurveAssignment, FeatureCurves, OutputCurve};
 use mlc_common::easing::{Easing, EasingType};
 use mlc_common::effect::*;
 use mlc_common::fixture::FaderAddress;
+use mlc_common::group::FixtureGroup;
 use mlc_common::patched::feature::FixtureFeature;
 use mlc_common::patched::PatchedFixture;
 
 use crate::fixture::feature::feature_tile_to_raw;
 
 pub type BakedEffectCue = Vec<(Duration, u8)>;
-pub type BakedFixtureData = Vec<PatchedFixture>;
+
+/// What effects are baked against
+#[derive(Debug, Clone, Default)]
+pub struct BakedFixtureData {
+    pub fixtures: Vec<PatchedFixture>,
+    /// The curves of the features of the fixtures
+    pub curves: Vec<CurveAssignment>,
+    /// The groups feature tracks can refer to
+    pub groups: Vec<FixtureGroup>,
+}
 
 pub enum BakingRequest {
     Bake(Effect),
@@ -32,7 +43,7 @@ pub struct EffectBaker {
     pub join_handle: JoinHandle<()>,
 }
 
-pub fn startup_effect_baker(patched_fixtures: Vec<PatchedFixture>) -> EffectBaker {
+pub fn startup_effect_baker(patched_fixtures: BakedFixtureData) -> EffectBaker {
     let (task_sender, mut task_receiver) = mpsc::unbounded::<BakingRequest>();
     let (mut effect_sender, effect_recv) = mpsc::unbounded::<(EffectId, BakedEffect)>();
 
@@ -64,10 +75,14 @@ pub struct BakedEffect {
     pub(super) faders: HashMap<FaderAddress, Vec<(Duration, u8)>>,
     pub(super) max_time: Duration,
     pub(super) looping: bool,
+    pub(super) timecode_start: Duration,
+    /// The faders of the feature tracks that form intensities, grouped by tile with the coarse fader first
+    pub(super) intensity: Vec<Vec<FaderAddress>>,
 }
 
 pub(crate) async fn bake(effect: &Effect, patched_fixtures: &BakedFixtureData) -> BakedEffect {
     let mut faders = HashMap::new();
+    let mut intensity = vec![];
 
     for track in &effect.tracks {
         match track {
@@ -80,6 +95,7 @@ pub(crate) async fn bake(effect: &Effect, patched_fixtures: &BakedFixtureData) -
                 for (address, baked_cue) in baked_cues {
                     faders.insert(address, baked_cue);
                 }
+                intensity.extend(intensity_tiles(cue, patched_fixtures));
             }
         };
     }
@@ -90,9 +106,41 @@ pub(crate) async fn bake(effect: &Effect, patched_fixtures: &BakedFixtureData) -
         faders,
         max_time: effect.duration,
         looping: effect.looping,
+        timecode_start: effect.timecode_start,
+        intensity,
     }
 }
 
+/// The tiles of the track that form intensities. Like for the masters those are the dimmers,
+/// or the colors of fixtures without one.
+fn intensity_tiles(track: &FeatureTrack, fixtures: &BakedFixtureData) -> Vec<Vec<FaderAddress>> {
+    track
+        .all_fixtures(&fixtures.groups)
+        .iter()
+        .filter_map(|id| fixtures.fixtures.iter().find(|f| f.id == *id))
+        .filter_map(|f| {
+            let feature = f
+                .features
+                .iter()
+                .find(|feat| feat.name() == track.feature)?;
+            let has_dimmer = f
+                .features
+                .iter()
+                .any(|feat| matches!(feat, FixtureFeature::Dimmer(_)));
+            let is_intensity = match feature {
+                FixtureFeature::Dimmer(_) => true,
+                FixtureFeature::Rgb(_) | FixtureFeature::White(_) | FixtureFeature::Amber(_) => {
+                    !has_dimmer
+                }
+                FixtureFeature::Rotation(_) | FixtureFeature::PanTilt(_) => false,
+            };
+            is_intensity.then(|| feature.tiles())
+        })
+        .flatten()
+        .map(|tile| tile.faders())
+        .collect()
+}
+
 fn bake_fader_cue(fader_cue: &FaderTrack, max_time: &Duration) -> BakedEffectCue {
     let mut vals: Vec<_> = fader_cue
         .values
@@ -112,30 +160,56 @@ async fn bake_feature_track(
 ) -> Vec<(FaderAddress, BakedEffectCue)> {
     let mut baked_tracks = vec![];
 
-    for f_id in &track.fixtures {
-        let patched: Option<_> = fixtures.iter().find(|u| &u.id == f_id);
+    for f_id in track.all_fixtures(&fixtures.groups) {
+        let patched: Option<_> = fixtures.fixtures.iter().find(|u| u.id == f_id);
         let mut cues = if let Some(fixture) = patched {
             let feature = fixture
                 .features
                 .iter()
                 .find(|feat| feat.name() == track.feature);
+            let curves = FeatureCurves::of(&fixtures.curves, fixture.id);
+            let curve = curves.get(track.feature);
             if let Some(feature) = feature {
                 match &track.detail {
                     FeatureTrackDetail::SinglePercent(t) => {
-                        bake_feature_track_single_percent(t, max_time, feature, &track.resolution)
-                            .await
+                        bake_feature_track_single_percent(
+                            t,
+                            max_time,
+                            feature,
+                            curve,
+                            &track.resolution,
+                        )
+                        .await
                     }
                     FeatureTrackDetail::D3Percent(t) => {
-                        bake_feature_track_three_percent(t, max_time, feature, &track.resolution)
-                            .await
+                        bake_feature_track_three_percent(
+                            t,
+                            max_time,
+                            feature,
+                            curve,
+                            &track.resolution,
+                        )
+                        .await
                     }
                     FeatureTrackDetail::SingleRotation(t) => {
-                        bake_feature_track_single_rotation(t, max_time, feature, &track.resolution)
-                            .await
+                        bake_feature_track_single_rotation(
+                            t,
+                            max_time,
+                            feature,
+                            curve,
+                            &track.resolution,
+                        )
+                        .await
                     }
                     FeatureTrackDetail::D2Rotation(t) => {
-                        bake_feature_track_d2_rotation(t, max_time, feature, &track.resolution)
-                            .await
+                        bake_feature_track_d2_rotation(
+                            t,
+                            max_time,
+                            feature,
+                            curve,
+                            &track.resolution,
+                        )
+                        .await
                     }
                 }
             } else {
@@ -159,6 +233,7 @@ async fn bake_feature_track_single_percent(
     t: &PercentTrack,
     max_time: &Duration,
     fixture_feature: &FixtureFeature,
+    curve: &OutputCurve,
     resolution: &Duration,
 ) -> Vec<(FaderAddress, BakedEffectCue)> {
     let feature_tile = match fixture_feature {
@@ -180,13 +255,16 @@ async fn bake_feature_track_single_percent(
         in_v + (out_v - in_v) * val as f32
     });
 
-    convert_to_cues::<PercentageKey, _, 1>(&time_steps, |v| [to_raw(feature_tile, &(*v as f64))])
+    convert_to_cues::<PercentageKey, _, 1>(&time_steps, |v| {
+        [to_raw(feature_tile, &(*v as f64), curve)]
+    })
 }
 
 async fn bake_feature_track_single_rotation(
     t: &RotationTrack,
     max_time: &Duration,
     fixture_feature: &FixtureFeature,
+    curve: &OutputCurve,
     resolution: &Duration,
 ) -> Vec<(FaderAddress, BakedEffectCue)> {
     let (feature_tile_cw, feature_tile_ccw) = match fixture_feature {
@@ -208,9 +286,9 @@ async fn bake_feature_track_single_rotation(
 
     convert_to_cues::<RotationKey, _, 1>(&time_steps, |v| {
         [if v >= &0.0 {
-            to_raw(feature_tile_cw, &(*v / 1.0).into())
+            to_raw(feature_tile_cw, &(*v / 1.0).into(), curve)
         } else {
-            to_raw(feature_tile_ccw, &(v.abs() / 1.0).into())
+            to_raw(feature_tile_ccw, &(v.abs() / 1.0).into(), curve)
         }]
     })
 }
@@ -219,6 +297,7 @@ async fn bake_feature_track_d2_rotation(
     t: &D2RotationTrack,
     max_time: &Duration,
     fixture_feature: &FixtureFeature,
+    curve: &OutputCurve,
     resolution: &Duration,
 ) -> Vec<(FaderAddress, BakedEffectCue)> {
     let (pan, tilt) = match fixture_feature {
@@ -249,7 +328,10 @@ async fn bake_feature_track_d2_rotation(
     );
 
     convert_to_cues::<D2RotationKey, _, 2>(&time_steps, |v| {
-        [to_raw(pan, &v.0.into()), to_raw(tilt, &v.1.into())]
+        [
+            to_raw(pan, &v.0.into(), curve),
+            to_raw(tilt, &v.1.into(), curve),
+        ]
     })
 }
 
@@ -257,6 +339,7 @@ async fn bake_feature_track_three_percent(
     t: &D3PercentTrack,
     max_time: &Duration,
     fixture_feature: &FixtureFeature,
+    curve: &OutputCurve,
     resolution: &Duration,
 ) -> Vec<(FaderAddress, BakedEffectCue)> {
     let (d1, d2, d3) = match fixture_feature {
@@ -289,9 +372,9 @@ async fn bake_feature_track_three_percent(
 
     convert_to_cues::<D3PercentageKey, _, 3>(&time_steps, |v| {
         [
-            to_raw(d1, &v.0.into()),
-            to_raw(d2, &v.1.into()),
-            to_raw(d3, &v.2.into()),
+            to_raw(d1, &v.0.into(), curve),
+            to_raw(d2, &v.1.into(), curve),
+            to_raw(d3, &v.2.into(), curve),
         ]
     })
 }
@@ -20,6 +20,7 @@ use mlc_common::Info;
 use crate::data_serving::ProjectGuard;
 use crate::project::ProjectHandle;
 use crate::runtime::effects::player::EffectPlayerUpdate;
+use crate::runtime::endpoints::ArtNetTimecode;
 use crate::{module::Module, send};
 
 use self::player::{startup_effect_player, EffectPlayerCmd, EffectPlayerHandle};
@@ -43,6 +44,7 @@ impl Module for EffectModule {
         let effect_player = startup_effect_player(
             app.state::<ProjectHandle>().unwrap().clone(),
             app.state::<RuntimeData>().unwrap().clone(),
+            app.state::<ArtNetTimecode>().unwrap().subscribe(),
         )
         .block_on();
 
@@ -160,6 +162,10 @@ async fn get_effect_handler<'a>(
 /// # Get Effect Player
 /// Upgrades to a WebSocket connection on which communication with the Effect Player is possible.
 ///
+/// Besides starting and stopping effects, running effects can be paused, resumed, seeked,
+/// played at a different rate and have their intensity features scaled.
+/// Their playback is reported with [`EffectPlayerMsg::EffectStates`].
+///
 /// [Guarded][`ProjectGuard`]
 #[openapi(tag = "Effects")]
 #[get("/effectPlayer")]
@@ -178,6 +184,7 @@ async fn get_effect_player(
                         let msg = match msg {
                             EffectPlayerUpdate::PlayingEffects(effects) => EffectPlayerMsg::PlayingEffects{effects},
                             EffectPlayerUpdate::EffectProgresses(updates) => EffectPlayerMsg::EffectProgresses(updates), 
+                            EffectPlayerUpdate::EffectStates(states) => EffectPlayerMsg::EffectStates(states),
                         };
 
                         let _ = stream.send(make_msg(&msg)).await;
@@ -186,10 +193,16 @@ async fn get_effect_player(
                     Some(msg) = stream.next() => {
                         if let Ok(msg) = msg {
                             if let Some(req) = decode_msg::<EffectPlayerRequest>(&msg){
-                                match req {
-                                    EffectPlayerRequest::Play { effect } => {let _ = effect_player.cmd_sender.send(EffectPlayerCmd::Play { id: effect }).await;},
-                                    EffectPlayerRequest::Stop { effect } => {let _ = effect_player.cmd_sender.send(EffectPlayerCmd::Stop  { id: effect }).await;},
-                                }
+                                let cmd = match req {
+                                    EffectPlayerRequest::Play { effect } => EffectPlayerCmd::Play { id: effect },
+                                    EffectPlayerRequest::Stop { effect } => EffectPlayerCmd::Stop { id: effect },
+                                    EffectPlayerRequest::Pause { effect } => EffectPlayerCmd::Pause { id: effect },
+                                    EffectPlayerRequest::Resume { effect } => EffectPlayerCmd::Resume { id: effect },
+                                    EffectPlayerRequest::Seek { effect, position } => EffectPlayerCmd::Seek { id: effect, position },
+                                    EffectPlayerRequest::SetRate { effect, rate } => EffectPlayerCmd::SetRate { id: effect, rate },
+                                    EffectPlayerRequest::SetIntensity { effect, intensity } => EffectPlayerCmd::SetIntensity { id: effect, intensity: intensity.take() },
+                                };
+                                let _ = effect_player.cmd_sender.send(cmd).await;
                             }
                         }
                     }
@@ -224,6 +237,7 @@ async fn handle_msg(
                 duration: Duration::seconds(5),
                 tracks: vec![],
                 looping: false,
+                timecode_start: Duration::zero(),
             });
             let _ = stream
                 .send(make_msg(&EffectHandlerResponse::EffectCreated { name, id }))
@@ -234,6 +248,7 @@ async fn handle_msg(
             tracks,
             looping,
             duration,
+            timecode_start,
         } => {
             let mut p = project.lock().await;
             let effect = p.effects.iter_mut().find(|f| f.id == id);
@@ -241,6 +256,7 @@ async fn handle_msg(
                 effect.tracks = tracks;
                 effect.looping = looping;
                 effect.duration = duration;
+                effect.timecode_start = timecode_start;
             }
             // let _ = stream
             //     .send(make_msg(&EffectHandlerResponse::EffectUpdated { id }))
@@ -1,6 +1,11 @@
 use chrono::Duration;
 use mlc_common::{
-    effect::EffectId,
+    effect::{
+        player::{PlayingEffect, SeekPosition},
+        EffectId,
+    },
+    fixture::FaderAddress,
+    masters::scale_tile,
     utils::{
         bounds::{One, Zero},
         BoundedValue,
@@ -14,7 +19,10 @@ use rocket::{
     },
     tokio::{
         select,
-        sync::broadcast::{self, Receiver as BReceiver, Sender as BSender},
+        sync::{
+            broadcast::{self, Receiver as BReceiver, Sender as BSender},
+            watch,
+        },
         time::{interval, Interval},
     },
 };
@@ -22,7 +30,7 @@ use std::{collections::HashMap, future::IntoFuture};
 
 use crate::{
     project::{ProjectHandle, ProjectI},
-    runtime::RuntimeData,
+    runtime::{endpoints::TimecodeChase, layers::Playback, RuntimeData},
 };
 
 use super::baking::{self, BakedEffect, BakedFixtureData, EffectBaker};
@@ -43,36 +51,86 @@ impl Clone for EffectPlayerHandle {
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum EffectPlayerCmd {
-    Play { id: EffectId },
-    Stop { id: EffectId },
-    EffectChanged { id: EffectId },
+    Play {
+        id: EffectId,
+    },
+    Stop {
+        id: EffectId,
+    },
+    EffectChanged {
+        id: EffectId,
+    },
     EffectsChanged,
     StopPlayer,
     GetPlayingEffects,
+    CurvesChanged,
+    GroupsChanged,
+    Pause {
+        id: EffectId,
+    },
+    Resume {
+        id: EffectId,
+    },
+    Seek {
+        id: EffectId,
+        position: SeekPosition,
+    },
+    SetRate {
+        id: EffectId,
+        rate: f64,
+    },
+    SetIntensity {
+        id: EffectId,
+        intensity: f64,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum EffectPlayerUpdate {
     PlayingEffects(Vec<EffectId>),
     EffectProgresses(Vec<(EffectId, BoundedValue<f32, Zero, One>)>),
+    EffectStates(Vec<PlayingEffect>),
+}
+
+/// A running effect and how it is played
+#[derive(Debug, Clone, Copy)]
+struct Running {
+    time: Duration,
+    paused: bool,
+    rate: f64,
+    /// Scales the intensity tiles of the effect
+    intensity: f64,
+}
+
+impl Default for Running {
+    fn default() -> Self {
+        Running {
+            time: Duration::zero(),
+            paused: false,
+            rate: 1.0,
+            intensity: 1.0,
+        }
+    }
 }
 
 struct EffectPlayer {
     project: ProjectHandle,
     baking_map: HashMap<EffectId, BakingStatus>,
     baked_effects: HashMap<EffectId, BakedEffect>,
-    playing_effects: HashMap<EffectId, Duration>,
+    playing_effects: HashMap<EffectId, Running>,
     update_freq: Interval,
     cmd_receiver: Receiver<EffectPlayerCmd>,
     update_sender: BSender<EffectPlayerUpdate>,
     effect_baker: EffectBaker,
     time: chrono::NaiveTime,
     runtime: RuntimeData,
+    timecode: watch::Receiver<TimecodeChase>,
 }
 
 pub async fn startup_effect_player(
     project: ProjectHandle,
     runtime: RuntimeData,
+    timecode: watch::Receiver<TimecodeChase>,
 ) -> EffectPlayerHandle {
     let (cmd_sender, cmd_receiver) = mpsc::channel::<EffectPlayerCmd>(1024);
     let (update_sender, update_receiver) = broadcast::channel::<EffectPlayerUpdate>(1024);
@@ -92,6 +150,7 @@ pub async fn startup_effect_player(
         update_sender,
         effect_baker: baker,
         runtime,
+        timecode,
         time: chrono::Utc::now().naive_utc().time(),
         update_freq: interval(std::time::Duration::from_millis(20)), //TODO: Make available in settings
     };
@@ -144,11 +203,12 @@ impl EffectPlayer {
         let elapsed = now - self.time;
         self.time = now;
 
+        let chase = *self.timecode.borrow();
         let mut value_map = HashMap::new();
 
         let mut marked_for_stopping = vec![];
 
-        for (id, time) in &mut self.playing_effects {
+        for (id, running) in &mut self.playing_effects {
             let status = self.baking_map.get(id);
             match status {
                 Some(BakingStatus::Baked) => {}
@@ -175,7 +235,10 @@ impl EffectPlayer {
                         .send(baking::BakingRequest::Bake(effect))
                         .await;
                     self.baking_map.insert(*id, BakingStatus::InProgress);
-                    continue;
+                    // The previous bake keeps playing until the new one is done, so its channels are not released
+                    if !self.baked_effects.contains_key(id) {
+                        continue;
+                    }
                 }
                 None => {
                     eprintln!("Why has effect no status?");
@@ -185,19 +248,30 @@ impl EffectPlayer {
 
             let effect = self.baked_effects.get(id).unwrap();
 
-            *time += elapsed;
+            let time = &mut running.time;
+            if running.paused {
+                // Holds its values until resumed
+            } else if chase.enabled {
+                // Effects only move with the timecode, so they pause when it stops
+                if let Some(position) = chase.position {
+                    *time = chase_time(effect, position);
+                }
+            } else {
+                *time += scale_duration(elapsed, running.rate);
 
-            if *time > effect.max_time {
-                if effect.looping {
-                    while *time > effect.max_time {
-                        *time -= effect.max_time;
+                if *time > effect.max_time {
+                    if effect.looping && effect.max_time > Duration::zero() {
+                        while *time > effect.max_time {
+                            *time -= effect.max_time;
+                        }
+                    } else {
+                        marked_for_stopping.push(*id);
+                        continue;
                     }
-                } else {
-                    marked_for_stopping.push(*id);
-                    continue;
                 }
             }
 
+            let mut values = HashMap::new();
             for f in &effect.faders {
                 let mut value = 0;
                 for (d, v) in f.1.iter() {
@@ -205,32 +279,25 @@ impl EffectPlayer {
                         value = *v;
                     }
                 }
-                value_map.insert(*f.0, value);
+                values.insert(*f.0, value);
+            }
+            if running.intensity < 1.0 {
+                scale_intensity(&mut values, &effect.intensity, running.intensity);
             }
+            value_map.extend(values);
         }
 
         if !marked_for_stopping.is_empty() {
             for to_stop in marked_for_stopping {
                 self.playing_effects.remove(&to_stop);
             }
-            let _ = self.update_sender.send(EffectPlayerUpdate::PlayingEffects(
-                self.playing_effects.keys().cloned().collect::<Vec<_>>(),
-            ));
+            self.send_playing();
         }
 
-        let mut universes = vec![];
-        let mut channels = vec![];
-        let mut values = vec![];
-
-        for (k, v) in value_map {
-            universes.push(k.universe);
-            channels.push(k.address);
-            values.push(v);
-        }
-
-        if !universes.is_empty() {
-            self.runtime.set_values(universes, channels, values).await;
-        }
+        // Channels of effects that stopped are released with this
+        self.runtime
+            .set_playback(Playback::Effects, &value_map)
+            .await;
 
         if !self.playing_effects.is_empty() {
             let _ = self
@@ -238,14 +305,14 @@ impl EffectPlayer {
                 .send(EffectPlayerUpdate::EffectProgresses(
                     self.playing_effects
                         .iter()
-                        .map(|(e, t)| {
+                        .map(|(e, r)| {
                             (
                                 *e,
                                 BoundedValue::create(
                                     self.baked_effects
                                         .get(e)
                                         .map(|b| {
-                                            t.num_milliseconds() as f32
+                                            r.time.num_milliseconds() as f32
                                                 / b.max_time.num_milliseconds() as f32
                                         })
                                         .unwrap_or(0.0),
@@ -269,18 +336,43 @@ impl EffectPlayer {
     async fn handle_cmd(&mut self, cmd: EffectPlayerCmd, should_exit: &mut bool) {
         match cmd {
             EffectPlayerCmd::Play { id } => {
-                self.playing_effects
-                    .entry(id)
-                    .or_insert_with(Duration::zero);
-                let _ = self.update_sender.send(EffectPlayerUpdate::PlayingEffects(
-                    self.playing_effects.keys().cloned().collect::<Vec<_>>(),
-                ));
+                self.playing_effects.entry(id).or_default();
+                self.send_playing();
             }
             EffectPlayerCmd::Stop { id } => {
                 self.playing_effects.remove(&id);
-                let _ = self.update_sender.send(EffectPlayerUpdate::PlayingEffects(
-                    self.playing_effects.keys().cloned().collect::<Vec<_>>(),
-                ));
+                self.send_playing();
+            }
+            EffectPlayerCmd::Pause { id } => self.change(id, |r| r.paused = true),
+            EffectPlayerCmd::Resume { id } => self.change(id, |r| r.paused = false),
+            EffectPlayerCmd::Seek { id, position } => {
+                let duration = self
+                    .project
+                    .lock()
+                    .await
+                    .effects
+                    .iter()
+                    .find(|e| e.id == id)
+                    .map(|e| e.duration)
+                    .unwrap_or_else(Duration::zero);
+                let time = match position {
+                    SeekPosition::Seconds(s) => Duration::milliseconds((s * 1000.0).round() as i64),
+                    SeekPosition::Fraction(f) => scale_duration(duration, f.take()),
+                };
+                self.change(id, |r| r.time = time.clamp(Duration::zero(), duration));
+            }
+            // NaN would survive clamping and stall or blank the effect, so non-finite values are ignored
+            EffectPlayerCmd::SetRate { id, rate } if !rate.is_finite() => {
+                eprintln!("Ignoring rate {rate} for effect {id}");
+            }
+            EffectPlayerCmd::SetRate { id, rate } => {
+                self.change(id, |r| r.rate = rate.max(0.0));
+            }
+            EffectPlayerCmd::SetIntensity { id, intensity } if !intensity.is_finite() => {
+                eprintln!("Ignoring intensity {intensity} for effect {id}");
+            }
+            EffectPlayerCmd::SetIntensity { id, intensity } => {
+                self.change(id, |r| r.intensity = intensity.clamp(0.0, 1.0));
             }
             EffectPlayerCmd::EffectChanged { id } => {
                 if let Some(status) = self.baking_map.get_mut(&id) {
@@ -313,15 +405,47 @@ impl EffectPlayer {
                 self.sync_baking_map().await;
                 self.playing_effects.clear();
             }
-            EffectPlayerCmd::StopPlayer => *should_exit = true,
-            EffectPlayerCmd::GetPlayingEffects => {
-                let _ = self.update_sender.send(EffectPlayerUpdate::PlayingEffects(
-                    self.playing_effects.keys().cloned().collect::<Vec<_>>(),
-                ));
+            EffectPlayerCmd::CurvesChanged | EffectPlayerCmd::GroupsChanged => {
+                // Feature curves and group members are baked into the effects
+                self.sync_baking_map().await;
+                for status in self.baking_map.values_mut() {
+                    *status = BakingStatus::Changed;
+                }
             }
+            EffectPlayerCmd::StopPlayer => *should_exit = true,
+            EffectPlayerCmd::GetPlayingEffects => self.send_playing(),
         }
     }
 
+    /// Changes the playback of a running effect, effects that are not running are ignored
+    fn change(&mut self, id: EffectId, f: impl FnOnce(&mut Running)) {
+        if let Some(running) = self.playing_effects.get_mut(&id) {
+            f(running);
+            self.send_states();
+        }
+    }
+
+    fn send_playing(&self) {
+        let _ = self.update_sender.send(EffectPlayerUpdate::PlayingEffects(
+            self.playing_effects.keys().cloned().collect::<Vec<_>>(),
+        ));
+        self.send_states();
+    }
+
+    fn send_states(&self) {
+        let _ = self.update_sender.send(EffectPlayerUpdate::EffectStates(
+            self.playing_effects
+                .iter()
+                .map(|(id, r)| PlayingEffect {
+                    effect: *id,
+                    paused: r.paused,
+                    rate: r.rate,
+                    intensity: BoundedValue::create(r.intensity),
+                })
+                .collect(),
+        ));
+    }
+
     async fn sync_baking_map(&mut self) {
         let p = self.project.lock().await;
         let mut new_map = HashMap::new();
@@ -336,18 +460,57 @@ impl EffectPlayer {
         let _ = self
             .effect_baker
             .task_sender
-            .send(baking::BakingRequest::Fixtures(
-                p.universes
+            .send(baking::BakingRequest::Fixtures(BakedFixtureData {
+                fixtures: p
+                    .universes
                     .iter()
                     .flat_map(|u| u.1.fixtures.clone())
                     .collect::<Vec<_>>(),
-            ))
+                curves: p.curves.clone(),
+                groups: p.groups.clone(),
+            }))
             .await;
 
         self.baking_map = new_map;
     }
 }
 
+/// The time of the effect at a timecode position. Before its start the effect waits at the beginning. After its end a
+/// looping effect starts over and any other effect holds its last values, as the timecode may still seek back
+fn chase_time(effect: &BakedEffect, position: std::time::Duration) -> Duration {
+    let t = Duration::from_std(position).unwrap_or(Duration::zero()) - effect.timecode_start;
+    if t < Duration::zero() {
+        Duration::zero()
+    } else if effect.looping && effect.max_time > Duration::zero() {
+        Duration::milliseconds(t.num_milliseconds() % effect.max_time.num_milliseconds())
+    } else {
+        t.min(effect.max_time)
+    }
+}
+
+fn scale_duration(d: Duration, factor: f64) -> Duration {
+    Duration::microseconds((d.num_microseconds().unwrap_or(i64::MAX) as f64 * factor) as i64)
+}
+
+/// Scales the intensity tiles of an effect, fine faders together with their coarse fader
+fn scale_intensity(
+    values: &mut HashMap<FaderAddress, u8>,
+    tiles: &[Vec<FaderAddress>],
+    factor: f64,
+) {
+    for tile in tiles {
+        let Some(mut bytes) = tile
+            .iter()
+            .map(|f| values.get(f).copied())
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+        scale_tile(&mut bytes, factor);
+        values.extend(tile.iter().copied().zip(bytes));
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum BakingStatus {
     Unbaked,
@@ -367,5 +530,48 @@ fn get_patched_fixtures_clone(p: &MutexGuard<ProjectI>) -> BakedFixtureData {
         "Debug: Patched fixture clone size for baking {} bytes",
         patched_fixtures.get_heap_size()
     );
-    patched_fixtures
+    BakedFixtureData {
+        fixtures: patched_fixtures,
+        curves: p.curves.clone(),
+        groups: p.groups.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn effect(looping: bool) -> BakedEffect {
+        BakedEffect {
+            faders: HashMap::new(),
+            max_time: Duration::seconds(10),
+            looping,
+            timecode_start: Duration::seconds(60),
+            intensity: vec![],
+        }
+    }
+
+    #[test]
+    fn chase_positions() {
+        let secs = std::time::Duration::from_secs;
+
+        assert_eq!(chase_time(&effect(false), secs(30)), Duration::zero());
+        assert_eq!(chase_time(&effect(false), secs(65)), Duration::seconds(5));
+        assert_eq!(chase_time(&effect(false), secs(95)), Duration::seconds(10));
+        assert_eq!(chase_time(&effect(true), secs(95)), Duration::seconds(5));
+    }
+
+    #[test]
+    fn intensity_scales_tiles_only() {
+        let fader = |address| FaderAddress {
+            universe: mlc_common::patched::UniverseId(1),
+            address: mlc_common::patched::UniverseAddress::create(address).unwrap(),
+        };
+        let mut values = HashMap::from([(fader(0), 255), (fader(1), 0), (fader(2), 200)]);
+
+        scale_intensity(&mut values, &[vec![fader(0), fader(1)]], 0.5);
+        assert_eq!(values[&fader(0)], 127);
+        assert_eq!(values[&fader(1)], 128);
+        assert_eq!(values[&fader(2)], 200);
+    }
 }
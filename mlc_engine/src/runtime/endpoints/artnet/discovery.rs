@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use artnet_protocol::PollReply;
+use chrono::Local;
+use rocket::tokio::sync::{broadcast::Sender, Mutex};
+
+use mlc_common::endpoints::{ArtNetNode, ArtNetPortAddress};
+use mlc_common::Info;
+
+use crate::send;
+
+use super::ArtNetRdm;
+
+/// Nodes that did not answer a poll for this long are dropped from the node table
+const NODE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The table of all Art-Net nodes that answered an ArtPoll.
+///
+/// The receive loops of the Art-Net sockets feed it with the replies to their periodic polls.
+#[derive(Debug, Clone, Default)]
+pub struct ArtNetDiscovery {
+    nodes: Arc<Mutex<HashMap<(Ipv4Addr, u8), ArtNetNode>>>,
+    rdm: ArtNetRdm,
+    info: Option<Sender<Info>>,
+}
+
+impl ArtNetDiscovery {
+    pub fn new(info: Sender<Info>) -> ArtNetDiscovery {
+        ArtNetDiscovery {
+            nodes: Arc::new(Mutex::new(HashMap::new())),
+            rdm: ArtNetRdm::new(info.clone()),
+            info: Some(info),
+        }
+    }
+
+    /// The RDM devices behind the nodes
+    pub fn rdm(&self) -> &ArtNetRdm {
+        &self.rdm
+    }
+
+    /// Inserts or refreshes the node that sent the reply and returns its parsed form
+    pub async fn insert(&self, reply: &PollReply, source: SocketAddr) -> ArtNetNode {
+        let node = parse_poll_reply(reply, source);
+        let mut nodes = self.nodes.lock().await;
+
+        let changed = nodes
+            .get(&(node.ip, node.bind_index))
+            .map(|old| !same_node(old, &node))
+            .unwrap_or(true);
+        nodes.insert((node.ip, node.bind_index), node.clone());
+
+        if changed {
+            if let Some(info) = &self.info {
+                send!(info, Info::ArtNetNodesChanged);
+            }
+        }
+
+        node
+    }
+
+    pub async fn nodes(&self) -> Vec<ArtNetNode> {
+        let mut nodes = self.nodes.lock().await;
+        let before = nodes.len();
+        let now = Local::now();
+        nodes.retain(|_, n| {
+            (now - n.last_seen)
+                .to_std()
+                .map(|d| d < NODE_TIMEOUT)
+                .unwrap_or(true)
+        });
+
+        if before != nodes.len() {
+            if let Some(info) = &self.info {
+                send!(info, Info::ArtNetNodesChanged);
+            }
+        }
+
+        let mut list = nodes.values().cloned().collect::<Vec<_>>();
+        list.sort_by_key(|n| (n.ip, n.bind_index));
+        list
+    }
+}
+
+pub fn parse_poll_reply(reply: &PollReply, source: SocketAddr) -> ArtNetNode {
+    let ip = match source {
+        SocketAddr::V4(v4) => *v4.ip(),
+        SocketAddr::V6(_) => reply.address,
+    };
+
+    let net = reply.port_address[0] & 0x7f;
+    let subnet = reply.port_address[1] & 0x0f;
+    let ports = (reply.num_ports[1] as usize).min(reply.swout.len());
+    let port_addresses = (0..ports)
+        // Bit 7 of the port type marks ports that can output DMX
+        .filter(|i| reply.port_types[*i] & 0x80 != 0)
+        .map(|i| ArtNetPortAddress {
+            net,
+            subnet,
+            universe: reply.swout[i] & 0x0f,
+        })
+        .collect();
+
+    ArtNetNode {
+        ip,
+        bind_index: reply.bind_index,
+        short_name: null_terminated(&reply.short_name),
+        long_name: null_terminated(&reply.long_name),
+        node_report: null_terminated(&reply.node_report),
+        port_addresses,
+        firmware: u16::from_be_bytes(reply.version),
+        status: reply.status_1,
+        last_seen: Local::now(),
+    }
+}
+
+fn null_terminated(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+fn same_node(a: &ArtNetNode, b: &ArtNetNode) -> bool {
+    a.short_name == b.short_name
+        && a.long_name == b.long_name
+        && a.port_addresses == b.port_addresses
+        && a.firmware == b.firmware
+        && a.status == b.status
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use rocket::tokio::{pin, select, sync::broadcast::Receiver, time::interval};
+
+use mlc_common::endpoints::ArtNetPortAddress;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+use crate::runtime::endpoints::input::{wait_for_exit, InputStreams, InputTarget, PRUNE_INTERVAL};
+use crate::runtime::endpoints::{health::EndpointHealth, Endpoint, EndpointData};
+use crate::runtime::merge::DEFAULT_PRIORITY;
+use crate::runtime::RuntimeData;
+
+use super::{ArtDmx, ArtNetSockets};
+
+/// Art-Net drops a source from a merge after it did not send for 10 seconds
+const MERGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Receives all Art-Net input endpoints from the ArtDmx of the shared Art-Net socket
+pub struct ArtNetReceiver {
+    runtime: RuntimeData,
+    sockets: ArtNetSockets,
+    inputs: Vec<(ArtNetPortAddress, InputTarget)>,
+    exit: Option<Receiver<EndpointData>>,
+}
+
+impl ArtNetReceiver {
+    pub fn new(runtime: RuntimeData, sockets: ArtNetSockets) -> ArtNetReceiver {
+        ArtNetReceiver {
+            runtime,
+            sockets,
+            inputs: vec![],
+            exit: None,
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        port_address: ArtNetPortAddress,
+        target: InputTarget,
+        rx: Receiver<EndpointData>,
+    ) {
+        self.inputs.push((port_address, target));
+        // All endpoints exit together, so listening to one of them is enough
+        self.exit.get_or_insert(rx);
+    }
+}
+
+impl Endpoint for ArtNetReceiver {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let Some(exit) = &mut self.exit else {
+            return Ok(());
+        };
+
+        let socket = self.sockets.open(Ipv4Addr::UNSPECIFIED).await?;
+        let mut dmx = socket.dmx();
+
+        let mut streams: HashMap<ArtNetPortAddress, InputStreams<SocketAddr>> = self
+            .inputs
+            .iter()
+            .map(|(a, _)| (*a, InputStreams::new(MERGE_TIMEOUT)))
+            .collect();
+
+        health.running().await;
+
+        let exit = wait_for_exit(exit);
+        pin!(exit);
+        let mut prune = interval(PRUNE_INTERVAL);
+
+        loop {
+            select! {
+                _ = &mut exit => break,
+                Ok(ArtDmx { source, port_address, sequence, data }) = dmx.recv() => {
+                    let Some(s) = streams.get_mut(&port_address) else {
+                        continue;
+                    };
+                    if s.update(source, sequence, DEFAULT_PRIORITY, &data) {
+                        let frame = s.frame();
+                        send(&self.runtime, &self.inputs, port_address, frame).await;
+                    }
+                },
+                _ = prune.tick() => {
+                    for (address, s) in &mut streams {
+                        if s.prune() {
+                            send(&self.runtime, &self.inputs, *address, s.frame()).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (_, target) in &self.inputs {
+            target.send(&self.runtime, None).await;
+        }
+        Ok(())
+    }
+}
+
+async fn send(
+    runtime: &RuntimeData,
+    inputs: &[(ArtNetPortAddress, InputTarget)],
+    address: ArtNetPortAddress,
+    frame: Option<([u8; UNIVERSE_SIZE], u8)>,
+) {
+    for (a, target) in inputs {
+        if *a == address {
+            target.send(runtime, frame).await;
+        }
+    }
+}
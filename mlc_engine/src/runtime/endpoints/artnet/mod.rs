@@ -0,0 +1,156 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use artnet_protocol::{ArtCommand, Output, PortAddress};
+use rocket::tokio::{
+    select,
+    sync::broadcast::{error::RecvError, Receiver},
+};
+
+use mlc_common::endpoints::ArtNetPortAddress;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+pub use self::discovery::ArtNetDiscovery;
+pub use self::input::ArtNetReceiver;
+pub use self::rdm::ArtNetRdm;
+pub use self::socket::ArtNetSockets;
+pub use self::timecode::{ArtNetTimecode, TimecodeChase, TimecodeReceiver};
+
+use self::socket::{ArtDmx, ArtNetSocket};
+use super::{health::EndpointHealth, Endpoint, EndpointData};
+
+mod discovery;
+mod input;
+mod rdm;
+mod socket;
+mod timecode;
+
+pub(crate) const ARTNET_PORT: u16 = 6454;
+
+pub struct ArtNetEndpoint {
+    pub data: [u8; UNIVERSE_SIZE],
+    pub port_address: ArtNetPortAddress,
+    pub unicast: Vec<Ipv4Addr>,
+    pub broadcast: bool,
+    pub interface: Option<Ipv4Addr>,
+    pub sockets: ArtNetSockets,
+    pub(crate) connections: Vec<SocketAddr>,
+    pub(crate) sequence: u8,
+    pub(crate) rx: Receiver<EndpointData>,
+}
+
+impl ArtNetEndpoint {
+    pub fn new(rx: Receiver<EndpointData>, sockets: ArtNetSockets) -> Self {
+        Self {
+            data: [0; UNIVERSE_SIZE],
+            port_address: ArtNetPortAddress::default(),
+            unicast: vec![],
+            broadcast: true,
+            interface: None,
+            sockets,
+            connections: vec![],
+            sequence: 0,
+            rx,
+        }
+    }
+}
+
+impl Endpoint for ArtNetEndpoint {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let socket = self
+            .sockets
+            .open(self.interface.unwrap_or(Ipv4Addr::UNSPECIFIED))
+            .await?;
+        let mut nodes = socket.nodes();
+
+        self.connections = self
+            .unicast
+            .iter()
+            .map(|ip| SocketAddr::from((*ip, ARTNET_PORT)))
+            .collect();
+
+        let mut rdm = self
+            .sockets
+            .discovery()
+            .rdm()
+            .register(self.port_address)
+            .await;
+
+        health.running().await;
+
+        loop {
+            select! {
+                msg = self.rx.recv() => {
+                    match msg {
+                        Ok(EndpointData::Exit) | Err(RecvError::Closed) => {
+                            println!("[ARTNET] Exiting");
+                            return Ok(());
+                        }
+                        Ok(EndpointData::Frame(frame)) => {
+                            self.data = frame.values;
+                            if frame.should_send() {
+                                self.send(&socket).await?;
+                            }
+                        }
+                        Err(RecvError::Lagged(_)) => {}
+                    }
+                },
+                Some(packet) = rdm.recv() => {
+                    self.send_packet(&socket, &packet).await;
+                },
+                Ok(node) = nodes.recv() => {
+                    // Nodes always send from the Art-Net port
+                    let adds = SocketAddr::from((node.ip, ARTNET_PORT));
+                    if node.port_addresses.contains(&self.port_address) && !self.connections.contains(&adds) {
+                        println!("[ARTNET] Connecting {adds}");
+                        self.connections.push(adds);
+                        self.send(&socket).await?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl ArtNetEndpoint {
+    async fn send(&mut self, socket: &ArtNetSocket) -> Result<(), String> {
+        // Sequence 0 disables reordering on the node, so it is skipped
+        self.sequence = self.sequence.checked_add(1).unwrap_or(1);
+        let cmd = make_output(&self.data, self.port_address, self.sequence);
+        let buf = cmd
+            .write_to_buffer()
+            .map_err(|e| format!("Building ArtDmx failed: {e:?}"))?;
+        self.send_packet(socket, &buf).await;
+        Ok(())
+    }
+
+    /// Sends to all connected nodes, or broadcasts while none is known
+    async fn send_packet(&self, socket: &ArtNetSocket, buf: &[u8]) {
+        if self.connections.is_empty() {
+            if self.broadcast {
+                if let Err(e) = socket
+                    .send_to(buf, (Ipv4Addr::BROADCAST, ARTNET_PORT).into())
+                    .await
+                {
+                    eprintln!("[ARTNET] Broadcasting failed: {e}");
+                }
+            }
+            return;
+        }
+
+        for a in &self.connections {
+            if let Err(e) = socket.send_to(buf, *a).await {
+                eprintln!("[ARTNET] Sending to {a} failed: {e}");
+            }
+        }
+    }
+}
+
+fn make_output(data: &[u8; UNIVERSE_SIZE], address: ArtNetPortAddress, sequence: u8) -> ArtCommand {
+    ArtCommand::Output(Output {
+        data: data.to_vec().into(),
+        sequence,
+        port_address: PortAddress::try_from(u16::from(address))
+            .expect("Port address is masked to 15 bits"),
+        ..Default::default()
+    })
+}
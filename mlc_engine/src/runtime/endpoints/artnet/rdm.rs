@@ -0,0 +1,664 @@
+//! RDM over Art-Net.
+//!
+//! The nodes discover the RDM devices on their outputs on their own and report them in ArtTodData, which is
+//! requested with ArtTodRequest. Single RDM messages are tunneled through ArtRdm. Requests are sent by the Art-Net
+//! endpoint that outputs the port address of the devices, the replies come in through the receive loop of its socket.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use rocket::tokio::{
+    sync::{broadcast::Sender, mpsc, oneshot, Mutex},
+    time::{sleep, timeout},
+};
+
+use mlc_common::endpoints::ArtNetPortAddress;
+use mlc_common::rdm::{RdmDevice, RdmUid};
+use mlc_common::Info;
+
+use crate::send;
+
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+const PROTOCOL_VERSION: [u8; 2] = [0, 14];
+const OP_TOD_REQUEST: u16 = 0x8000;
+const OP_TOD_DATA: u16 = 0x8100;
+const OP_TOD_CONTROL: u16 = 0x8200;
+const OP_RDM: u16 = 0x8300;
+/// Makes the node run a full discovery on its output
+const TOD_FLUSH: u8 = 0x01;
+const RDM_VERSION: u8 = 0x01;
+
+const START_CODE: u8 = 0xcc;
+const SUB_START_CODE: u8 = 0x01;
+
+pub const GET_COMMAND: u8 = 0x20;
+pub const GET_COMMAND_RESPONSE: u8 = 0x21;
+pub const SET_COMMAND: u8 = 0x30;
+pub const SET_COMMAND_RESPONSE: u8 = 0x31;
+
+const RESPONSE_ACK: u8 = 0x00;
+const RESPONSE_NACK_REASON: u8 = 0x02;
+
+pub const PID_DEVICE_INFO: u16 = 0x0060;
+pub const PID_DEVICE_MODEL_DESCRIPTION: u16 = 0x0080;
+pub const PID_MANUFACTURER_LABEL: u16 = 0x0081;
+pub const PID_DEVICE_LABEL: u16 = 0x0082;
+pub const PID_DMX_PERSONALITY_DESCRIPTION: u16 = 0x00e1;
+pub const PID_DMX_START_ADDRESS: u16 = 0x00f0;
+
+/// The UID MLC sends with, from the manufacturer range ESTA reserves for prototypes
+const CONTROLLER_UID: RdmUid = RdmUid {
+    manufacturer: 0x7ff0,
+    device: 0x4d4c_4301,
+};
+
+/// How long the nodes get to report their devices after a ToD request
+const TOD_WINDOW: Duration = Duration::from_secs(2);
+const RESPONSE_TIMEOUT: Duration = Duration::from_millis(800);
+/// RDM is not reliable, so every request is sent this often before giving up
+const ATTEMPTS: usize = 2;
+
+/// A single RDM message without the start code
+#[derive(Debug, Clone, PartialEq)]
+pub struct RdmMessage {
+    pub destination: RdmUid,
+    pub source: RdmUid,
+    pub transaction: u8,
+    /// The port id in requests and the response type in responses
+    pub port_or_response: u8,
+    pub sub_device: u16,
+    pub command_class: u8,
+    pub pid: u16,
+    pub data: Vec<u8>,
+}
+
+impl RdmMessage {
+    /// The message as it is tunneled in ArtRdm, which leaves out the start code
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![SUB_START_CODE, 24 + self.data.len() as u8];
+        buf.extend_from_slice(&self.destination.to_bytes());
+        buf.extend_from_slice(&self.source.to_bytes());
+        buf.extend_from_slice(&[self.transaction, self.port_or_response, 0]);
+        buf.extend_from_slice(&self.sub_device.to_be_bytes());
+        buf.push(self.command_class);
+        buf.extend_from_slice(&self.pid.to_be_bytes());
+        buf.push(self.data.len() as u8);
+        buf.extend_from_slice(&self.data);
+        let checksum = checksum(&buf);
+        buf.extend_from_slice(&checksum.to_be_bytes());
+        buf
+    }
+
+    pub fn decode(data: &[u8]) -> Option<RdmMessage> {
+        if data.len() < 25 || data[0] != SUB_START_CODE {
+            return None;
+        }
+        // The length includes the start code but not the checksum
+        let end = (data[1] as usize).checked_sub(1)?;
+        if end < 23 || data.len() < end + 2 || data[22] as usize != end - 23 {
+            return None;
+        }
+        if checksum(&data[..end]) != u16::from_be_bytes([data[end], data[end + 1]]) {
+            return None;
+        }
+
+        Some(RdmMessage {
+            destination: RdmUid::from_bytes(data[2..8].try_into().ok()?),
+            source: RdmUid::from_bytes(data[8..14].try_into().ok()?),
+            transaction: data[14],
+            port_or_response: data[15],
+            sub_device: u16::from_be_bytes([data[17], data[18]]),
+            command_class: data[19],
+            pid: u16::from_be_bytes([data[20], data[21]]),
+            data: data[23..end].to_vec(),
+        })
+    }
+}
+
+/// The sum of all bytes including the start code
+fn checksum(data: &[u8]) -> u16 {
+    data.iter()
+        .fold(START_CODE as u16, |sum, b| sum.wrapping_add(*b as u16))
+}
+
+fn header(op: u16) -> Vec<u8> {
+    let mut buf = ARTNET_ID.to_vec();
+    buf.extend_from_slice(&op.to_le_bytes());
+    buf.extend_from_slice(&PROTOCOL_VERSION);
+    buf
+}
+
+fn opcode(packet: &[u8]) -> Option<u16> {
+    if packet.len() < 12 || &packet[..8] != ARTNET_ID {
+        return None;
+    }
+    Some(u16::from_le_bytes([packet[8], packet[9]]))
+}
+
+fn port_address(net: u8, sub_uni: u8) -> ArtNetPortAddress {
+    ArtNetPortAddress {
+        net: net & 0x7f,
+        subnet: sub_uni >> 4,
+        universe: sub_uni & 0x0f,
+    }
+}
+
+pub fn tod_request(address: ArtNetPortAddress) -> Vec<u8> {
+    let mut buf = header(OP_TOD_REQUEST);
+    buf.extend_from_slice(&[0; 9]);
+    buf.extend_from_slice(&[address.net, 0, 1, address.sub_uni()]);
+    buf
+}
+
+pub fn tod_flush(address: ArtNetPortAddress) -> Vec<u8> {
+    let mut buf = header(OP_TOD_CONTROL);
+    buf.extend_from_slice(&[0; 9]);
+    buf.extend_from_slice(&[address.net, TOD_FLUSH, address.sub_uni()]);
+    buf
+}
+
+pub fn rdm_packet(address: ArtNetPortAddress, message: &RdmMessage) -> Vec<u8> {
+    let mut buf = header(OP_RDM);
+    buf.extend_from_slice(&[RDM_VERSION, 0]);
+    buf.extend_from_slice(&[0; 7]);
+    buf.extend_from_slice(&[address.net, 0, address.sub_uni()]);
+    buf.extend_from_slice(&message.encode());
+    buf
+}
+
+/// The devices of one ArtTodData, `None` for every other packet and for a ToD the node refused
+pub fn parse_tod_data(packet: &[u8]) -> Option<(ArtNetPortAddress, Vec<RdmUid>)> {
+    if opcode(packet)? != OP_TOD_DATA || packet.len() < 28 || packet[22] != 0 {
+        return None;
+    }
+
+    let uids = packet[28..]
+        .chunks_exact(6)
+        .take(packet[27] as usize)
+        .map(|uid| RdmUid::from_bytes(uid.try_into().expect("Chunks of 6")))
+        .collect();
+    Some((port_address(packet[21], packet[23]), uids))
+}
+
+pub fn parse_rdm(packet: &[u8]) -> Option<(ArtNetPortAddress, RdmMessage)> {
+    if opcode(packet)? != OP_RDM || packet.len() < 24 {
+        return None;
+    }
+    Some((
+        port_address(packet[21], packet[23]),
+        RdmMessage::decode(&packet[24..])?,
+    ))
+}
+
+/// A device from its DEVICE_INFO response, without the labels which need requests of their own
+pub fn parse_device_info(
+    uid: RdmUid,
+    node: Ipv4Addr,
+    port_address: ArtNetPortAddress,
+    data: &[u8],
+) -> Result<RdmDevice, String> {
+    if data.len() < 19 {
+        return Err(format!("DEVICE_INFO of {uid} is too short"));
+    }
+
+    let footprint = u16::from_be_bytes([data[10], data[11]]);
+    let address = u16::from_be_bytes([data[14], data[15]]);
+    Ok(RdmDevice {
+        uid,
+        node,
+        port_address,
+        manufacturer: String::new(),
+        model: String::new(),
+        model_id: u16::from_be_bytes([data[2], data[3]]),
+        label: String::new(),
+        software_version: u32::from_be_bytes([data[6], data[7], data[8], data[9]]),
+        dmx_start_address: (footprint > 0 && (1..=512).contains(&address)).then_some(address),
+        footprint,
+        personality: data[12],
+        personality_count: data[13],
+        personality_description: String::new(),
+        last_seen: Local::now(),
+    })
+}
+
+fn rdm_string(data: &[u8]) -> String {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).trim().to_string()
+}
+
+#[derive(Debug, Default)]
+struct RdmI {
+    devices: HashMap<RdmUid, RdmDevice>,
+    /// The Art-Net endpoints RDM packets for a port address are sent through
+    outputs: HashMap<ArtNetPortAddress, mpsc::Sender<Vec<u8>>>,
+    /// The devices reported since the last ToD request and the node they are connected to
+    tod: HashMap<ArtNetPortAddress, HashMap<RdmUid, Ipv4Addr>>,
+    /// Requests waiting for their response, keyed by device and transaction number
+    pending: HashMap<(RdmUid, u8), oneshot::Sender<RdmMessage>>,
+    transaction: u8,
+}
+
+/// The table of all RDM devices behind the Art-Net outputs.
+///
+/// It is shared between all Art-Net endpoints, the receive loops of their sockets pass on everything RDM.
+#[derive(Debug, Clone, Default)]
+pub struct ArtNetRdm {
+    inner: Arc<Mutex<RdmI>>,
+    info: Option<Sender<Info>>,
+}
+
+impl ArtNetRdm {
+    pub fn new(info: Sender<Info>) -> ArtNetRdm {
+        ArtNetRdm {
+            inner: Arc::default(),
+            info: Some(info),
+        }
+    }
+
+    /// Routes the RDM packets of the port address through the returned channel
+    pub async fn register(&self, address: ArtNetPortAddress) -> mpsc::Receiver<Vec<u8>> {
+        let (tx, rx) = mpsc::channel(16);
+        self.inner.lock().await.outputs.insert(address, tx);
+        rx
+    }
+
+    /// Handles the packet if it is an ArtTodData or an RDM response
+    pub async fn receive(&self, packet: &[u8], source: SocketAddr) {
+        if let Some((address, uids)) = parse_tod_data(packet) {
+            let SocketAddr::V4(source) = source else {
+                return;
+            };
+            let mut inner = self.inner.lock().await;
+            let tod = inner.tod.entry(address).or_default();
+            for uid in uids {
+                tod.insert(uid, *source.ip());
+            }
+            return;
+        }
+
+        if let Some((_, msg)) = parse_rdm(packet) {
+            if !matches!(
+                msg.command_class,
+                GET_COMMAND_RESPONSE | SET_COMMAND_RESPONSE
+            ) {
+                return;
+            }
+            let mut inner = self.inner.lock().await;
+            if let Some(tx) = inner.pending.remove(&(msg.source, msg.transaction)) {
+                let _ = tx.send(msg);
+            }
+        }
+    }
+
+    pub async fn devices(&self) -> Vec<RdmDevice> {
+        let mut list = self
+            .inner
+            .lock()
+            .await
+            .devices
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        list.sort_by_key(|d| (u16::from(d.port_address), d.uid));
+        list
+    }
+
+    /// Requests the ToD of every running Art-Net output and reads the details of all reported devices
+    pub async fn discover(&self) -> Result<(), String> {
+        let outputs = {
+            let mut inner = self.inner.lock().await;
+            inner.outputs.retain(|_, tx| !tx.is_closed());
+            for address in inner.outputs.keys().copied().collect::<Vec<_>>() {
+                inner.tod.insert(address, HashMap::new());
+            }
+            inner.outputs.clone()
+        };
+        if outputs.is_empty() {
+            return Err(
+                "No Art-Net output is running, RDM devices are found through them".to_string(),
+            );
+        }
+
+        for (address, tx) in &outputs {
+            let _ = tx.send(tod_flush(*address)).await;
+            let _ = tx.send(tod_request(*address)).await;
+        }
+        sleep(TOD_WINDOW).await;
+
+        let tod = self.inner.lock().await.tod.clone();
+        let mut found = HashMap::new();
+        for (address, devices) in tod.into_iter().filter(|(a, _)| outputs.contains_key(a)) {
+            for (uid, node) in devices {
+                match self.query_device(address, uid, node).await {
+                    Ok(device) => {
+                        found.insert(uid, device);
+                    }
+                    Err(e) => eprintln!("[RDM] {e}"),
+                }
+            }
+        }
+
+        {
+            let mut inner = self.inner.lock().await;
+            inner
+                .devices
+                .retain(|_, d| !outputs.contains_key(&d.port_address));
+            inner.devices.extend(found);
+        }
+        if let Some(info) = &self.info {
+            send!(info, Info::RdmDevicesChanged);
+        }
+        Ok(())
+    }
+
+    /// Sets the 1-based DMX start address of a discovered device
+    pub async fn set_start_address(&self, uid: RdmUid, address: u16) -> Result<RdmDevice, String> {
+        let device = self
+            .inner
+            .lock()
+            .await
+            .devices
+            .get(&uid)
+            .cloned()
+            .ok_or(format!("Unknown RDM device {uid}"))?;
+        if address == 0 || address as usize + device.footprint.max(1) as usize - 1 > 512 {
+            return Err(format!(
+                "Start address {address} does not fit a footprint of {}",
+                device.footprint
+            ));
+        }
+
+        self.request(
+            device.port_address,
+            uid,
+            SET_COMMAND,
+            PID_DMX_START_ADDRESS,
+            address.to_be_bytes().to_vec(),
+        )
+        .await?;
+
+        let device = {
+            let mut inner = self.inner.lock().await;
+            let device = inner.devices.entry(uid).or_insert(device);
+            device.dmx_start_address = Some(address);
+            device.last_seen = Local::now();
+            device.clone()
+        };
+        if let Some(info) = &self.info {
+            send!(info, Info::RdmDevicesChanged);
+        }
+        Ok(device)
+    }
+
+    async fn query_device(
+        &self,
+        address: ArtNetPortAddress,
+        uid: RdmUid,
+        node: Ipv4Addr,
+    ) -> Result<RdmDevice, String> {
+        let info = self.get(address, uid, PID_DEVICE_INFO, vec![]).await?;
+        let mut device = parse_device_info(uid, node, address, &info)?;
+
+        // The labels are optional, devices that do not support them NACK the request
+        let label = |pid| async move {
+            self.get(address, uid, pid, vec![])
+                .await
+                .map(|d| rdm_string(&d))
+                .unwrap_or_default()
+        };
+        device.manufacturer = label(PID_MANUFACTURER_LABEL).await;
+        device.model = label(PID_DEVICE_MODEL_DESCRIPTION).await;
+        device.label = label(PID_DEVICE_LABEL).await;
+        if device.personality > 0 {
+            device.personality_description = self
+                .get(
+                    address,
+                    uid,
+                    PID_DMX_PERSONALITY_DESCRIPTION,
+                    vec![device.personality],
+                )
+                .await
+                .map(|d| rdm_string(d.get(3..).unwrap_or_default()))
+                .unwrap_or_default();
+        }
+
+        Ok(device)
+    }
+
+    async fn get(
+        &self,
+        address: ArtNetPortAddress,
+        uid: RdmUid,
+        pid: u16,
+        data: Vec<u8>,
+    ) -> Result<Vec<u8>, String> {
+        self.request(address, uid, GET_COMMAND, pid, data)
+            .await
+            .map(|msg| msg.data)
+    }
+
+    async fn request(
+        &self,
+        address: ArtNetPortAddress,
+        uid: RdmUid,
+        command_class: u8,
+        pid: u16,
+        data: Vec<u8>,
+    ) -> Result<RdmMessage, String> {
+        for _ in 0..ATTEMPTS {
+            let (tx, rx) = oneshot::channel();
+            let (output, transaction) = {
+                let mut inner = self.inner.lock().await;
+                let output = inner
+                    .outputs
+                    .get(&address)
+                    .cloned()
+                    .ok_or(format!("No Art-Net output for {address:?}"))?;
+                inner.transaction = inner.transaction.wrapping_add(1);
+                let transaction = inner.transaction;
+                inner.pending.insert((uid, transaction), tx);
+                (output, transaction)
+            };
+
+            let msg = RdmMessage {
+                destination: uid,
+                source: CONTROLLER_UID,
+                transaction,
+                port_or_response: 1,
+                sub_device: 0,
+                command_class,
+                pid,
+                data: data.clone(),
+            };
+            output
+                .send(rdm_packet(address, &msg))
+                .await
+                .map_err(|_| format!("The Art-Net output for {address:?} stopped"))?;
+
+            match timeout(RESPONSE_TIMEOUT, rx).await {
+                Ok(Ok(response)) => {
+                    return match response.port_or_response {
+                        RESPONSE_ACK => Ok(response),
+                        RESPONSE_NACK_REASON => Err(format!(
+                            "{uid} refused PID {pid:#06x} with reason {:#06x}",
+                            u16::from_be_bytes([
+                                response.data.first().copied().unwrap_or_default(),
+                                response.data.get(1).copied().unwrap_or_default(),
+                            ])
+                        )),
+                        other => Err(format!(
+                            "{uid} answered PID {pid:#06x} with unsupported response type {other}"
+                        )),
+                    };
+                }
+                _ => {
+                    self.inner.lock().await.pending.remove(&(uid, transaction));
+                }
+            }
+        }
+
+        Err(format!("{uid} did not answer PID {pid:#06x}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::tokio::{net::UdpSocket, select, spawn};
+
+    use super::*;
+
+    const DEVICE: RdmUid = RdmUid {
+        manufacturer: 0x1234,
+        device: 0x0000_0042,
+    };
+
+    #[test]
+    fn message_round_trip() {
+        let msg = RdmMessage {
+            destination: DEVICE,
+            source: CONTROLLER_UID,
+            transaction: 7,
+            port_or_response: 1,
+            sub_device: 0,
+            command_class: SET_COMMAND,
+            pid: PID_DMX_START_ADDRESS,
+            data: vec![0, 17],
+        };
+        let packet = rdm_packet(ArtNetPortAddress::from(0x0123), &msg);
+
+        let (address, decoded) = parse_rdm(&packet).unwrap();
+        assert_eq!(u16::from(address), 0x0123);
+        assert_eq!(decoded, msg);
+
+        let mut corrupt = packet.clone();
+        corrupt[40] ^= 0xff;
+        assert_eq!(parse_rdm(&corrupt), None);
+    }
+
+    fn tod_data(address: ArtNetPortAddress, uids: &[RdmUid]) -> Vec<u8> {
+        let mut buf = header(OP_TOD_DATA);
+        buf.extend_from_slice(&[RDM_VERSION, 1]);
+        buf.extend_from_slice(&[0; 6]);
+        buf.extend_from_slice(&[1, address.net, 0, address.sub_uni()]);
+        buf.extend_from_slice(&(uids.len() as u16).to_be_bytes());
+        buf.extend_from_slice(&[0, uids.len() as u8]);
+        for uid in uids {
+            buf.extend_from_slice(&uid.to_bytes());
+        }
+        buf
+    }
+
+    /// Answers like a node with a single 12 channel device behind its output
+    fn stand_in_response(packet: &[u8], start_address: &mut u16) -> Option<Vec<u8>> {
+        let address = ArtNetPortAddress::default();
+        match opcode(packet)? {
+            OP_TOD_REQUEST | OP_TOD_CONTROL => return Some(tod_data(address, &[DEVICE])),
+            OP_RDM => {}
+            _ => return None,
+        }
+
+        let (_, request) = parse_rdm(packet)?;
+        let data = match (request.command_class, request.pid) {
+            (GET_COMMAND, PID_DEVICE_INFO) => {
+                let mut info = vec![0x01, 0x00, 0x00, 0x99, 0x01, 0x01, 0, 0, 0, 3];
+                info.extend_from_slice(&12u16.to_be_bytes());
+                info.extend_from_slice(&[2, 3]);
+                info.extend_from_slice(&start_address.to_be_bytes());
+                info.extend_from_slice(&[0, 0, 0]);
+                info
+            }
+            (GET_COMMAND, PID_MANUFACTURER_LABEL) => b"Stand-in".to_vec(),
+            (GET_COMMAND, PID_DEVICE_MODEL_DESCRIPTION) => b"Spot 575".to_vec(),
+            (GET_COMMAND, PID_DMX_PERSONALITY_DESCRIPTION) => {
+                let mut description = vec![request.data[0], 0, 12];
+                description.extend_from_slice(b"Extended");
+                description
+            }
+            (SET_COMMAND, PID_DMX_START_ADDRESS) => {
+                *start_address = u16::from_be_bytes([request.data[0], request.data[1]]);
+                vec![]
+            }
+            // DEVICE_LABEL is not supported
+            _ => {
+                let response = RdmMessage {
+                    port_or_response: RESPONSE_NACK_REASON,
+                    command_class: request.command_class + 1,
+                    data: vec![0, 0],
+                    destination: request.source,
+                    source: DEVICE,
+                    ..request
+                };
+                return Some(rdm_packet(address, &response));
+            }
+        };
+
+        let response = RdmMessage {
+            port_or_response: RESPONSE_ACK,
+            command_class: request.command_class + 1,
+            data,
+            destination: request.source,
+            source: DEVICE,
+            ..request
+        };
+        Some(rdm_packet(address, &response))
+    }
+
+    #[rocket::async_test]
+    async fn discover_stand_in_node() {
+        let rdm = ArtNetRdm::default();
+        let mut outgoing = rdm.register(ArtNetPortAddress::default()).await;
+
+        let node = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let node_address = node.local_addr().unwrap();
+        spawn(async move {
+            let mut start_address = 1;
+            let mut buffer = [0u8; 1024];
+            while let Ok((length, source)) = node.recv_from(&mut buffer).await {
+                if let Some(response) = stand_in_response(&buffer[..length], &mut start_address) {
+                    node.send_to(&response, source).await.unwrap();
+                }
+            }
+        });
+
+        // Takes the place of the Art-Net endpoint
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let endpoint = rdm.clone();
+        spawn(async move {
+            let mut buffer = [0u8; 1024];
+            loop {
+                select! {
+                    Some(packet) = outgoing.recv() => {
+                        socket.send_to(&packet, node_address).await.unwrap();
+                    }
+                    Ok((length, source)) = socket.recv_from(&mut buffer) => {
+                        endpoint.receive(&buffer[..length], source).await;
+                    }
+                }
+            }
+        });
+
+        rdm.discover().await.unwrap();
+        let devices = rdm.devices().await;
+        assert_eq!(devices.len(), 1);
+        let device = &devices[0];
+        assert_eq!(device.uid, DEVICE);
+        assert_eq!(device.node, Ipv4Addr::LOCALHOST);
+        assert_eq!(device.manufacturer, "Stand-in");
+        assert_eq!(device.model, "Spot 575");
+        assert_eq!(device.label, "");
+        assert_eq!(device.dmx_start_address, Some(1));
+        assert_eq!(device.footprint, 12);
+        assert_eq!((device.personality, device.personality_count), (2, 3));
+        assert_eq!(device.personality_description, "Extended");
+
+        let device = rdm.set_start_address(DEVICE, 101).await.unwrap();
+        assert_eq!(device.dmx_start_address, Some(101));
+        assert!(rdm.set_start_address(DEVICE, 510).await.is_err());
+
+        rdm.discover().await.unwrap();
+        assert_eq!(rdm.devices().await[0].dmx_start_address, Some(101));
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use artnet_protocol::{ArtCommand, Poll};
+use rocket::tokio::{
+    net::UdpSocket,
+    select, spawn,
+    sync::{broadcast, Mutex},
+    task::JoinHandle,
+    time::{interval, sleep},
+};
+use socket2::{Domain, Protocol, Socket, Type};
+
+use mlc_common::endpoints::{ArtNetNode, ArtNetPortAddress};
+
+use super::{ArtNetDiscovery, ArtNetTimecode, ARTNET_PORT};
+
+/// Art-Net controllers should poll every 2.5 to 3 seconds so late nodes get picked up
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// How long a manual discovery waits for replies
+const DISCOVERY_WINDOW: Duration = Duration::from_millis(1500);
+
+/// An ArtDmx packet received on an Art-Net socket
+#[derive(Debug, Clone)]
+pub struct ArtDmx {
+    pub source: SocketAddr,
+    pub port_address: ArtNetPortAddress,
+    /// `None` if the sender does not use sequence numbers
+    pub sequence: Option<u8>,
+    pub data: Vec<u8>,
+}
+
+/// The sockets bound to the Art-Net port.
+///
+/// Every bind address is bound only once and shared by all Art-Net endpoints on it. The receive loop of a socket
+/// polls for nodes and hands every packet to the one consuming it: ArtPollReply to the node table, ArtTimeCode to the
+/// timecode, RDM to the RDM devices and ArtDmx to the input endpoints.
+#[derive(Debug, Clone)]
+pub struct ArtNetSockets {
+    discovery: ArtNetDiscovery,
+    timecode: ArtNetTimecode,
+    open: Arc<Mutex<HashMap<Ipv4Addr, Weak<ArtNetSocket>>>>,
+}
+
+impl ArtNetSockets {
+    pub fn new(discovery: ArtNetDiscovery, timecode: ArtNetTimecode) -> ArtNetSockets {
+        ArtNetSockets {
+            discovery,
+            timecode,
+            open: Arc::default(),
+        }
+    }
+
+    pub fn discovery(&self) -> &ArtNetDiscovery {
+        &self.discovery
+    }
+
+    pub fn timecode(&self) -> &ArtNetTimecode {
+        &self.timecode
+    }
+
+    /// The socket bound to the Art-Net port on the address, bound now if no one holds it yet.
+    ///
+    /// It is closed once the last holder dropped it.
+    pub async fn open(&self, bind: Ipv4Addr) -> Result<Arc<ArtNetSocket>, String> {
+        let mut open = self.open.lock().await;
+        if let Some(socket) = open.get(&bind).and_then(Weak::upgrade) {
+            return Ok(socket);
+        }
+
+        let socket = Arc::new(
+            bind_socket(bind).map_err(|e| format!("Binding Art-Net port on {bind} failed: {e}"))?,
+        );
+        let (dmx, _) = broadcast::channel(256);
+        let (nodes, _) = broadcast::channel(64);
+        let task = spawn(receive(
+            socket.clone(),
+            self.discovery.clone(),
+            self.timecode.clone(),
+            dmx.clone(),
+            nodes.clone(),
+        ));
+
+        let socket = Arc::new(ArtNetSocket {
+            socket,
+            dmx,
+            nodes,
+            task,
+        });
+        open.retain(|_, s| s.strong_count() > 0);
+        open.insert(bind, Arc::downgrade(&socket));
+        Ok(socket)
+    }
+
+    /// Sends a single ArtPoll and gives the nodes a short while to reply
+    pub async fn discover(&self, interface: Option<Ipv4Addr>) -> Result<(), String> {
+        let socket = self
+            .open(interface.unwrap_or(Ipv4Addr::UNSPECIFIED))
+            .await?;
+        socket
+            .send_to(&poll()?, (Ipv4Addr::BROADCAST, ARTNET_PORT).into())
+            .await
+            .map_err(|e| e.to_string())?;
+        sleep(DISCOVERY_WINDOW).await;
+        Ok(())
+    }
+}
+
+/// A socket bound to the Art-Net port, shared through [`ArtNetSockets`]
+#[derive(Debug)]
+pub struct ArtNetSocket {
+    socket: Arc<UdpSocket>,
+    dmx: broadcast::Sender<ArtDmx>,
+    nodes: broadcast::Sender<ArtNetNode>,
+    task: JoinHandle<()>,
+}
+
+impl ArtNetSocket {
+    pub async fn send_to(&self, buf: &[u8], target: SocketAddr) -> std::io::Result<usize> {
+        self.socket.send_to(buf, target).await
+    }
+
+    /// The ArtDmx packets received from now on
+    pub fn dmx(&self) -> broadcast::Receiver<ArtDmx> {
+        self.dmx.subscribe()
+    }
+
+    /// The nodes that reply to a poll from now on
+    pub fn nodes(&self) -> broadcast::Receiver<ArtNetNode> {
+        self.nodes.subscribe()
+    }
+}
+
+impl Drop for ArtNetSocket {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn bind_socket(address: Ipv4Addr) -> std::io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    // A socket on a single interface and one on all interfaces can both hold the port
+    socket.set_reuse_address(true)?;
+    socket.set_broadcast(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&SocketAddr::from((address, ARTNET_PORT)).into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+fn poll() -> Result<Vec<u8>, String> {
+    ArtCommand::Poll(Poll::default())
+        .write_to_buffer()
+        .map_err(|e| format!("Building ArtPoll failed: {e:?}"))
+}
+
+async fn receive(
+    socket: Arc<UdpSocket>,
+    discovery: ArtNetDiscovery,
+    timecode: ArtNetTimecode,
+    dmx: broadcast::Sender<ArtDmx>,
+    nodes: broadcast::Sender<ArtNetNode>,
+) {
+    let poll = match poll() {
+        Ok(poll) => poll,
+        Err(e) => {
+            eprintln!("[ARTNET] {e}");
+            return;
+        }
+    };
+    let mut poll_timer = interval(POLL_INTERVAL);
+
+    // Large enough for an ArtTodData with 200 devices
+    let mut buffer = [0u8; 1536];
+    loop {
+        select! {
+            _ = poll_timer.tick() => {
+                if let Err(e) = socket.send_to(&poll, (Ipv4Addr::BROADCAST, ARTNET_PORT)).await {
+                    eprintln!("[ARTNET] Polling failed: {e}");
+                }
+            },
+            Ok((length, source)) = socket.recv_from(&mut buffer) => {
+                let packet = &buffer[..length];
+                timecode.receive(packet);
+                discovery.rdm().receive(packet, source).await;
+                match ArtCommand::from_buffer(packet) {
+                    Ok(ArtCommand::PollReply(reply)) => {
+                        let node = discovery.insert(&reply, source).await;
+                        let _ = nodes.send(node);
+                    }
+                    Ok(ArtCommand::Output(output)) => {
+                        let _ = dmx.send(ArtDmx {
+                            source,
+                            port_address: ArtNetPortAddress::from(u16::from(output.port_address)),
+                            // Sequence 0 means the sender does not use sequence numbers
+                            sequence: (output.sequence != 0).then_some(output.sequence),
+                            data: output.data.as_ref().to_vec(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
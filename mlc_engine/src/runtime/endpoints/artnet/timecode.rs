@@ -0,0 +1,187 @@
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::tokio::sync::{broadcast::Receiver, watch};
+
+use crate::runtime::endpoints::input::wait_for_exit;
+use crate::runtime::endpoints::{health::EndpointHealth, Endpoint, EndpointData};
+
+use super::ArtNetSockets;
+
+const ARTNET_ID: &[u8; 8] = b"Art-Net\0";
+const OP_TIME_CODE: u16 = 0x9700;
+const TIME_CODE_LENGTH: usize = 19;
+
+/// The frame rates of ArtTimeCode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimecodeType {
+    Film,
+    Ebu,
+    DropFrame,
+    Smpte,
+}
+
+impl TimecodeType {
+    fn frames_per_second(&self) -> f64 {
+        match self {
+            TimecodeType::Film => 24.0,
+            TimecodeType::Ebu => 25.0,
+            TimecodeType::DropFrame => 29.97,
+            TimecodeType::Smpte => 30.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timecode {
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+    pub frames: u8,
+    pub kind: TimecodeType,
+}
+
+impl Timecode {
+    /// Parses an ArtTimeCode packet, `None` for every other packet
+    pub fn parse(packet: &[u8]) -> Option<Timecode> {
+        if packet.len() < TIME_CODE_LENGTH
+            || &packet[..8] != ARTNET_ID
+            || u16::from_le_bytes([packet[8], packet[9]]) != OP_TIME_CODE
+        {
+            return None;
+        }
+
+        Some(Timecode {
+            frames: packet[14],
+            seconds: packet[15],
+            minutes: packet[16],
+            hours: packet[17],
+            kind: match packet[18] {
+                0 => TimecodeType::Film,
+                1 => TimecodeType::Ebu,
+                2 => TimecodeType::DropFrame,
+                _ => TimecodeType::Smpte,
+            },
+        })
+    }
+
+    /// The time since 00:00:00:00
+    pub fn position(&self) -> Duration {
+        let seconds = self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64;
+        Duration::from_secs(seconds)
+            + Duration::from_secs_f64(self.frames as f64 / self.kind.frames_per_second())
+    }
+}
+
+/// Whether playing effects chase the timecode and where it currently is
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimecodeChase {
+    pub enabled: bool,
+    /// The position of the last timecode received, kept when the timecode stops
+    pub position: Option<Duration>,
+}
+
+/// The Art-Net timecode the effect player chases.
+///
+/// The receive loops of all Art-Net sockets feed it.
+#[derive(Debug, Clone)]
+pub struct ArtNetTimecode {
+    tx: Arc<watch::Sender<TimecodeChase>>,
+}
+
+impl Default for ArtNetTimecode {
+    fn default() -> Self {
+        ArtNetTimecode {
+            tx: Arc::new(watch::Sender::new(TimecodeChase::default())),
+        }
+    }
+}
+
+impl ArtNetTimecode {
+    pub fn set_chase(&self, enabled: bool) {
+        self.tx.send_if_modified(|chase| {
+            let changed = chase.enabled != enabled;
+            chase.enabled = enabled;
+            if !enabled {
+                chase.position = None;
+            }
+            changed
+        });
+    }
+
+    /// Updates the position if the packet is an ArtTimeCode and the chase is enabled
+    pub fn receive(&self, packet: &[u8]) {
+        let Some(timecode) = Timecode::parse(packet) else {
+            return;
+        };
+        self.tx.send_if_modified(|chase| {
+            if chase.enabled {
+                chase.position = Some(timecode.position());
+            }
+            chase.enabled
+        });
+    }
+
+    pub fn subscribe(&self) -> watch::Receiver<TimecodeChase> {
+        self.tx.subscribe()
+    }
+}
+
+/// Keeps the Art-Net socket on all interfaces open, its receive loop passes the timecode on
+pub struct TimecodeReceiver {
+    pub sockets: ArtNetSockets,
+    pub rx: Receiver<EndpointData>,
+}
+
+impl Endpoint for TimecodeReceiver {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let _socket = self.sockets.open(Ipv4Addr::UNSPECIFIED).await?;
+        health.running().await;
+        wait_for_exit(&mut self.rx).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(frames: u8, seconds: u8, minutes: u8, hours: u8, kind: u8) -> Vec<u8> {
+        let mut p = ARTNET_ID.to_vec();
+        p.extend_from_slice(&OP_TIME_CODE.to_le_bytes());
+        p.extend_from_slice(&[0, 14, 0, 0]);
+        p.extend_from_slice(&[frames, seconds, minutes, hours, kind]);
+        p
+    }
+
+    #[test]
+    fn parse_position() {
+        let tc = Timecode::parse(&packet(12, 3, 2, 1, 1)).unwrap();
+        assert_eq!(tc.kind, TimecodeType::Ebu);
+        assert_eq!(
+            tc.position(),
+            Duration::from_secs(3600 + 120 + 3) + Duration::from_millis(480)
+        );
+
+        let mut other = packet(0, 0, 0, 0, 3);
+        other[9] = 0x50;
+        assert_eq!(Timecode::parse(&other), None);
+    }
+
+    #[test]
+    fn chase_only_when_enabled() {
+        let timecode = ArtNetTimecode::default();
+        let rx = timecode.subscribe();
+
+        timecode.receive(&packet(0, 10, 0, 0, 3));
+        assert_eq!(rx.borrow().position, None);
+
+        timecode.set_chase(true);
+        timecode.receive(&packet(0, 10, 0, 0, 3));
+        assert_eq!(rx.borrow().position, Some(Duration::from_secs(10)));
+
+        timecode.set_chase(false);
+        assert_eq!(*rx.borrow(), TimecodeChase::default());
+    }
+}
@@ -0,0 +1,130 @@
+use mlc_common::universe::UNIVERSE_SIZE;
+use rocket::tokio::sync::{
+    broadcast::{error::RecvError, Receiver},
+    watch,
+};
+
+use super::EndpointData;
+
+/// Collects the frames of several endpoint channels, so an endpoint spanning multiple universes sends them together.
+///
+/// Every member receives one frame per tick of the frame clock, a round is complete once all of them arrived.
+/// Only the latest frame of every member is kept, so nothing piles up while the endpoint fails or backs off.
+pub struct FrameGroup {
+    shared: watch::Sender<Members>,
+    changed: watch::Receiver<Members>,
+    values: Vec<[u8; UNIVERSE_SIZE]>,
+}
+
+/// What changed during a complete round
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Round {
+    pub dirty: bool,
+    pub refresh: bool,
+}
+
+#[derive(Debug, Default)]
+struct Members {
+    latest: Vec<Latest>,
+    exit: bool,
+}
+
+/// The last frame of a member, with the flags of all frames since the last round
+#[derive(Debug)]
+struct Latest {
+    values: [u8; UNIVERSE_SIZE],
+    received: bool,
+    dirty: bool,
+    refresh: bool,
+}
+
+impl Default for FrameGroup {
+    fn default() -> Self {
+        let (shared, changed) = watch::channel(Members::default());
+        Self {
+            shared,
+            changed,
+            values: vec![],
+        }
+    }
+}
+
+impl FrameGroup {
+    /// Adds a member and returns its index
+    pub fn add(&mut self, rx: Receiver<EndpointData>) -> usize {
+        let index = self.values.len();
+        self.shared.send_modify(|m| {
+            m.latest.push(Latest {
+                values: [0; UNIVERSE_SIZE],
+                received: false,
+                dirty: false,
+                refresh: false,
+            })
+        });
+        forward(index, rx, self.shared.clone());
+        self.values.push([0; UNIVERSE_SIZE]);
+        index
+    }
+
+    /// The last values of every member, in the order they were added
+    pub fn values(&self) -> &[[u8; UNIVERSE_SIZE]] {
+        &self.values
+    }
+
+    /// Waits for the next complete round. Returns `None` once the group has to exit
+    pub async fn recv(&mut self) -> Option<Round> {
+        loop {
+            self.changed.borrow_and_update();
+
+            let mut exit = false;
+            let mut round = None;
+            let values = &mut self.values;
+            self.shared.send_if_modified(|m| {
+                exit = m.exit || m.latest.is_empty();
+                if exit || !m.latest.iter().all(|l| l.received) {
+                    return false;
+                }
+
+                let mut r = Round::default();
+                for (latest, values) in m.latest.iter_mut().zip(values.iter_mut()) {
+                    *values = latest.values;
+                    latest.received = false;
+                    r.dirty |= std::mem::take(&mut latest.dirty);
+                    r.refresh |= std::mem::take(&mut latest.refresh);
+                }
+                round = Some(r);
+                false
+            });
+
+            if exit {
+                return None;
+            }
+            if round.is_some() {
+                return round;
+            }
+            self.changed.changed().await.ok()?;
+        }
+    }
+}
+
+/// Keeps the latest frame of one member in the state of its group
+fn forward(index: usize, mut rx: Receiver<EndpointData>, shared: watch::Sender<Members>) {
+    rocket::tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(EndpointData::Frame(frame)) => shared.send_modify(|m| {
+                    let latest = &mut m.latest[index];
+                    latest.values = frame.values;
+                    latest.received = true;
+                    latest.dirty |= frame.dirty;
+                    latest.refresh |= frame.refresh;
+                }),
+                Ok(EndpointData::Exit) | Err(RecvError::Closed) => {
+                    shared.send_modify(|m| m.exit = true);
+                    break;
+                }
+                Err(RecvError::Lagged(_)) => continue,
+            }
+        }
+    });
+}
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use rocket::tokio::{
+    sync::{broadcast::Sender, Mutex},
+    time::{sleep, Instant},
+};
+
+use mlc_common::endpoints::{EndpointDevice, EndpointState, EndpointStatus};
+use mlc_common::patched::UniverseId;
+use mlc_common::Info;
+
+use crate::send;
+
+use super::Endpoint;
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// The state of every endpoint of the current endpoint config.
+///
+/// Each config reload starts a new generation, reports of endpoints from older generations are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct EndpointHealthTable {
+    inner: Arc<Mutex<HealthI>>,
+    info: Option<Sender<Info>>,
+}
+
+#[derive(Debug, Default)]
+struct HealthI {
+    generation: u64,
+    states: BTreeMap<(UniverseId, usize), EndpointStatus>,
+}
+
+impl EndpointHealthTable {
+    pub fn new(info: Sender<Info>) -> EndpointHealthTable {
+        EndpointHealthTable {
+            inner: Arc::new(Mutex::new(HealthI::default())),
+            info: Some(info),
+        }
+    }
+
+    /// Forgets all endpoints, used before the endpoints are recreated
+    pub async fn reset(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.generation += 1;
+        inner.states.clear();
+        self.notify();
+    }
+
+    /// Registers the endpoints at the given positions of the endpoint config, which are run by one task
+    pub async fn reporter(&self, keys: Vec<(UniverseId, usize)>) -> EndpointHealth {
+        let mut inner = self.inner.lock().await;
+        for (universe, index) in &keys {
+            inner.states.insert(
+                (*universe, *index),
+                EndpointStatus {
+                    universe: *universe,
+                    index: *index,
+                    state: EndpointState::Starting,
+                    restarts: 0,
+                    since: Local::now(),
+                    device: None,
+                },
+            );
+        }
+        self.notify();
+
+        EndpointHealth {
+            table: self.clone(),
+            generation: inner.generation,
+            keys,
+        }
+    }
+
+    pub async fn states(&self) -> Vec<EndpointStatus> {
+        self.inner.lock().await.states.values().cloned().collect()
+    }
+
+    fn notify(&self) {
+        if let Some(info) = &self.info {
+            send!(info, Info::EndpointStatesChanged);
+        }
+    }
+}
+
+/// Handed to a running endpoint to report its state
+#[derive(Debug, Clone)]
+pub struct EndpointHealth {
+    table: EndpointHealthTable,
+    generation: u64,
+    keys: Vec<(UniverseId, usize)>,
+}
+
+impl EndpointHealth {
+    /// Should be called by the endpoint as soon as its I/O is set up
+    pub async fn running(&self) {
+        self.set(EndpointState::Running, false).await;
+    }
+
+    /// Stores what the endpoint read back from its device
+    pub async fn device(&self, device: EndpointDevice) {
+        let mut inner = self.table.inner.lock().await;
+        if inner.generation != self.generation {
+            return;
+        }
+
+        for key in &self.keys {
+            if let Some(status) = inner.states.get_mut(key) {
+                status.device = Some(device.clone());
+            }
+        }
+        self.table.notify();
+    }
+
+    async fn set(&self, state: EndpointState, restart: bool) {
+        let mut inner = self.table.inner.lock().await;
+        if inner.generation != self.generation {
+            return;
+        }
+
+        let mut changed = false;
+        for key in &self.keys {
+            if let Some(status) = inner.states.get_mut(key) {
+                if restart {
+                    status.restarts += 1;
+                    changed = true;
+                }
+                if status.state != state {
+                    status.state = state.clone();
+                    status.since = Local::now();
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            self.table.notify();
+        }
+    }
+
+    /// Whether the endpoint still belongs to the current endpoint config
+    async fn is_current(&self) -> bool {
+        self.table.inner.lock().await.generation == self.generation
+    }
+}
+
+/// Runs an endpoint until it stops on its own, restarting it with an exponential backoff whenever it fails.
+///
+/// Once the endpoint config was reloaded a failed endpoint is not restarted anymore.
+pub async fn supervise<E: Endpoint>(mut endpoint: E, health: EndpointHealth) {
+    let mut backoff = MIN_BACKOFF;
+    let mut restart = false;
+    loop {
+        health.set(EndpointState::Starting, restart).await;
+
+        let started = Instant::now();
+        let result = endpoint.run(&health).await;
+
+        // An endpoint that ran fine for a while starts over with a short backoff
+        if started.elapsed() > MAX_BACKOFF {
+            backoff = MIN_BACKOFF;
+        }
+
+        match result {
+            Ok(()) => {
+                health.set(EndpointState::Stopped, false).await;
+                return;
+            }
+            Err(e) => {
+                eprintln!("[ENDPOINT] {e}, restarting in {}s", backoff.as_secs());
+                health.set(EndpointState::Error(e), false).await;
+            }
+        }
+
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+
+        if !health.is_current().await {
+            return;
+        }
+        restart = true;
+    }
+}
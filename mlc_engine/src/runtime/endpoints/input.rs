@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::Duration;
+
+use rocket::tokio::{
+    sync::broadcast::{error::RecvError, Receiver},
+    time::Instant,
+};
+
+use mlc_common::endpoints::MergeMode;
+use mlc_common::patched::UniverseId;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+use crate::runtime::RuntimeData;
+
+use super::EndpointData;
+
+/// How often input endpoints look for sources that stopped sending
+pub const PRUNE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The local universe an input endpoint merges into
+#[derive(Debug, Clone)]
+pub struct InputTarget {
+    pub universe: UniverseId,
+    pub merge: MergeMode,
+    pub local_priority: u8,
+    id: uuid::Uuid,
+}
+
+impl InputTarget {
+    pub fn new(universe: UniverseId, merge: MergeMode, local_priority: u8) -> InputTarget {
+        InputTarget {
+            universe,
+            merge,
+            local_priority,
+            id: uuid::Uuid::new_v4(),
+        }
+    }
+
+    pub async fn send(&self, runtime: &RuntimeData, frame: Option<([u8; UNIVERSE_SIZE], u8)>) {
+        match frame {
+            Some((values, priority)) => {
+                runtime
+                    .input_frame(
+                        self.universe,
+                        self.id,
+                        self.merge,
+                        self.local_priority,
+                        &values,
+                        priority,
+                    )
+                    .await
+            }
+            None => runtime.input_lost(self.universe, self.id).await,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Stream {
+    priority: u8,
+    sequence: Option<u8>,
+    values: [u8; UNIVERSE_SIZE],
+    last_seen: Instant,
+}
+
+/// All sources that currently send to one universe, keyed by whatever identifies a source in the protocol
+#[derive(Debug)]
+pub struct InputStreams<K> {
+    streams: HashMap<K, Stream>,
+    timeout: Duration,
+}
+
+impl<K: Hash + Eq> InputStreams<K> {
+    pub fn new(timeout: Duration) -> InputStreams<K> {
+        InputStreams {
+            streams: HashMap::new(),
+            timeout,
+        }
+    }
+
+    /// Stores the data of a source. Returns false if the packet arrived out of order and was dropped
+    pub fn update(&mut self, source: K, sequence: Option<u8>, priority: u8, data: &[u8]) -> bool {
+        let now = Instant::now();
+        let stream = self.streams.entry(source).or_insert_with(|| Stream {
+            priority,
+            sequence: None,
+            values: [0; UNIVERSE_SIZE],
+            last_seen: now,
+        });
+
+        if let (Some(last), Some(sequence)) = (stream.sequence, sequence) {
+            // Packets up to 20 behind the last one are late, everything else is a restart of the source
+            let diff = sequence.wrapping_sub(last) as i8;
+            if diff <= 0 && diff > -20 {
+                return false;
+            }
+        }
+
+        let len = data.len().min(UNIVERSE_SIZE);
+        stream.values = [0; UNIVERSE_SIZE];
+        stream.values[..len].copy_from_slice(&data[..len]);
+        stream.priority = priority;
+        stream.sequence = sequence;
+        stream.last_seen = now;
+        true
+    }
+
+    pub fn remove(&mut self, source: &K) -> bool {
+        self.streams.remove(source).is_some()
+    }
+
+    /// Drops all sources that did not send for longer than the timeout. Returns whether any were dropped
+    pub fn prune(&mut self) -> bool {
+        let before = self.streams.len();
+        let timeout = self.timeout;
+        self.streams.retain(|_, s| s.last_seen.elapsed() < timeout);
+        before != self.streams.len()
+    }
+
+    /// The sources with the highest priority merged HTP, together with that priority
+    pub fn frame(&self) -> Option<([u8; UNIVERSE_SIZE], u8)> {
+        let priority = self.streams.values().map(|s| s.priority).max()?;
+        let mut values = [0; UNIVERSE_SIZE];
+        for s in self.streams.values().filter(|s| s.priority == priority) {
+            for (v, n) in values.iter_mut().zip(s.values) {
+                *v = (*v).max(n);
+            }
+        }
+        Some((values, priority))
+    }
+}
+
+/// Input endpoints do not output anything, they only listen for the exit message
+pub async fn wait_for_exit(rx: &mut Receiver<EndpointData>) {
+    loop {
+        match rx.recv().await {
+            Ok(EndpointData::Exit) | Err(RecvError::Closed) => return,
+            _ => {}
+        }
+    }
+}
@@ -1,100 +1,288 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::net::Ipv4Addr;
 
 use rocket::tokio::sync::broadcast::{Receiver, Sender};
 
-use crate::runtime::endpoints::usb::UsbEndpoint;
-use mlc_common::endpoints::{EPConfigItem, EndPointConfig};
-use mlc_common::patched::{UniverseAddress, UniverseId};
+use crate::runtime::endpoints::{usb::UsbEndpoint, usb_pro::UsbProEndpoint};
+use mlc_common::endpoints::{ChannelMap, EPConfigItem, EndPointConfig};
+use mlc_common::patched::UniverseId;
 use mlc_common::universe::UNIVERSE_SIZE;
 
-use self::{artnet::ArtNetEndpoint, sacn::SacnEndpoint};
+use crate::runtime::{merge::DEFAULT_PRIORITY, RuntimeData};
+
+use self::{
+    artnet::{ArtNetEndpoint, ArtNetReceiver, TimecodeReceiver},
+    health::{supervise, EndpointHealth},
+    input::InputTarget,
+    pixel::{DdpEndpoint, OpcEndpoint},
+    recorder::RecorderEndpoint,
+    sacn::{SacnEndpoint, SacnReceiver, SacnSyncGroup},
+};
+
+pub use self::artnet::{ArtNetDiscovery, ArtNetSockets, ArtNetTimecode, TimecodeChase};
+pub use self::health::EndpointHealthTable;
+pub use self::recorder::recording_path;
 
 mod artnet;
+mod group;
+mod health;
+mod input;
+mod pixel;
+mod recorder;
 mod sacn;
 mod usb;
+mod usb_pro;
 
-macro_rules! register_default {
-    ($type:ty, $rx:expr) => {
-        <$type>::default().register($rx);
-    };
-}
+/// The positions in the endpoint config of all endpoints one task runs
+type Keys = Vec<(UniverseId, usize)>;
 
 pub trait CreateEndpoints {
-    async fn create_endpoints(&self) -> HashMap<UniverseId, Vec<Sender<EndpointData>>>;
+    async fn create_endpoints(
+        &self,
+        artnet: &ArtNetSockets,
+        health: &EndpointHealthTable,
+        runtime: &RuntimeData,
+    ) -> HashMap<UniverseId, Vec<EndpointSender>>;
 }
 
 impl CreateEndpoints for EndPointConfig {
-    async fn create_endpoints(&self) -> HashMap<UniverseId, Vec<Sender<EndpointData>>> {
+    async fn create_endpoints(
+        &self,
+        artnet: &ArtNetSockets,
+        health: &EndpointHealthTable,
+        runtime: &RuntimeData,
+    ) -> HashMap<UniverseId, Vec<EndpointSender>> {
         let mut points = HashMap::new();
+        let mut sacn_groups: HashMap<u16, (SacnSyncGroup, Keys)> = HashMap::new();
+        let mut ddp_groups: HashMap<Ipv4Addr, (DdpEndpoint, Keys)> = HashMap::new();
+        let mut opc_groups: HashMap<(String, u8), (OpcEndpoint, Keys)> = HashMap::new();
+        let mut recorders: HashMap<String, (RecorderEndpoint, Keys)> = HashMap::new();
+        let mut sacn_input = (SacnReceiver::new(runtime.clone()), vec![]);
+        let mut artnet_input = (ArtNetReceiver::new(runtime.clone(), artnet.clone()), vec![]);
+        let mut timecode_input: (Option<Receiver<EndpointData>>, Keys) = (None, vec![]);
         for (k, v) in &self.endpoints {
             let mut point = vec![];
-            for items in v {
+            for (i, items) in v.iter().enumerate() {
                 let (tx, rx) = rocket::tokio::sync::broadcast::channel::<EndpointData>(500);
+                let key = (*k, i);
                 match items {
                     EPConfigItem::Logger => {
-                        register_default!(LoggerEndpoint, rx);
+                        LoggerEndpoint { rx }.register(health.reporter(vec![key]).await);
                     }
-                    EPConfigItem::ArtNet => {
-                        register_default!(ArtNetEndpoint, rx);
+                    EPConfigItem::ArtNet {
+                        port_address,
+                        unicast,
+                        broadcast,
+                        interface,
+                        ..
+                    } => ArtNetEndpoint {
+                        port_address: *port_address,
+                        unicast: unicast.clone(),
+                        broadcast: *broadcast,
+                        interface: *interface,
+                        ..ArtNetEndpoint::new(rx, artnet.clone())
                     }
-                    EPConfigItem::Sacn { universe, speed } => SacnEndpoint {
-                        universe: *universe,
-                        speed: *speed,
-                        ..Default::default()
+                    .register(health.reporter(vec![key]).await),
+                    EPConfigItem::Sacn {
+                        universe,
+                        speed,
+                        priority,
+                        unicast,
+                        sync_universe,
+                        ..
+                    } => {
+                        let endpoint = SacnEndpoint {
+                            universe: *universe,
+                            speed: *speed,
+                            priority: *priority,
+                            unicast: unicast.clone(),
+                            sync_universe: *sync_universe,
+                            source: self.sacn.clone(),
+                            ..Default::default()
+                        };
+                        match sync_universe {
+                            Some(sync) => {
+                                let (group, keys) = sacn_groups.entry(*sync).or_default();
+                                group.add(endpoint, rx);
+                                keys.push(key);
+                            }
+                            None => {
+                                let mut group = SacnSyncGroup::default();
+                                group.add(endpoint, rx);
+                                group.register(health.reporter(vec![key]).await);
+                            }
+                        }
                     }
-                    .register(rx),
-                    EPConfigItem::Usb { speed, port } => UsbEndpoint {
+                    EPConfigItem::Usb { speed, port, .. } => UsbEndpoint {
                         port: port.clone(),
                         speed: *speed,
+                        rx,
+                    }
+                    .register(health.reporter(vec![key]).await),
+                    EPConfigItem::UsbPro { port, .. } => UsbProEndpoint {
+                        port: port.clone(),
+                        rx,
+                    }
+                    .register(health.reporter(vec![key]).await),
+                    EPConfigItem::SacnInput {
+                        universe,
+                        merge,
+                        local_priority,
+                    } => {
+                        let target = InputTarget::new(*k, *merge, *local_priority);
+                        sacn_input.0.add(*universe, target, rx);
+                        sacn_input.1.push(key);
+                    }
+                    EPConfigItem::ArtNetInput {
+                        port_address,
+                        merge,
+                    } => {
+                        let target = InputTarget::new(*k, *merge, DEFAULT_PRIORITY);
+                        artnet_input.0.add(*port_address, target, rx);
+                        artnet_input.1.push(key);
+                    }
+                    EPConfigItem::Ddp {
+                        destination,
+                        mapping,
+                        ..
+                    } => {
+                        let (endpoint, keys) = ddp_groups
+                            .entry(*destination)
+                            .or_insert_with(|| (DdpEndpoint::new(*destination), vec![]));
+                        endpoint.add(*mapping, rx);
+                        keys.push(key);
+                    }
+                    EPConfigItem::Opc {
+                        address,
+                        channel,
+                        mapping,
+                        ..
+                    } => {
+                        let (endpoint, keys) = opc_groups
+                            .entry((address.clone(), *channel))
+                            .or_insert_with(|| {
+                                (OpcEndpoint::new(address.clone(), *channel), vec![])
+                            });
+                        endpoint.add(*mapping, rx);
+                        keys.push(key);
+                    }
+                    EPConfigItem::ArtNetTimecode => {
+                        // All endpoints exit together, so listening to one of them is enough
+                        timecode_input.0.get_or_insert(rx);
+                        timecode_input.1.push(key);
+                    }
+                    EPConfigItem::Recorder { file } => {
+                        let (endpoint, keys) = recorders
+                            .entry(file.clone())
+                            .or_insert_with(|| (RecorderEndpoint::new(file.clone()), vec![]));
+                        endpoint.add(*k, rx);
+                        keys.push(key);
                     }
-                    .register(rx),
                 }
-                point.push(tx);
+                point.push(EndpointSender {
+                    tx,
+                    channel_map: items.channel_map().cloned().flatten(),
+                });
             }
             points.insert(*k, point);
         }
 
+        for (group, keys) in sacn_groups.into_values() {
+            group.register(health.reporter(keys).await);
+        }
+        for (endpoint, keys) in ddp_groups.into_values() {
+            endpoint.register(health.reporter(keys).await);
+        }
+        for (endpoint, keys) in opc_groups.into_values() {
+            endpoint.register(health.reporter(keys).await);
+        }
+        for (endpoint, keys) in recorders.into_values() {
+            endpoint.register(health.reporter(keys).await);
+        }
+        if !sacn_input.1.is_empty() {
+            sacn_input.0.register(health.reporter(sacn_input.1).await);
+        }
+        artnet.timecode().set_chase(!timecode_input.1.is_empty());
+        if let Some(rx) = timecode_input.0 {
+            TimecodeReceiver {
+                sockets: artnet.clone(),
+                rx,
+            }
+            .register(health.reporter(timecode_input.1).await);
+        }
+        if !artnet_input.1.is_empty() {
+            artnet_input
+                .0
+                .register(health.reporter(artnet_input.1).await);
+        }
+
         points
     }
 }
 
-pub trait Endpoint: Default {
-    fn register(self, rx: Receiver<EndpointData>);
+/// The channel the frames of one endpoint config item are sent through
+#[derive(Debug)]
+pub struct EndpointSender {
+    pub tx: Sender<EndpointData>,
+    pub channel_map: Option<ChannelMap>,
+}
+
+pub trait Endpoint: Send + Sized + 'static {
+    /// Runs the endpoint until it receives [`EndpointData::Exit`].
+    ///
+    /// A returned error is reported and the endpoint is run again after a backoff,
+    /// so everything that has to survive a restart has to live in `self`.
+    fn run(&mut self, health: &EndpointHealth) -> impl Future<Output = Result<(), String>> + Send;
+
+    fn register(self, health: EndpointHealth) {
+        rocket::tokio::spawn(supervise(self, health));
+    }
+}
+
+/// The values of a universe as sent by the frame clock of the runtime
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub values: [u8; UNIVERSE_SIZE],
+    /// The values changed since the last tick
+    pub dirty: bool,
+    /// The keep-alive interval elapsed, unchanged values should be sent again
+    pub refresh: bool,
+}
+
+impl Frame {
+    pub fn should_send(&self) -> bool {
+        self.dirty || self.refresh
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum EndpointData {
-    Single {
-        channel: UniverseAddress,
-        value: u8,
-    },
-    Multiple {
-        channels: Vec<UniverseAddress>,
-        values: Vec<u8>,
-    },
-    Entire {
-        values: [u8; UNIVERSE_SIZE],
-    },
+    /// Sent to every endpoint once per tick of the frame clock
+    Frame(Frame),
     Exit,
 }
 
-#[derive(Default)]
-pub struct LoggerEndpoint;
+pub struct LoggerEndpoint {
+    rx: Receiver<EndpointData>,
+}
 
 impl Endpoint for LoggerEndpoint {
-    fn register(self, mut rx: Receiver<EndpointData>) {
-        rocket::tokio::spawn(async move {
-            let id = uuid::Uuid::new_v4();
-            while let Ok(msg) = rx.recv().await {
-                match msg {
-                    EndpointData::Exit => {
-                        println!("[DBG]: {:?} EXIT", &id);
-                        break;
-                    }
-                    s => println!("[DBG]: {:?} {:?}", &id, s),
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let id = uuid::Uuid::new_v4();
+        health.running().await;
+        while let Ok(msg) = self.rx.recv().await {
+            match msg {
+                EndpointData::Exit => {
+                    println!("[DBG]: {:?} EXIT", &id);
+                    break;
                 }
+                EndpointData::Frame(f) if f.dirty => println!("[DBG]: {:?} {:?}", &id, f.values),
+                EndpointData::Frame(_) => {}
             }
-        });
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,132 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::ops::Range;
+
+use rocket::tokio::{net::UdpSocket, sync::broadcast::Receiver};
+
+use mlc_common::endpoints::PixelMapping;
+
+use crate::runtime::endpoints::{
+    group::FrameGroup, health::EndpointHealth, Endpoint, EndpointData,
+};
+
+use super::{compose, spans};
+
+pub const DDP_PORT: u16 = 4048;
+
+const HEADER_LENGTH: usize = 10;
+/// The data of one packet, 480 RGB pixels
+const MAX_DATA_LENGTH: usize = 1440;
+
+const FLAG_VERSION_1: u8 = 0x40;
+/// Tells the controller to display everything received so far
+const FLAG_PUSH: u8 = 0x01;
+/// RGB with 8 bits per color
+const DATA_TYPE_RGB8: u8 = 0x0B;
+/// The default output device of the controller
+const DESTINATION_DISPLAY: u8 = 0x01;
+
+/// All DDP endpoints that send to the same controller
+pub struct DdpEndpoint {
+    destination: Ipv4Addr,
+    mappings: Vec<PixelMapping>,
+    frames: FrameGroup,
+    sequence: u8,
+}
+
+impl DdpEndpoint {
+    pub fn new(destination: Ipv4Addr) -> DdpEndpoint {
+        DdpEndpoint {
+            destination,
+            mappings: vec![],
+            frames: FrameGroup::default(),
+            sequence: 0,
+        }
+    }
+
+    pub fn add(&mut self, mapping: PixelMapping, rx: Receiver<EndpointData>) {
+        self.frames.add(rx);
+        self.mappings.push(mapping);
+    }
+}
+
+impl Endpoint for DdpEndpoint {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .await
+            .map_err(|e| format!("Binding DDP socket failed: {e}"))?;
+        let destination = SocketAddr::from((self.destination, DDP_PORT));
+        let spans = spans(&self.mappings);
+        health.running().await;
+
+        while let Some(round) = self.frames.recv().await {
+            if !(round.dirty || round.refresh) {
+                continue;
+            }
+
+            let data = compose(self.mappings.iter().zip(self.frames.values()));
+            // The sequence only uses 4 bits, 0 would disable it
+            self.sequence = self.sequence % 15 + 1;
+            for p in packets(&data, &spans, self.sequence) {
+                if let Err(e) = socket.send_to(&p, destination).await {
+                    eprintln!("[DDP] Sending to {destination} failed: {e}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the spans of the pixel data into packets, only the last one pushes the frame to the display
+fn packets(data: &[u8], spans: &[Range<usize>], sequence: u8) -> Vec<Vec<u8>> {
+    let mut packets = vec![];
+    for span in spans {
+        let mut offset = span.start;
+        while offset < span.end {
+            let end = (offset + MAX_DATA_LENGTH).min(span.end);
+            let mut p = Vec::with_capacity(HEADER_LENGTH + end - offset);
+            p.push(FLAG_VERSION_1);
+            p.push(sequence & 0x0F);
+            p.push(DATA_TYPE_RGB8);
+            p.push(DESTINATION_DISPLAY);
+            p.extend_from_slice(&(offset as u32).to_be_bytes());
+            p.extend_from_slice(&((end - offset) as u16).to_be_bytes());
+            p.extend_from_slice(&data[offset..end]);
+            packets.push(p);
+            offset = end;
+        }
+    }
+
+    if let Some(last) = packets.last_mut() {
+        last[0] |= FLAG_PUSH;
+    }
+    packets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packet_layout() {
+        let data = (0..2000).map(|i| i as u8).collect::<Vec<_>>();
+        let packets = packets(&data, &[0..1500, 1800..2000], 3);
+
+        assert_eq!(packets.len(), 3);
+        assert_eq!(
+            &packets[0][..10],
+            &[0x40, 3, 0x0B, 1, 0, 0, 0, 0, 0x05, 0xA0]
+        );
+        assert_eq!(packets[0].len(), 10 + 1440);
+        assert_eq!(
+            &packets[1][..10],
+            &[0x40, 3, 0x0B, 1, 0, 0, 0x05, 0xA0, 0, 60]
+        );
+        assert_eq!(packets[1][10], data[1440]);
+        assert_eq!(
+            &packets[2][..10],
+            &[0x41, 3, 0x0B, 1, 0, 0, 0x07, 0x08, 0, 200]
+        );
+        assert_eq!(packets[2][10], data[1800]);
+    }
+}
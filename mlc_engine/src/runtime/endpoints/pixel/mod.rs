@@ -0,0 +1,108 @@
+//! Endpoints for LED pixel controllers.
+//!
+//! A controller usually spans several universes. Every endpoint config maps a channel range of its universe into the
+//! pixel data of the controller, and all configs for the same controller are sent together by one endpoint.
+
+use std::ops::Range;
+
+use mlc_common::endpoints::PixelMapping;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+pub use self::{ddp::DdpEndpoint, opc::OpcEndpoint};
+
+mod ddp;
+mod opc;
+
+/// The channels of the universe that are sent, clamped to the universe
+fn channels(mapping: &PixelMapping) -> Range<usize> {
+    let start = (mapping.start as usize).min(UNIVERSE_SIZE);
+    let end = (start + mapping.length as usize).min(UNIVERSE_SIZE);
+    start..end
+}
+
+/// The bytes of the pixel data the mapping writes to
+fn target(mapping: &PixelMapping) -> Range<usize> {
+    let start = mapping.offset as usize;
+    start..start + channels(mapping).len()
+}
+
+/// Writes the mapped channels of every universe into one pixel buffer starting at byte 0.
+/// Bytes no mapping writes to stay 0, later mappings overwrite earlier ones
+fn compose<'a>(
+    members: impl Iterator<Item = (&'a PixelMapping, &'a [u8; UNIVERSE_SIZE])>,
+) -> Vec<u8> {
+    let mut data = vec![];
+    for (mapping, values) in members {
+        let target = target(mapping);
+        if data.len() < target.end {
+            data.resize(target.end, 0);
+        }
+        data[target].copy_from_slice(&values[channels(mapping)]);
+    }
+    data
+}
+
+/// The parts of the pixel data that are written by any mapping, sorted and merged where they touch
+fn spans(mappings: &[PixelMapping]) -> Vec<Range<usize>> {
+    let mut targets = mappings
+        .iter()
+        .map(target)
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>();
+    targets.sort_by_key(|t| t.start);
+
+    let mut spans: Vec<Range<usize>> = vec![];
+    for t in targets {
+        match spans.last_mut() {
+            Some(last) if t.start <= last.end => last.end = last.end.max(t.end),
+            _ => spans.push(t),
+        }
+    }
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(start: u16, length: u16, offset: u32) -> PixelMapping {
+        PixelMapping {
+            start,
+            length,
+            offset,
+        }
+    }
+
+    #[test]
+    fn compose_universes() {
+        let a = [1u8; UNIVERSE_SIZE];
+        let b = [2u8; UNIVERSE_SIZE];
+        let mappings = [mapping(0, 510, 0), mapping(0, 510, 510)];
+        let data = compose(mappings.iter().zip([&a, &b]));
+
+        assert_eq!(data.len(), 1020);
+        assert_eq!(data[509], 1);
+        assert_eq!(data[510], 2);
+    }
+
+    #[test]
+    fn compose_clamps_to_universe() {
+        let mut a = [0u8; UNIVERSE_SIZE];
+        a[500] = 9;
+        let mappings = [mapping(500, 100, 3)];
+        let data = compose(mappings.iter().zip([&a]));
+
+        assert_eq!(data, [0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn spans_merge() {
+        let mappings = [
+            mapping(0, 30, 100),
+            mapping(0, 30, 0),
+            mapping(0, 30, 30),
+            mapping(0, 0, 50),
+        ];
+        assert_eq!(spans(&mappings), vec![0..60, 100..130]);
+    }
+}
@@ -0,0 +1,76 @@
+use rocket::tokio::{io::AsyncWriteExt, net::TcpStream, sync::broadcast::Receiver};
+
+use mlc_common::endpoints::PixelMapping;
+
+use crate::runtime::endpoints::{
+    group::FrameGroup, health::EndpointHealth, Endpoint, EndpointData,
+};
+
+use super::compose;
+
+const DEFAULT_PORT: u16 = 7890;
+const COMMAND_SET_PIXEL_COLORS: u8 = 0;
+
+/// All OPC endpoints that send to the same channel of the same server
+pub struct OpcEndpoint {
+    address: String,
+    channel: u8,
+    mappings: Vec<PixelMapping>,
+    frames: FrameGroup,
+}
+
+impl OpcEndpoint {
+    pub fn new(address: String, channel: u8) -> OpcEndpoint {
+        OpcEndpoint {
+            address,
+            channel,
+            mappings: vec![],
+            frames: FrameGroup::default(),
+        }
+    }
+
+    pub fn add(&mut self, mapping: PixelMapping, rx: Receiver<EndpointData>) {
+        self.frames.add(rx);
+        self.mappings.push(mapping);
+    }
+}
+
+impl Endpoint for OpcEndpoint {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let address = if self.address.contains(':') {
+            self.address.clone()
+        } else {
+            format!("{}:{DEFAULT_PORT}", self.address)
+        };
+        let mut stream = TcpStream::connect(&address)
+            .await
+            .map_err(|e| format!("Connecting to OPC server {address} failed: {e}"))?;
+        stream.set_nodelay(true).map_err(|e| e.to_string())?;
+        health.running().await;
+
+        while let Some(round) = self.frames.recv().await {
+            if !(round.dirty || round.refresh) {
+                continue;
+            }
+
+            let data = compose(self.mappings.iter().zip(self.frames.values()));
+            stream
+                .write_all(&message(self.channel, &data))
+                .await
+                .map_err(|e| format!("Sending to OPC server {address} failed: {e}"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A set pixel colors message, the data is cut off at the maximum message length
+fn message(channel: u8, data: &[u8]) -> Vec<u8> {
+    let data = &data[..data.len().min(u16::MAX as usize)];
+    let mut buf = Vec::with_capacity(data.len() + 4);
+    buf.push(channel);
+    buf.push(COMMAND_SET_PIXEL_COLORS);
+    buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    buf.extend_from_slice(data);
+    buf
+}
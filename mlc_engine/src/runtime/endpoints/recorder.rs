@@ -0,0 +1,83 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use chrono::Local;
+use rocket::tokio::sync::broadcast::Receiver;
+
+use mlc_common::patched::UniverseId;
+use mlc_common::recording::{RecordingWriter, EXTENSION};
+
+use crate::project::data_dir;
+use crate::runtime::endpoints::{
+    group::FrameGroup, health::EndpointHealth, Endpoint, EndpointData,
+};
+
+/// The path of the recording with the given name, `None` if the name would leave the data directory
+pub fn recording_path(name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return None;
+    }
+    data_dir().map(|dir| dir.join(format!("{name}.{EXTENSION}")))
+}
+
+/// All recorders that write into the same recording
+pub struct RecorderEndpoint {
+    name: String,
+    universes: Vec<UniverseId>,
+    frames: FrameGroup,
+    /// Kept across restarts, so a failed write continues the recording instead of overwriting it
+    recording: Option<(RecordingWriter<BufWriter<File>>, Instant)>,
+}
+
+impl RecorderEndpoint {
+    pub fn new(name: String) -> RecorderEndpoint {
+        RecorderEndpoint {
+            name,
+            universes: vec![],
+            frames: FrameGroup::default(),
+            recording: None,
+        }
+    }
+
+    pub fn add(&mut self, universe: UniverseId, rx: Receiver<EndpointData>) {
+        self.frames.add(rx);
+        self.universes.push(universe);
+    }
+}
+
+impl Endpoint for RecorderEndpoint {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let (writer, start) = match &mut self.recording {
+            Some(recording) => recording,
+            None => {
+                // Every session gets a file of its own, so restarting the endpoints never overwrites a recording
+                let session = format!("{}_{}", self.name, Local::now().format("%Y-%m-%d_%H-%M-%S"));
+                let path = recording_path(&session)
+                    .ok_or(format!("Invalid recording name {}", self.name))?;
+                let file = File::create_new(&path)
+                    .map_err(|e| format!("Creating recording {} failed: {e}", path.display()))?;
+                let writer =
+                    RecordingWriter::new(BufWriter::new(file)).map_err(|e| e.to_string())?;
+                self.recording.insert((writer, Instant::now()))
+            }
+        };
+        health.running().await;
+
+        while let Some(round) = self.frames.recv().await {
+            if !round.dirty {
+                continue;
+            }
+
+            let time = start.elapsed();
+            for (universe, values) in self.universes.iter().zip(self.frames.values()) {
+                writer
+                    .write(time, *universe, values)
+                    .map_err(|e| format!("Writing recording {} failed: {e}", self.name))?;
+            }
+        }
+
+        writer.flush().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use rocket::tokio::{net::UdpSocket, pin, select, sync::broadcast::Receiver, time::interval};
+
+use mlc_common::universe::UNIVERSE_SIZE;
+
+use crate::runtime::endpoints::input::{wait_for_exit, InputStreams, InputTarget, PRUNE_INTERVAL};
+use crate::runtime::endpoints::{health::EndpointHealth, Endpoint, EndpointData};
+use crate::runtime::RuntimeData;
+
+use super::packet::{multicast_address, parse_data_packet, SACN_PORT};
+
+/// E1.31 network data loss timeout
+const DATA_LOSS_TIMEOUT: Duration = Duration::from_millis(2500);
+
+/// Receives all sACN input endpoints on a single socket, as only one socket can listen on the sACN port
+pub struct SacnReceiver {
+    runtime: RuntimeData,
+    inputs: Vec<(u16, InputTarget)>,
+    exit: Option<Receiver<EndpointData>>,
+}
+
+impl SacnReceiver {
+    pub fn new(runtime: RuntimeData) -> SacnReceiver {
+        SacnReceiver {
+            runtime,
+            inputs: vec![],
+            exit: None,
+        }
+    }
+
+    pub fn add(&mut self, universe: u16, target: InputTarget, rx: Receiver<EndpointData>) {
+        self.inputs.push((universe, target));
+        // All endpoints exit together, so listening to one of them is enough
+        self.exit.get_or_insert(rx);
+    }
+}
+
+impl Endpoint for SacnReceiver {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let Some(exit) = &mut self.exit else {
+            return Ok(());
+        };
+
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, SACN_PORT))
+            .await
+            .map_err(|e| format!("Binding sACN input port failed: {e}"))?;
+
+        let mut streams: HashMap<u16, InputStreams<uuid::Uuid>> = HashMap::new();
+        for (universe, _) in &self.inputs {
+            if streams.contains_key(universe) {
+                continue;
+            }
+            if let Err(e) =
+                socket.join_multicast_v4(multicast_address(*universe), Ipv4Addr::UNSPECIFIED)
+            {
+                eprintln!("[SACN] Joining universe {universe} failed: {e}");
+            }
+            streams.insert(*universe, InputStreams::new(DATA_LOSS_TIMEOUT));
+        }
+
+        health.running().await;
+
+        let exit = wait_for_exit(exit);
+        pin!(exit);
+        let mut prune = interval(PRUNE_INTERVAL);
+        let mut buffer = [0u8; 1144];
+
+        loop {
+            select! {
+                _ = &mut exit => break,
+                Ok(length) = socket.recv(&mut buffer) => {
+                    let Some(packet) = parse_data_packet(&buffer[..length]) else {
+                        continue;
+                    };
+                    if packet.preview {
+                        continue;
+                    }
+                    let Some(s) = streams.get_mut(&packet.universe) else {
+                        continue;
+                    };
+
+                    let changed = if packet.terminated {
+                        s.remove(&packet.cid)
+                    } else {
+                        s.update(packet.cid, Some(packet.sequence), packet.priority, packet.data)
+                    };
+                    if changed {
+                        let frame = s.frame();
+                        send(&self.runtime, &self.inputs, packet.universe, frame).await;
+                    }
+                },
+                _ = prune.tick() => {
+                    for (universe, s) in &mut streams {
+                        if s.prune() {
+                            send(&self.runtime, &self.inputs, *universe, s.frame()).await;
+                        }
+                    }
+                }
+            }
+        }
+
+        for (_, target) in &self.inputs {
+            target.send(&self.runtime, None).await;
+        }
+        Ok(())
+    }
+}
+
+async fn send(
+    runtime: &RuntimeData,
+    inputs: &[(u16, InputTarget)],
+    universe: u16,
+    frame: Option<([u8; UNIVERSE_SIZE], u8)>,
+) {
+    for (u, target) in inputs {
+        if *u == universe {
+            target.send(runtime, frame).await;
+        }
+    }
+}
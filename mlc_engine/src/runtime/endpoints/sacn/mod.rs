@@ -0,0 +1,161 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use rocket::tokio::{net::UdpSocket, sync::broadcast::Receiver, time::Instant};
+
+use mlc_common::endpoints::{SacnSource, Speed};
+use mlc_common::universe::UNIVERSE_SIZE;
+
+use self::packet::{multicast_address, sync_packet, DataPacket, SACN_PORT};
+
+pub use self::input::SacnReceiver;
+
+use super::{group::FrameGroup, health::EndpointHealth, Endpoint, EndpointData};
+
+mod input;
+mod packet;
+
+/// How often the stream terminated packet is repeated when an endpoint shuts down
+const TERMINATE_REPEATS: usize = 3;
+
+pub struct SacnEndpoint {
+    pub data: [u8; UNIVERSE_SIZE],
+    pub universe: u16,
+    pub speed: Speed,
+    pub priority: u8,
+    pub unicast: Vec<Ipv4Addr>,
+    pub sync_universe: Option<u16>,
+    pub source: SacnSource,
+    pub(crate) sequence: u8,
+}
+
+impl Default for SacnEndpoint {
+    fn default() -> Self {
+        Self {
+            data: [0; UNIVERSE_SIZE],
+            universe: 1,
+            speed: Speed::Medium,
+            priority: 100,
+            unicast: vec![],
+            sync_universe: None,
+            source: SacnSource::default(),
+            sequence: 0,
+        }
+    }
+}
+
+impl SacnEndpoint {
+    fn destinations(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        std::iter::once(multicast_address(self.universe))
+            .chain(self.unicast.iter().copied())
+            .map(|ip| SocketAddr::from((ip, SACN_PORT)))
+    }
+
+    async fn send(&mut self, socket: &UdpSocket, terminated: bool) {
+        self.sequence = self.sequence.wrapping_add(1);
+        let buf = DataPacket {
+            cid: &self.source.cid,
+            source_name: &self.source.name,
+            priority: self.priority,
+            sync_address: self.sync_universe.unwrap_or(0),
+            sequence: self.sequence,
+            terminated,
+            universe: self.universe,
+            data: &self.data,
+        }
+        .encode();
+
+        for a in self.destinations() {
+            if let Err(e) = socket.send_to(&buf, a).await {
+                eprintln!(
+                    "[SACN] Sending universe {} to {a} failed: {e}",
+                    self.universe
+                );
+            }
+        }
+    }
+}
+
+/// All sACN endpoints that share a sync universe.
+///
+/// They are sent from a single task once the frames of all members for a tick of the frame clock arrived, and every
+/// round is followed by one E1.31 sync packet, so receivers switch all universes of the group in the same moment.
+#[derive(Default)]
+pub struct SacnSyncGroup {
+    endpoints: Vec<SacnEndpoint>,
+    frames: FrameGroup,
+}
+
+impl SacnSyncGroup {
+    pub fn add(&mut self, endpoint: SacnEndpoint, rx: Receiver<EndpointData>) {
+        self.frames.add(rx);
+        self.endpoints.push(endpoint);
+    }
+
+    async fn send_terminated(&mut self, socket: &UdpSocket) {
+        for _ in 0..TERMINATE_REPEATS {
+            for e in &mut self.endpoints {
+                e.send(socket, true).await;
+            }
+        }
+    }
+}
+
+impl Endpoint for SacnSyncGroup {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+            .await
+            .map_err(|e| format!("Binding sACN socket failed: {e}"))?;
+
+        // The fastest member sets the pace for the whole group
+        let Some(min_period) = self.endpoints.iter().map(|e| e.speed.get_duration()).min() else {
+            return Ok(());
+        };
+
+        let sync = self.endpoints[0].sync_universe;
+        let cid = self.endpoints[0].source.cid;
+        let mut sync_destinations = vec![];
+        if let Some(sync) = sync {
+            sync_destinations.push(SocketAddr::from((multicast_address(sync), SACN_PORT)));
+            for ip in self.endpoints.iter().flat_map(|e| &e.unicast) {
+                let a = SocketAddr::from((*ip, SACN_PORT));
+                if !sync_destinations.contains(&a) {
+                    sync_destinations.push(a);
+                }
+            }
+        }
+        let mut sync_sequence = 0u8;
+
+        health.running().await;
+
+        // Changes that were held back by the speed of the group are sent with the next round
+        let mut dirty = false;
+        let mut last_send: Option<Instant> = None;
+        while let Some(round) = self.frames.recv().await {
+            dirty |= round.dirty;
+            let due = last_send.is_none_or(|t| t.elapsed() >= min_period);
+            if !(round.refresh || (dirty && due)) {
+                continue;
+            }
+            dirty = false;
+            last_send = Some(Instant::now());
+
+            for (index, e) in self.endpoints.iter_mut().enumerate() {
+                e.data = self.frames.values()[index];
+                e.send(&socket, false).await;
+            }
+
+            if let Some(sync) = sync {
+                sync_sequence = sync_sequence.wrapping_add(1);
+                let buf = sync_packet(&cid, sync_sequence, sync);
+                for a in &sync_destinations {
+                    if let Err(e) = socket.send_to(&buf, a).await {
+                        eprintln!("[SACN] Sending sync {sync} to {a} failed: {e}");
+                    }
+                }
+            }
+        }
+
+        self.send_terminated(&socket).await;
+        Ok(())
+    }
+}
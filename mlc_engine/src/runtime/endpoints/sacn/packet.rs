@@ -0,0 +1,281 @@
+//! Encoding and decoding of the E1.31 (sACN) packets we use.
+//!
+//! Only the data and the synchronization packet are needed, so they are written by hand instead of
+//! pulling in a full sACN implementation.
+
+use std::net::Ipv4Addr;
+
+use mlc_common::universe::UNIVERSE_SIZE;
+use uuid::Uuid;
+
+pub const SACN_PORT: u16 = 5568;
+pub const MAX_PRIORITY: u8 = 200;
+
+const ACN_PACKET_IDENTIFIER: [u8; 12] = *b"ASC-E1.17\0\0\0";
+const VECTOR_ROOT_E131_DATA: u32 = 0x0000_0004;
+const VECTOR_ROOT_E131_EXTENDED: u32 = 0x0000_0008;
+const VECTOR_E131_DATA_PACKET: u32 = 0x0000_0002;
+const VECTOR_E131_EXTENDED_SYNCHRONIZATION: u32 = 0x0000_0001;
+const VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+
+const SOURCE_NAME_LENGTH: usize = 64;
+const DATA_PACKET_LENGTH: usize = 126 + UNIVERSE_SIZE;
+const SYNC_PACKET_LENGTH: usize = 49;
+
+const OPTION_PREVIEW: u8 = 0b1000_0000;
+const OPTION_STREAM_TERMINATED: u8 = 0b0100_0000;
+
+/// The multicast group receivers of `universe` listen on
+pub fn multicast_address(universe: u16) -> Ipv4Addr {
+    let [hi, lo] = universe.to_be_bytes();
+    Ipv4Addr::new(239, 255, hi, lo)
+}
+
+pub struct DataPacket<'a> {
+    pub cid: &'a Uuid,
+    pub source_name: &'a str,
+    pub priority: u8,
+    /// 0 when the universe is not synchronized
+    pub sync_address: u16,
+    pub sequence: u8,
+    pub terminated: bool,
+    pub universe: u16,
+    pub data: &'a [u8; UNIVERSE_SIZE],
+}
+
+impl DataPacket<'_> {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(DATA_PACKET_LENGTH);
+
+        root_layer(
+            &mut buf,
+            VECTOR_ROOT_E131_DATA,
+            self.cid,
+            DATA_PACKET_LENGTH,
+        );
+
+        // Framing layer
+        flags_and_length(&mut buf, DATA_PACKET_LENGTH);
+        buf.extend_from_slice(&VECTOR_E131_DATA_PACKET.to_be_bytes());
+        buf.extend_from_slice(&source_name(self.source_name));
+        buf.push(self.priority.min(MAX_PRIORITY));
+        buf.extend_from_slice(&self.sync_address.to_be_bytes());
+        buf.push(self.sequence);
+        buf.push(if self.terminated {
+            OPTION_STREAM_TERMINATED
+        } else {
+            0
+        });
+        buf.extend_from_slice(&self.universe.to_be_bytes());
+
+        // DMP layer
+        flags_and_length(&mut buf, DATA_PACKET_LENGTH);
+        buf.push(VECTOR_DMP_SET_PROPERTY);
+        // Address & data type, first property address and address increment are fixed
+        buf.push(0xa1);
+        buf.extend_from_slice(&0u16.to_be_bytes());
+        buf.extend_from_slice(&1u16.to_be_bytes());
+        buf.extend_from_slice(&(UNIVERSE_SIZE as u16 + 1).to_be_bytes());
+        // DMX start code
+        buf.push(0);
+        buf.extend_from_slice(self.data);
+
+        buf
+    }
+}
+
+/// A data packet as it was received
+#[derive(Debug)]
+pub struct ReceivedData<'a> {
+    pub cid: Uuid,
+    pub priority: u8,
+    pub sequence: u8,
+    pub preview: bool,
+    pub terminated: bool,
+    pub universe: u16,
+    /// The slots after the start code, may be shorter than a full universe
+    pub data: &'a [u8],
+}
+
+/// Returns `None` for everything but a valid data packet with the DMX start code
+pub fn parse_data_packet(buf: &[u8]) -> Option<ReceivedData<'_>> {
+    if buf.len() < DATA_PACKET_LENGTH - UNIVERSE_SIZE || buf[4..16] != ACN_PACKET_IDENTIFIER {
+        return None;
+    }
+
+    let vector = |at: usize| u32::from_be_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]]);
+    if vector(18) != VECTOR_ROOT_E131_DATA
+        || vector(40) != VECTOR_E131_DATA_PACKET
+        || buf[117] != VECTOR_DMP_SET_PROPERTY
+    {
+        return None;
+    }
+
+    // The property value count includes the start code
+    let count = u16::from_be_bytes([buf[123], buf[124]]) as usize;
+    if count == 0 || buf.len() < 125 + count || buf[125] != 0 {
+        return None;
+    }
+
+    Some(ReceivedData {
+        cid: Uuid::from_bytes(buf[22..38].try_into().ok()?),
+        priority: buf[108],
+        sequence: buf[111],
+        preview: buf[112] & OPTION_PREVIEW != 0,
+        terminated: buf[112] & OPTION_STREAM_TERMINATED != 0,
+        universe: u16::from_be_bytes([buf[113], buf[114]]),
+        data: &buf[126..(125 + count).min(DATA_PACKET_LENGTH)],
+    })
+}
+
+/// Tells all receivers of universes with `sync_address` to output the data they have buffered
+pub fn sync_packet(cid: &Uuid, sequence: u8, sync_address: u16) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(SYNC_PACKET_LENGTH);
+
+    root_layer(&mut buf, VECTOR_ROOT_E131_EXTENDED, cid, SYNC_PACKET_LENGTH);
+
+    flags_and_length(&mut buf, SYNC_PACKET_LENGTH);
+    buf.extend_from_slice(&VECTOR_E131_EXTENDED_SYNCHRONIZATION.to_be_bytes());
+    buf.push(sequence);
+    buf.extend_from_slice(&sync_address.to_be_bytes());
+    // Reserved
+    buf.extend_from_slice(&[0, 0]);
+
+    buf
+}
+
+fn root_layer(buf: &mut Vec<u8>, vector: u32, cid: &Uuid, total: usize) {
+    // Preamble and postamble size
+    buf.extend_from_slice(&0x0010u16.to_be_bytes());
+    buf.extend_from_slice(&0x0000u16.to_be_bytes());
+    buf.extend_from_slice(&ACN_PACKET_IDENTIFIER);
+    flags_and_length(buf, total);
+    buf.extend_from_slice(&vector.to_be_bytes());
+    buf.extend_from_slice(cid.as_bytes());
+}
+
+/// Every PDU starts with its length in the lower 12 bits and the flags 0x7 in the upper 4 bits.
+/// A PDU always reaches to the end of the packet, so its length is what is left of `total`.
+fn flags_and_length(buf: &mut Vec<u8>, total: usize) {
+    let length = (total - buf.len()) as u16;
+    buf.extend_from_slice(&(0x7000 | (length & 0x0fff)).to_be_bytes());
+}
+
+/// The source name is UTF-8, null terminated and padded to 64 bytes
+fn source_name(name: &str) -> [u8; SOURCE_NAME_LENGTH] {
+    let mut out = [0u8; SOURCE_NAME_LENGTH];
+    let mut end = name.len().min(SOURCE_NAME_LENGTH - 1);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    out[..end].copy_from_slice(&name.as_bytes()[..end]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet<'a>(cid: &'a Uuid, data: &'a [u8; UNIVERSE_SIZE]) -> DataPacket<'a> {
+        DataPacket {
+            cid,
+            source_name: "MLC Controller",
+            priority: 100,
+            sync_address: 0,
+            sequence: 7,
+            terminated: false,
+            universe: 258,
+            data,
+        }
+    }
+
+    #[test]
+    fn data_packet_layout() {
+        let cid = Uuid::new_v4();
+        let mut data = [0u8; UNIVERSE_SIZE];
+        data[0] = 255;
+        data[511] = 17;
+        let buf = packet(&cid, &data).encode();
+
+        assert_eq!(buf.len(), 638);
+        assert_eq!(&buf[4..16], b"ASC-E1.17\0\0\0");
+        assert_eq!(&buf[16..18], &[0x72, 0x6e]);
+        assert_eq!(&buf[18..22], &[0, 0, 0, 4]);
+        assert_eq!(&buf[22..38], cid.as_bytes());
+        assert_eq!(&buf[38..40], &[0x72, 0x58]);
+        assert_eq!(&buf[44..58], b"MLC Controller");
+        assert_eq!(buf[58], 0);
+        assert_eq!(buf[108], 100);
+        assert_eq!(buf[111], 7);
+        assert_eq!(&buf[113..115], &[1, 2]);
+        assert_eq!(&buf[115..117], &[0x72, 0x0b]);
+        assert_eq!(&buf[123..125], &[0x02, 0x01]);
+        assert_eq!(buf[125], 0);
+        assert_eq!(buf[126], 255);
+        assert_eq!(buf[637], 17);
+    }
+
+    #[test]
+    fn data_packet_options() {
+        let cid = Uuid::new_v4();
+        let data = [0u8; UNIVERSE_SIZE];
+        let name = "ä".repeat(40);
+        let mut p = packet(&cid, &data);
+        p.priority = 255;
+        p.sync_address = 7000;
+        p.terminated = true;
+        p.source_name = &name;
+        let buf = p.encode();
+
+        assert_eq!(buf[108], MAX_PRIORITY);
+        assert_eq!(&buf[109..111], &7000u16.to_be_bytes());
+        assert_eq!(buf[112], OPTION_STREAM_TERMINATED);
+        // 31 two byte characters fit before the terminating null
+        assert!(std::str::from_utf8(&buf[44..106]).is_ok());
+        assert_eq!(&buf[106..108], &[0, 0]);
+    }
+
+    #[test]
+    fn parse_own_packet() {
+        let cid = Uuid::new_v4();
+        let mut data = [0u8; UNIVERSE_SIZE];
+        data[9] = 42;
+        let mut p = packet(&cid, &data);
+        p.priority = 150;
+        p.terminated = true;
+        let buf = p.encode();
+
+        let r = parse_data_packet(&buf).expect("Valid packet");
+        assert_eq!(r.cid, cid);
+        assert_eq!(r.priority, 150);
+        assert_eq!(r.sequence, 7);
+        assert!(r.terminated);
+        assert!(!r.preview);
+        assert_eq!(r.universe, 258);
+        assert_eq!(r.data.len(), UNIVERSE_SIZE);
+        assert_eq!(r.data[9], 42);
+
+        assert!(parse_data_packet(&buf[..200]).is_none());
+        assert!(parse_data_packet(&sync_packet(&cid, 1, 1)).is_none());
+    }
+
+    #[test]
+    fn sync_packet_layout() {
+        let cid = Uuid::new_v4();
+        let buf = sync_packet(&cid, 3, 999);
+
+        assert_eq!(buf.len(), 49);
+        assert_eq!(&buf[16..18], &[0x70, 0x21]);
+        assert_eq!(&buf[18..22], &[0, 0, 0, 8]);
+        assert_eq!(&buf[38..40], &[0x70, 0x0b]);
+        assert_eq!(&buf[40..44], &[0, 0, 0, 1]);
+        assert_eq!(buf[44], 3);
+        assert_eq!(&buf[45..47], &999u16.to_be_bytes());
+    }
+
+    #[test]
+    fn multicast_groups() {
+        assert_eq!(multicast_address(1), Ipv4Addr::new(239, 255, 0, 1));
+        assert_eq!(multicast_address(63999), Ipv4Addr::new(239, 255, 249, 255));
+    }
+}
@@ -1,44 +1,32 @@
-use crate::runtime::endpoints::{Endpoint, EndpointData};
+use crate::runtime::endpoints::{health::EndpointHealth, Endpoint, EndpointData};
 use mlc_common::endpoints::Speed;
 use open_dmx::DMXSerial;
+use rocket::tokio::sync::broadcast::{error::RecvError, Receiver};
 
 pub struct UsbEndpoint {
     pub(crate) port: String,
     pub(crate) speed: Speed,
-}
-
-impl Default for UsbEndpoint {
-    fn default() -> Self {
-        Self {
-            port: String::new(),
-            speed: Speed::Medium,
-        }
-    }
+    pub(crate) rx: Receiver<EndpointData>,
 }
 
 impl Endpoint for UsbEndpoint {
-    fn register(self, mut rx: rocket::tokio::sync::broadcast::Receiver<EndpointData>) {
-        rocket::tokio::spawn(async move {
-            let mut dmx = DMXSerial::open(&self.port).unwrap();
-            dmx.set_packet_time(self.speed.get_duration());
-            while let Ok(data) = rx.recv().await {
-                match data {
-                    EndpointData::Single { channel, value } => {
-                        dmx.set_channel(channel.i() + 1, value)
-                            .expect("Sending usb data failed");
-                    }
-                    EndpointData::Multiple { channels, values } => {
-                        for (i, channel) in channels.iter().enumerate() {
-                            dmx.set_channel(channel.i() + 1, values[i])
-                                .expect("Sending usb data failed");
-                        }
-                    }
-                    EndpointData::Entire { values } => {
-                        dmx.set_channels(values);
-                    }
-                    EndpointData::Exit => {}
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let mut dmx = DMXSerial::open(&self.port)
+            .map_err(|e| format!("Opening {} failed: {e:?}", self.port))?;
+        dmx.set_packet_time(self.speed.get_duration());
+        health.running().await;
+
+        loop {
+            match self.rx.recv().await {
+                // The serial thread of open_dmx sends the buffer on its own pace and stops once the interface is gone
+                Ok(EndpointData::Frame(frame)) => {
+                    dmx.check_agent()
+                        .map_err(|e| format!("{} disconnected: {e:?}", self.port))?;
+                    dmx.set_channels(frame.values);
                 }
+                Ok(EndpointData::Exit) | Err(RecvError::Closed) => return Ok(()),
+                Err(RecvError::Lagged(_)) => {}
             }
-        });
+        }
     }
 }
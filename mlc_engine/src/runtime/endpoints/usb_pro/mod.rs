@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use rocket::tokio::{
+    sync::broadcast::{error::RecvError, Receiver},
+    task::spawn_blocking,
+};
+use serialport::SerialPort;
+
+use mlc_common::endpoints::{EndpointDevice, UsbProDevice};
+
+use self::widget::Widget;
+
+use super::{health::EndpointHealth, Endpoint, EndpointData};
+
+mod widget;
+
+/// The widget shows up as a virtual serial port, so the baud rate is ignored
+const BAUD_RATE: u32 = 57_600;
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Outputs a universe through an Enttec DMX USB Pro compatible widget
+pub struct UsbProEndpoint {
+    pub(crate) port: String,
+    pub(crate) rx: Receiver<EndpointData>,
+}
+
+impl Endpoint for UsbProEndpoint {
+    async fn run(&mut self, health: &EndpointHealth) -> Result<(), String> {
+        let port = self.port.clone();
+        let (mut widget, device) = blocking(move || {
+            let serial = serialport::new(&port, BAUD_RATE)
+                .timeout(TIMEOUT)
+                .open()
+                .map_err(|e| format!("Opening {port} failed: {e}"))?;
+            let mut widget: Widget<Box<dyn SerialPort>> = Widget::new(serial);
+
+            let serial_number = widget
+                .serial_number()
+                .map_err(|e| format!("Reading the serial number of {port} failed: {e}"))?;
+            let parameters = widget
+                .parameters()
+                .map_err(|e| format!("Reading the parameters of {port} failed: {e}"))?;
+            let device = UsbProDevice {
+                serial_number,
+                firmware: parameters.firmware,
+                break_time: parameters.break_time,
+                mab_time: parameters.mab_time,
+                refresh_rate: parameters.refresh_rate,
+            };
+            Ok((widget, device))
+        })
+        .await?;
+        health.device(EndpointDevice::UsbPro(device)).await;
+        health.running().await;
+
+        loop {
+            match self.rx.recv().await {
+                Ok(EndpointData::Frame(frame)) if frame.should_send() => {
+                    let port = self.port.clone();
+                    widget = blocking(move || {
+                        widget
+                            .send_dmx(&frame.values)
+                            .map_err(|e| format!("Sending to {port} failed: {e}"))?;
+                        Ok(widget)
+                    })
+                    .await?;
+                }
+                Ok(EndpointData::Frame(_)) | Err(RecvError::Lagged(_)) => {}
+                Ok(EndpointData::Exit) | Err(RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
+}
+
+/// Serial I/O blocks, so it is moved off the async runtime
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    spawn_blocking(f).await.map_err(|e| e.to_string())?
+}
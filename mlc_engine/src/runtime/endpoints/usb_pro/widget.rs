@@ -0,0 +1,225 @@
+//! The serial protocol of the Enttec DMX USB Pro and compatible widgets.
+//!
+//! Every message is framed as `0x7E, label, length LSB, length MSB, data, 0xE7`. The widget is generic over the
+//! port, so it can be driven by a serial port as well as by an in-memory buffer.
+
+use std::io::{self, Read, Write};
+
+use mlc_common::universe::UNIVERSE_SIZE;
+
+const START: u8 = 0x7E;
+const END: u8 = 0xE7;
+
+const LABEL_GET_PARAMETERS: u8 = 3;
+const LABEL_OUTPUT_ONLY_SEND_DMX: u8 = 6;
+const LABEL_GET_SERIAL_NUMBER: u8 = 10;
+
+/// The DMX start code of dimmer data
+const START_CODE: u8 = 0x00;
+
+/// How many unrelated messages are skipped while waiting for a reply
+const MAX_SKIPPED: usize = 16;
+
+/// The configuration stored on the widget
+#[derive(Debug, Clone, PartialEq)]
+pub struct WidgetParameters {
+    pub firmware: u16,
+    /// DMX break time in 10.67 microsecond units
+    pub break_time: u8,
+    /// DMX mark after break time in 10.67 microsecond units
+    pub mab_time: u8,
+    /// DMX packets per second, 0 sends as fast as possible
+    pub refresh_rate: u8,
+}
+
+pub struct Widget<P> {
+    port: P,
+}
+
+impl<P: Read + Write> Widget<P> {
+    pub fn new(port: P) -> Widget<P> {
+        Widget { port }
+    }
+
+    /// The widget keeps sending the last universe until it receives a new one
+    pub fn send_dmx(&mut self, values: &[u8; UNIVERSE_SIZE]) -> io::Result<()> {
+        let mut data = Vec::with_capacity(UNIVERSE_SIZE + 1);
+        data.push(START_CODE);
+        data.extend_from_slice(values);
+        self.write_message(LABEL_OUTPUT_ONLY_SEND_DMX, &data)
+    }
+
+    pub fn parameters(&mut self) -> io::Result<WidgetParameters> {
+        // No user configuration is requested
+        self.write_message(LABEL_GET_PARAMETERS, &[0, 0])?;
+        let data = self.read_message(LABEL_GET_PARAMETERS)?;
+        if data.len() < 5 {
+            return Err(invalid("Widget parameters reply is too short"));
+        }
+
+        Ok(WidgetParameters {
+            firmware: u16::from_le_bytes([data[0], data[1]]),
+            break_time: data[2],
+            mab_time: data[3],
+            refresh_rate: data[4],
+        })
+    }
+
+    /// The serial number is BCD encoded, so its hex representation shows the number printed on the widget
+    pub fn serial_number(&mut self) -> io::Result<u32> {
+        self.write_message(LABEL_GET_SERIAL_NUMBER, &[])?;
+        let data = self.read_message(LABEL_GET_SERIAL_NUMBER)?;
+        let bytes: [u8; 4] = data
+            .get(..4)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| invalid("Serial number reply is too short"))?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn write_message(&mut self, label: u8, data: &[u8]) -> io::Result<()> {
+        self.port.write_all(&encode(label, data))?;
+        self.port.flush()
+    }
+
+    /// Reads messages until one with `label` arrives
+    fn read_message(&mut self, label: u8) -> io::Result<Vec<u8>> {
+        for _ in 0..MAX_SKIPPED {
+            let (l, data) = self.read_any()?;
+            if l == label {
+                return Ok(data);
+            }
+        }
+        Err(invalid("Widget did not reply"))
+    }
+
+    fn read_any(&mut self) -> io::Result<(u8, Vec<u8>)> {
+        let mut byte = [0u8];
+        loop {
+            self.port.read_exact(&mut byte)?;
+            if byte[0] == START {
+                break;
+            }
+        }
+
+        let mut header = [0u8; 3];
+        self.port.read_exact(&mut header)?;
+        let length = u16::from_le_bytes([header[1], header[2]]) as usize;
+        let mut data = vec![0u8; length];
+        self.port.read_exact(&mut data)?;
+
+        self.port.read_exact(&mut byte)?;
+        if byte[0] != END {
+            return Err(invalid("Widget message is not terminated"));
+        }
+        Ok((header[0], data))
+    }
+}
+
+pub fn encode(label: u8, data: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(data.len() + 5);
+    buf.push(START);
+    buf.push(label);
+    buf.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    buf.extend_from_slice(data);
+    buf.push(END);
+    buf
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Replays the prepared widget output and records everything written to the widget
+    struct MemoryPort {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl MemoryPort {
+        fn new(input: Vec<u8>) -> MemoryPort {
+            MemoryPort {
+                input: Cursor::new(input),
+                output: vec![],
+            }
+        }
+    }
+
+    impl Read for MemoryPort {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for MemoryPort {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn send_dmx_framing() {
+        let mut widget = Widget::new(MemoryPort::new(vec![]));
+        let mut values = [0u8; UNIVERSE_SIZE];
+        values[0] = 255;
+        values[511] = 7;
+        widget.send_dmx(&values).unwrap();
+
+        let out = &widget.port.output;
+        assert_eq!(out.len(), UNIVERSE_SIZE + 6);
+        assert_eq!(&out[..5], &[0x7E, 6, 0x01, 0x02, 0x00]);
+        assert_eq!(out[5], 255);
+        assert_eq!(out[516], 7);
+        assert_eq!(out[517], 0xE7);
+    }
+
+    #[test]
+    fn parameters_skip_unrelated_messages() {
+        let mut input = vec![0x00, 0x13];
+        input.extend(encode(5, &[0, 0, 1, 2]));
+        input.extend(encode(3, &[0x44, 0x01, 9, 1, 40]));
+        let mut widget = Widget::new(MemoryPort::new(input));
+
+        let p = widget.parameters().unwrap();
+        assert_eq!(
+            p,
+            WidgetParameters {
+                firmware: 0x0144,
+                break_time: 9,
+                mab_time: 1,
+                refresh_rate: 40,
+            }
+        );
+        assert_eq!(widget.port.output, encode(3, &[0, 0]));
+    }
+
+    #[test]
+    fn serial_number() {
+        let input = encode(10, &[0x78, 0x56, 0x34, 0x12]);
+        let mut widget = Widget::new(MemoryPort::new(input));
+
+        assert_eq!(widget.serial_number().unwrap(), 0x1234_5678);
+        assert_eq!(widget.port.output, encode(10, &[]));
+    }
+
+    #[test]
+    fn unterminated_message() {
+        let mut input = encode(10, &[1, 2, 3, 4]);
+        *input.last_mut().unwrap() = 0;
+        let mut widget = Widget::new(MemoryPort::new(input));
+
+        assert_eq!(
+            widget.serial_number().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use rocket::{
+    futures::{SinkExt, StreamExt},
+    tokio::{
+        select,
+        sync::broadcast::error::RecvError,
+        time::{interval, MissedTickBehavior},
+    },
+    Shutdown,
+};
+use rocket_ws::{stream::DuplexStream, Message};
+
+use mlc_common::fader_stream::{FaderEncoder, FaderFrame};
+use mlc_common::patched::UniverseId;
+use mlc_common::RuntimeUpdate;
+
+use super::{clients::Client, decode_msg, RuntimeData};
+
+/// How often the changed universes are sent
+const FLUSH_INTERVAL: Duration = Duration::from_millis(40);
+/// How often every universe is sent as a keyframe
+const KEYFRAME_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Sends the fader values as CBOR encoded [`FaderFrame`]s.
+///
+/// Changes are collected and sent as delta frames every [`FLUSH_INTERVAL`], so a universe changing at the rate of the
+/// effects costs one small frame per interval. The client can send a [`UniverseId`] to request a keyframe of it.
+///
+/// Without `echo` the changes the client made itself are assumed to be known and left out of the delta frames.
+pub async fn binary(
+    runtime: &RuntimeData,
+    mut stream: DuplexStream,
+    mut shutdown: Shutdown,
+    client: &Client,
+    echo: bool,
+) -> rocket_ws::result::Result<()> {
+    let mut rx = runtime.subscribe().await;
+    let mut encoder = FaderEncoder::default();
+    let mut dirty = HashSet::new();
+
+    let mut flush = interval(FLUSH_INTERVAL);
+    flush.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut keyframes = interval(KEYFRAME_INTERVAL);
+    keyframes.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        select! {
+            _ = keyframes.tick() => {
+                dirty.clear();
+                for (universe, values) in runtime.initial_states().await {
+                    stream.send(encode(&encoder.keyframe(universe, &values))).await?;
+                }
+            }
+            _ = flush.tick() => {
+                for universe in dirty.drain() {
+                    let Some(values) = runtime.get_universe_values(&universe).await else {
+                        continue;
+                    };
+                    if let Some(frame) = encoder.delta(universe, &values) {
+                        stream.send(encode(&frame)).await?;
+                    }
+                }
+            }
+            update = rx.recv() => match update {
+                Ok(update) if !echo && update.author() == client.id => assume(&mut encoder, update),
+                Ok(update) => dirty.extend(update.universes()),
+                // Updates were dropped, so any universe may have changed
+                Err(RecvError::Lagged(_)) => dirty.extend(runtime.initial_states().await.into_keys()),
+                Err(RecvError::Closed) => break,
+            },
+            msg = stream.next() => match msg {
+                Some(Ok(msg)) if !msg.is_close() => {
+                    if let Some(universe) = decode_msg::<UniverseId>(&msg) {
+                        if let Some(values) = runtime.get_universe_values(&universe).await {
+                            stream.send(encode(&encoder.keyframe(universe, &values))).await?;
+                        }
+                    }
+                }
+                _ => break,
+            },
+            _ = &mut shutdown => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn assume(encoder: &mut FaderEncoder, update: RuntimeUpdate) {
+    match update {
+        RuntimeUpdate::ValueUpdated {
+            universe,
+            channel_index,
+            value,
+            ..
+        } => encoder.assume(universe, channel_index, value),
+        RuntimeUpdate::ValuesUpdated {
+            universes,
+            channel_indexes,
+            values,
+            ..
+        } => {
+            for ((universe, channel), value) in
+                universes.into_iter().zip(channel_indexes).zip(values)
+            {
+                encoder.assume(universe, channel, value);
+            }
+        }
+        RuntimeUpdate::Universe {
+            universe, values, ..
+        } => {
+            for (channel, value) in values.into_iter().enumerate() {
+                encoder.assume(universe, channel, value);
+            }
+        }
+        RuntimeUpdate::Connected { .. } => {}
+    }
+}
+
+fn encode(frame: &FaderFrame) -> Message {
+    let mut bytes = vec![];
+    ciborium::into_writer(frame, &mut bytes).expect("Frames are serializable");
+    Message::binary(bytes)
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use rocket::tokio::sync::broadcast::Sender;
+
+use mlc_common::config::{FixtureCapability, ShutterEffect};
+use mlc_common::curve::{CurveAssignment, FeatureCurves};
+use mlc_common::fixture::FaderAddress;
+use mlc_common::group::GroupId;
+use mlc_common::highlight::{self, HighlightRequest};
+use mlc_common::patched::feature::FeatureSetRequest;
+use mlc_common::patched::{FixtureId, FixtureSelection, PatchedFixture, UniverseId};
+use mlc_common::universe::FixtureUniverse;
+use mlc_common::utils::BoundedValue;
+use mlc_common::Info;
+
+use crate::fixture::feature::ApplyFeature;
+use crate::project::ProjectHandle;
+use crate::send;
+
+use super::{RuntimeData, ToFaderValue};
+
+/// Highlights a fixture or ends the highlight and returns the fixture that is highlighted now
+pub async fn apply(
+    request: HighlightRequest,
+    project: &ProjectHandle,
+    runtime: &RuntimeData,
+    info: &Sender<Info>,
+) -> Result<Option<FixtureId>, String> {
+    let fixture = match request {
+        HighlightRequest::Fixture(id) => id,
+        HighlightRequest::Next(group) => step(group, true, project, runtime).await?,
+        HighlightRequest::Previous(group) => step(group, false, project, runtime).await?,
+        HighlightRequest::Off => {
+            runtime.set_highlight(None, &HashMap::new()).await;
+            send!(info, Info::HighlightChanged);
+            return Ok(None);
+        }
+    };
+
+    let universes = project
+        .lock()
+        .await
+        .universes
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    if !universes
+        .iter()
+        .any(|u| u.fixtures.iter().any(|f| f.id == fixture))
+    {
+        return Err(format!("Fixture {fixture} is not patched"));
+    }
+
+    let values = values(fixture, &universes, &project.get_curves().await);
+    runtime.set_highlight(Some(fixture), &values).await;
+    send!(info, Info::HighlightChanged);
+    Ok(Some(fixture))
+}
+
+async fn step(
+    group: GroupId,
+    forward: bool,
+    project: &ProjectHandle,
+    runtime: &RuntimeData,
+) -> Result<FixtureId, String> {
+    let members = project
+        .selection_fixtures(&FixtureSelection::Group(group))
+        .await?;
+    highlight::step(&members, runtime.highlighted().await, forward)
+        .ok_or_else(|| format!("Group {group} has no patched fixtures"))
+}
+
+/// The highlighted fixture at full intensity in open white with pan and tilt centered, every other fixture dark
+fn values(
+    fixture: FixtureId,
+    universes: &[FixtureUniverse],
+    curves: &[CurveAssignment],
+) -> HashMap<FaderAddress, u8> {
+    let full = || BoundedValue::create(1.0);
+    let zero = || BoundedValue::create(0.0);
+
+    let mut values = HashMap::new();
+    for u in universes {
+        for f in u.fixtures.iter().filter(|f| f.id != fixture) {
+            let curves = FeatureCurves::of(curves, f.id);
+            // Like the masters, fixtures without a dimmer are dimmed through their colors
+            let dimmer = f
+                .features
+                .values(FeatureSetRequest::Dimmer { value: zero() }, &curves);
+            if !dimmer.is_empty() {
+                values.extend(dimmer);
+                continue;
+            }
+            for req in [
+                FeatureSetRequest::Rgb {
+                    red: zero(),
+                    green: zero(),
+                    blue: zero(),
+                },
+                FeatureSetRequest::White { value: zero() },
+                FeatureSetRequest::Amber { value: zero() },
+            ] {
+                values.extend(f.features.values(req, &curves));
+            }
+        }
+    }
+
+    for u in universes {
+        for f in u.fixtures.iter().filter(|f| f.id == fixture) {
+            let curves = FeatureCurves::of(curves, f.id);
+            for req in [
+                FeatureSetRequest::Dimmer { value: full() },
+                FeatureSetRequest::Rgb {
+                    red: full(),
+                    green: full(),
+                    blue: full(),
+                },
+                FeatureSetRequest::White { value: full() },
+                FeatureSetRequest::Amber { value: zero() },
+                FeatureSetRequest::PanTilt {
+                    pan: BoundedValue::create(0.5),
+                    tilt: BoundedValue::create(0.5),
+                },
+            ] {
+                values.extend(f.features.values(req, &curves));
+            }
+            values.extend(open_shutter(u.id, f));
+        }
+    }
+
+    values
+}
+
+/// The channels of the fixture with an open shutter capability, set to the middle of its range
+fn open_shutter(universe: UniverseId, fixture: &PatchedFixture) -> Vec<(FaderAddress, u8)> {
+    fixture
+        .channels
+        .iter()
+        .filter_map(|c| {
+            let open = c.config.capabilities.iter().find(|cap| {
+                matches!(
+                    &cap.detail,
+                    FixtureCapability::ShutterStrobe(s) if s.shutter_effect == ShutterEffect::Open
+                )
+            })?;
+            Some((
+                FaderAddress {
+                    universe,
+                    address: c.channel_address,
+                },
+                0.5.to_fader_value_range(&open.dmx_range),
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,364 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use mlc_common::endpoints::MergeMode;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+use super::merge::DEFAULT_PRIORITY;
+
+/// The age of the defaults, so LTP channels always prefer any other layer over them
+pub const DEFAULTS_STAMP: u64 = 0;
+
+/// The priority of manual changes, above every playback
+const PROGRAMMER_PRIORITY: u8 = 150;
+/// The priority of a located fixture, above the programmer but below parked channels
+const HIGHLIGHT_PRIORITY: u8 = 200;
+
+/// The sources that write into a universe, merged in this order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Layer {
+    /// The values channels fall back to, 0 for channels without one
+    Defaults,
+    Playback(Playback),
+    /// Manual changes through faders and fixture features
+    Programmer,
+    /// A fixture being located and every other fixture dimmed, merged above the input endpoints
+    Highlight,
+    /// Channels fixed to a value, merged above the input endpoints
+    Parked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Playback {
+    Effects,
+    Replay,
+    Scenes,
+}
+
+impl Layer {
+    fn priority(&self) -> u8 {
+        match self {
+            Layer::Defaults => 0,
+            Layer::Playback(_) => DEFAULT_PRIORITY,
+            Layer::Programmer => PROGRAMMER_PRIORITY,
+            Layer::Highlight => HIGHLIGHT_PRIORITY,
+            Layer::Parked => u8::MAX,
+        }
+    }
+
+    fn above_inputs(&self) -> bool {
+        matches!(self, Layer::Highlight | Layer::Parked)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SourceLayer {
+    /// `None` for channels the layer does not hold
+    values: [Option<u8>; UNIVERSE_SIZE],
+    /// When each channel was last written, LTP takes the latest
+    stamps: [u64; UNIVERSE_SIZE],
+}
+
+impl SourceLayer {
+    fn empty() -> SourceLayer {
+        SourceLayer {
+            values: [None; UNIVERSE_SIZE],
+            stamps: [0; UNIVERSE_SIZE],
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.iter().all(Option::is_none)
+    }
+}
+
+/// The layer currently winning a channel while merging from the bottom up
+#[derive(Debug, Clone, Copy)]
+struct Winner {
+    value: u8,
+    stamp: u64,
+    priority: u8,
+    /// `None` while no layer holds the channel or an input endpoint won it
+    layer: Option<Layer>,
+}
+
+/// The source layers of one universe.
+///
+/// The output is merged from the defaults upwards, every layer using the policy of the channel:
+/// playbacks merge intensity channels HTP and everything else LTP unless the channel has a merge policy of its own,
+/// programmer, highlight and parked channels win by priority so they stay in control until released.
+#[derive(Debug, Clone)]
+pub struct LayerStack {
+    layers: BTreeMap<Layer, SourceLayer>,
+    /// The intensity channels of the patched fixtures
+    intensity: [bool; UNIVERSE_SIZE],
+    /// The intensity channels grouped by the value they form, coarse first
+    intensity_tiles: Vec<Vec<usize>>,
+    /// The policies the playbacks merge the channels with instead of the default one
+    policies: [Option<MergeMode>; UNIVERSE_SIZE],
+}
+
+impl Default for LayerStack {
+    fn default() -> Self {
+        LayerStack {
+            layers: BTreeMap::from([
+                (Layer::Defaults, SourceLayer::empty()),
+                (Layer::Programmer, SourceLayer::empty()),
+                (Layer::Parked, SourceLayer::empty()),
+            ]),
+            intensity: [false; UNIVERSE_SIZE],
+            intensity_tiles: vec![],
+            policies: [None; UNIVERSE_SIZE],
+        }
+    }
+}
+
+impl LayerStack {
+    pub fn set_intensity(&mut self, tiles: Vec<Vec<usize>>) {
+        self.intensity = [false; UNIVERSE_SIZE];
+        for c in tiles.iter().flatten() {
+            if let Some(i) = self.intensity.get_mut(*c) {
+                *i = true;
+            }
+        }
+        self.intensity_tiles = tiles;
+    }
+
+    pub fn intensity_tiles(&self) -> &[Vec<usize>] {
+        &self.intensity_tiles
+    }
+
+    pub fn set_policies(&mut self, policies: [Option<MergeMode>; UNIVERSE_SIZE]) {
+        self.policies = policies;
+    }
+
+    /// The policy `layer` merges `channel` onto the layers below with
+    pub fn mode(&self, layer: Layer, channel: usize) -> MergeMode {
+        match layer {
+            Layer::Defaults => MergeMode::Ltp,
+            Layer::Playback(_) => match self.policies[channel] {
+                Some(mode) => mode,
+                None if self.intensity[channel] => MergeMode::Htp,
+                None => MergeMode::Ltp,
+            },
+            Layer::Programmer | Layer::Highlight | Layer::Parked => MergeMode::Priority,
+        }
+    }
+
+    /// The values the layer holds, `None` for channels it does not hold
+    pub fn values(&self, layer: Layer) -> Option<&[Option<u8>; UNIVERSE_SIZE]> {
+        self.layers.get(&layer).map(|l| &l.values)
+    }
+
+    pub fn holds(&self, layer: Layer) -> bool {
+        self.layers.contains_key(&layer)
+    }
+
+    /// Writes a channel of the layer and returns whether its value changed
+    pub fn set(&mut self, layer: Layer, channel: usize, value: u8, stamp: u64) -> bool {
+        let l = self.layers.entry(layer).or_insert_with(SourceLayer::empty);
+        l.stamps[channel] = stamp;
+        l.values[channel].replace(value) != Some(value)
+    }
+
+    /// Replaces all values of the layer and returns the changed channels.
+    /// Channels whose value did not change keep their age.
+    pub fn replace(
+        &mut self,
+        layer: Layer,
+        values: &[Option<u8>; UNIVERSE_SIZE],
+        stamp: u64,
+    ) -> Vec<usize> {
+        let l = self.layers.entry(layer).or_insert_with(SourceLayer::empty);
+        let mut changed = vec![];
+        for (i, v) in values.iter().enumerate() {
+            if l.values[i] != *v {
+                l.values[i] = *v;
+                l.stamps[i] = stamp;
+                changed.push(i);
+            }
+        }
+
+        if matches!(layer, Layer::Playback(_)) && l.is_empty() {
+            self.layers.remove(&layer);
+        }
+        changed
+    }
+
+    /// Drops all values of the layer, so the layers below show again
+    pub fn release(&mut self, layer: Layer) -> bool {
+        match layer {
+            Layer::Playback(_) => self.layers.remove(&layer).is_some_and(|l| !l.is_empty()),
+            Layer::Defaults | Layer::Programmer | Layer::Highlight | Layer::Parked => {
+                let Some(l) = self.layers.get_mut(&layer) else {
+                    return false;
+                };
+                let changed = !l.is_empty();
+                *l = SourceLayer::empty();
+                changed
+            }
+        }
+    }
+
+    /// The output value of the channel and the layer it comes from, `None` if an input endpoint or no layer sets it.
+    /// `inputs` merges the input endpoints onto the layers below them
+    pub fn source(&self, channel: usize, inputs: impl FnOnce(u8) -> u8) -> (u8, Option<Layer>) {
+        let winner = Winner {
+            value: 0,
+            stamp: 0,
+            priority: 0,
+            layer: None,
+        };
+        let below = self.layers.iter().filter(|(l, _)| !l.above_inputs());
+        let above = self.layers.iter().filter(|(l, _)| l.above_inputs());
+
+        let mut winner = self.merge(channel, below, winner);
+        let merged = inputs(winner.value);
+        if merged != winner.value {
+            winner.value = merged;
+            winner.layer = None;
+        }
+        let winner = self.merge(channel, above, winner);
+        (winner.value, winner.layer)
+    }
+
+    fn merge<'a>(
+        &self,
+        channel: usize,
+        layers: impl Iterator<Item = (&'a Layer, &'a SourceLayer)>,
+        mut winner: Winner,
+    ) -> Winner {
+        for (layer, l) in layers {
+            let Some(value) = l.values[channel] else {
+                continue;
+            };
+            let stamp = l.stamps[channel];
+
+            let take = match self.mode(*layer, channel) {
+                MergeMode::Htp => value >= winner.value,
+                MergeMode::Ltp => stamp >= winner.stamp,
+                MergeMode::Priority => match layer.priority().cmp(&winner.priority) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => value >= winner.value,
+                    Ordering::Less => false,
+                },
+            };
+            if take {
+                winner = Winner {
+                    value,
+                    stamp,
+                    priority: layer.priority(),
+                    layer: Some(*layer),
+                };
+            }
+        }
+        winner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(stack: &LayerStack, channel: usize) -> u8 {
+        stack.source(channel, |v| v).0
+    }
+
+    #[test]
+    fn release_restores_below() {
+        let mut stack = LayerStack::default();
+        stack.set(Layer::Defaults, 0, 10, 0);
+        stack.set(Layer::Playback(Playback::Effects), 0, 100, 1);
+        assert_eq!(values(&stack, 0), 100);
+
+        stack.set(Layer::Programmer, 0, 50, 2);
+        assert_eq!(values(&stack, 0), 50);
+
+        // The programmer stays in control while the playback keeps changing
+        stack.set(Layer::Playback(Playback::Effects), 0, 120, 3);
+        assert_eq!(values(&stack, 0), 50);
+
+        stack.release(Layer::Programmer);
+        assert_eq!(values(&stack, 0), 120);
+        stack.release(Layer::Playback(Playback::Effects));
+        assert_eq!(values(&stack, 0), 10);
+
+        // Defaults never take a channel from a playback by being newer
+        stack.set(Layer::Playback(Playback::Effects), 0, 120, 4);
+        let mut defaults = [None; UNIVERSE_SIZE];
+        defaults[0] = Some(30);
+        stack.replace(Layer::Defaults, &defaults, DEFAULTS_STAMP);
+        assert_eq!(values(&stack, 0), 120);
+        stack.release(Layer::Playback(Playback::Effects));
+        assert_eq!(values(&stack, 0), 30);
+    }
+
+    #[test]
+    fn playbacks_merge_by_channel() {
+        let mut stack = LayerStack::default();
+        stack.set_intensity(vec![vec![0]]);
+        let effects = Layer::Playback(Playback::Effects);
+        let replay = Layer::Playback(Playback::Replay);
+
+        stack.set(effects, 0, 200, 1);
+        stack.set(effects, 1, 200, 1);
+        stack.set(replay, 0, 100, 2);
+        stack.set(replay, 1, 100, 2);
+
+        // Intensity is HTP, the rest LTP
+        assert_eq!(values(&stack, 0), 200);
+        assert_eq!(values(&stack, 1), 100);
+
+        let mut frame = [None; UNIVERSE_SIZE];
+        frame[1] = Some(100);
+        frame[0] = Some(200);
+        stack.replace(effects, &frame, 3);
+        // Unchanged channels keep their age, changed ones take over
+        assert_eq!(values(&stack, 0), 200);
+        assert_eq!(values(&stack, 1), 100);
+        frame[1] = Some(30);
+        stack.replace(effects, &frame, 4);
+        assert_eq!(values(&stack, 1), 30);
+
+        stack.replace(effects, &[None; UNIVERSE_SIZE], 5);
+        assert_eq!(values(&stack, 0), 100);
+    }
+
+    #[test]
+    fn policies_override_playbacks() {
+        let mut stack = LayerStack::default();
+        stack.set_intensity(vec![vec![0]]);
+        let mut policies = [None; UNIVERSE_SIZE];
+        policies[0] = Some(MergeMode::Ltp);
+        policies[1] = Some(MergeMode::Htp);
+        stack.set_policies(policies);
+
+        let effects = Layer::Playback(Playback::Effects);
+        let replay = Layer::Playback(Playback::Replay);
+        stack.set(effects, 0, 200, 1);
+        stack.set(effects, 1, 200, 1);
+        stack.set(replay, 0, 100, 2);
+        stack.set(replay, 1, 100, 2);
+        assert_eq!(values(&stack, 0), 100);
+        assert_eq!(values(&stack, 1), 200);
+
+        // The programmer is not affected
+        stack.set(Layer::Programmer, 1, 50, 3);
+        assert_eq!(values(&stack, 1), 50);
+    }
+
+    #[test]
+    fn parked_above_inputs() {
+        let mut stack = LayerStack::default();
+        stack.set(Layer::Programmer, 0, 50, 1);
+        assert_eq!(stack.source(0, |v| v.max(80)).0, 80);
+
+        stack.set(Layer::Parked, 0, 10, 2);
+        assert_eq!(stack.source(0, |v| v.max(80)).0, 10);
+
+        stack.release(Layer::Parked);
+        assert_eq!(stack.source(0, |v| v.max(80)).0, 80);
+        assert_eq!(stack.source(0, |v| v.max(80)), (80, None));
+        assert_eq!(stack.source(0, |v| v), (50, Some(Layer::Programmer)));
+    }
+}
@@ -0,0 +1,111 @@
+use mlc_common::endpoints::MergeMode;
+use mlc_common::universe::UNIVERSE_SIZE;
+
+/// The priority Art-Net input and local values have if nothing else is configured
+pub const DEFAULT_PRIORITY: u8 = 100;
+
+/// The last frame an input endpoint received, merged on top of the local values of its universe
+#[derive(Debug, Clone)]
+pub struct InputLayer {
+    mode: MergeMode,
+    local_priority: u8,
+    priority: u8,
+    values: [u8; UNIVERSE_SIZE],
+    /// Channels the input changed after the last local change, only used by [`MergeMode::Ltp`]
+    owned: [bool; UNIVERSE_SIZE],
+}
+
+impl InputLayer {
+    /// A new input owns all channels, the console taking over should not have to touch every fader first
+    pub fn new(mode: MergeMode, local_priority: u8) -> InputLayer {
+        InputLayer {
+            mode,
+            local_priority,
+            priority: DEFAULT_PRIORITY,
+            values: [0; UNIVERSE_SIZE],
+            owned: [true; UNIVERSE_SIZE],
+        }
+    }
+
+    pub fn update(&mut self, values: &[u8; UNIVERSE_SIZE], priority: u8) {
+        for (i, v) in values.iter().enumerate() {
+            if self.values[i] != *v {
+                self.owned[i] = true;
+            }
+        }
+        self.values = *values;
+        self.priority = priority;
+    }
+
+    /// Hands the channel back to the local values after they changed
+    pub fn release(&mut self, channel: usize) {
+        self.owned[channel] = false;
+    }
+
+    pub fn merge(&self, channel: usize, local: u8) -> u8 {
+        let input = self.values[channel];
+        match self.mode {
+            MergeMode::Htp => local.max(input),
+            MergeMode::Ltp => {
+                if self.owned[channel] {
+                    input
+                } else {
+                    local
+                }
+            }
+            MergeMode::Priority => match self.priority.cmp(&self.local_priority) {
+                std::cmp::Ordering::Greater => input,
+                std::cmp::Ordering::Equal => local.max(input),
+                std::cmp::Ordering::Less => local,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(v: u8) -> [u8; UNIVERSE_SIZE] {
+        [v; UNIVERSE_SIZE]
+    }
+
+    #[test]
+    fn htp() {
+        let mut l = InputLayer::new(MergeMode::Htp, DEFAULT_PRIORITY);
+        l.update(&frame(100), DEFAULT_PRIORITY);
+        assert_eq!(l.merge(0, 50), 100);
+        assert_eq!(l.merge(0, 150), 150);
+    }
+
+    #[test]
+    fn ltp() {
+        let mut l = InputLayer::new(MergeMode::Ltp, DEFAULT_PRIORITY);
+        l.update(&frame(100), DEFAULT_PRIORITY);
+        assert_eq!(l.merge(3, 200), 100);
+
+        l.release(3);
+        assert_eq!(l.merge(3, 200), 200);
+
+        // Resending the same value does not take the channel back
+        l.update(&frame(100), DEFAULT_PRIORITY);
+        assert_eq!(l.merge(3, 200), 200);
+
+        l.update(&frame(101), DEFAULT_PRIORITY);
+        assert_eq!(l.merge(3, 200), 101);
+    }
+
+    #[test]
+    fn priority() {
+        let mut l = InputLayer::new(MergeMode::Priority, DEFAULT_PRIORITY);
+        l.update(&frame(10), 150);
+        assert_eq!(l.merge(0, 200), 10);
+
+        l.update(&frame(10), DEFAULT_PRIORITY);
+        assert_eq!(l.merge(0, 200), 200);
+        assert_eq!(l.merge(0, 5), 10);
+
+        l.update(&frame(10), 50);
+        assert_eq!(l.merge(0, 5), 5);
+    }
+}
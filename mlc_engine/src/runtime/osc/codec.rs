@@ -0,0 +1,268 @@
+//! Encoding and decoding of OSC 1.0 packets.
+//!
+//! Only messages and bundles with the common argument types are needed, so they are written by hand instead of
+//! pulling in a full OSC implementation. Time tags of bundles are ignored, their messages apply immediately.
+
+const BUNDLE_TAG: &[u8] = b"#bundle\0";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Blob(Vec<u8>),
+    Bool(bool),
+    Nil,
+    Impulse,
+}
+
+impl OscArg {
+    /// The argument as a number, booleans are 0 and 1
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(v) => Some(*v as f64),
+            OscArg::Long(v) => Some(*v as f64),
+            OscArg::Float(v) => Some(*v as f64),
+            OscArg::Double(v) => Some(*v),
+            OscArg::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    pub fn new(address: impl Into<String>, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        write_string(&mut buf, &self.address);
+
+        let mut tags = String::from(",");
+        for a in &self.args {
+            tags.push(match a {
+                OscArg::Int(_) => 'i',
+                OscArg::Long(_) => 'h',
+                OscArg::Float(_) => 'f',
+                OscArg::Double(_) => 'd',
+                OscArg::String(_) => 's',
+                OscArg::Blob(_) => 'b',
+                OscArg::Bool(true) => 'T',
+                OscArg::Bool(false) => 'F',
+                OscArg::Nil => 'N',
+                OscArg::Impulse => 'I',
+            });
+        }
+        write_string(&mut buf, &tags);
+
+        for a in &self.args {
+            match a {
+                OscArg::Int(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Long(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Float(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                OscArg::Double(v) => buf.extend_from_slice(&v.to_be_bytes()),
+                OscArg::String(v) => write_string(&mut buf, v),
+                OscArg::Blob(v) => {
+                    buf.extend_from_slice(&(v.len() as i32).to_be_bytes());
+                    buf.extend_from_slice(v);
+                    pad(&mut buf);
+                }
+                OscArg::Bool(_) | OscArg::Nil | OscArg::Impulse => {}
+            }
+        }
+        buf
+    }
+}
+
+/// All messages of a packet, the messages of nested bundles in order
+pub fn decode(packet: &[u8]) -> Result<Vec<OscMessage>, String> {
+    let mut messages = vec![];
+    decode_into(packet, &mut messages)?;
+    Ok(messages)
+}
+
+fn decode_into(packet: &[u8], messages: &mut Vec<OscMessage>) -> Result<(), String> {
+    if !packet.starts_with(BUNDLE_TAG) {
+        messages.push(decode_message(packet)?);
+        return Ok(());
+    }
+
+    let mut r = Reader::new(packet);
+    r.take(BUNDLE_TAG.len() + 8)?; // The time tag
+    while !r.is_empty() {
+        let size = r.i32()?;
+        let element = r.take(usize::try_from(size).map_err(|_| "Negative bundle element size")?)?;
+        decode_into(element, messages)?;
+    }
+    Ok(())
+}
+
+fn decode_message(packet: &[u8]) -> Result<OscMessage, String> {
+    let mut r = Reader::new(packet);
+    let address = r.string()?;
+    if !address.starts_with('/') {
+        return Err(format!("Invalid OSC address {address}"));
+    }
+
+    // Very old implementations omit the type tags
+    if r.is_empty() {
+        return Ok(OscMessage::new(address, vec![]));
+    }
+    let tags = r.string()?;
+    let Some(tags) = tags.strip_prefix(',') else {
+        return Err("Missing OSC type tags".to_string());
+    };
+
+    let mut args = vec![];
+    for t in tags.chars() {
+        args.push(match t {
+            'i' => OscArg::Int(r.i32()?),
+            'h' => OscArg::Long(i64::from_be_bytes(r.array()?)),
+            'f' => OscArg::Float(f32::from_be_bytes(r.array()?)),
+            'd' => OscArg::Double(f64::from_be_bytes(r.array()?)),
+            's' | 'S' => OscArg::String(r.string()?),
+            'b' => {
+                let size = usize::try_from(r.i32()?).map_err(|_| "Negative blob size")?;
+                let blob = r.take(size)?.to_vec();
+                r.skip_padding(size)?;
+                OscArg::Blob(blob)
+            }
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            'N' => OscArg::Nil,
+            'I' => OscArg::Impulse,
+            t => return Err(format!("Unsupported OSC type tag {t}")),
+        });
+    }
+
+    Ok(OscMessage::new(address, args))
+}
+
+/// A null terminated string padded to a multiple of 4 bytes
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(s.as_bytes());
+    buf.push(0);
+    pad(buf);
+}
+
+fn pad(buf: &mut Vec<u8>) {
+    while !buf.len().is_multiple_of(4) {
+        buf.push(0);
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.data.len() < n {
+            return Err("OSC packet too short".to_string());
+        }
+        let (taken, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        Ok(self.take(N)?.try_into().expect("Took N bytes"))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    /// Skips the padding after `len` bytes of data
+    fn skip_padding(&mut self, len: usize) -> Result<(), String> {
+        self.take((4 - len % 4) % 4).map(|_| ())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let end = self
+            .data
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or("Unterminated OSC string")?;
+        let s = std::str::from_utf8(&self.data[..end])
+            .map_err(|_| "Invalid UTF-8 in OSC string")?
+            .to_string();
+        self.take(end + 1)?;
+        self.skip_padding(end + 1)?;
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_round_trip() {
+        let msg = OscMessage::new(
+            "/mlc/fader/1/12",
+            vec![
+                OscArg::Float(0.5),
+                OscArg::Int(-3),
+                OscArg::String("abc".to_string()),
+                OscArg::Blob(vec![1, 2, 3, 4, 5]),
+                OscArg::Bool(true),
+            ],
+        );
+        let data = msg.encode();
+
+        assert_eq!(data.len() % 4, 0);
+        assert_eq!(&data[..16], b"/mlc/fader/1/12\0");
+        assert_eq!(&data[16..24], b",fisbT\0\0");
+        assert_eq!(decode(&data).unwrap(), vec![msg]);
+    }
+
+    #[test]
+    fn nested_bundle() {
+        let a = OscMessage::new("/a", vec![OscArg::Int(1)]).encode();
+        let b = OscMessage::new("/b", vec![]).encode();
+
+        let mut inner = BUNDLE_TAG.to_vec();
+        inner.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        inner.extend_from_slice(&(b.len() as i32).to_be_bytes());
+        inner.extend_from_slice(&b);
+
+        let mut outer = BUNDLE_TAG.to_vec();
+        outer.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        outer.extend_from_slice(&(a.len() as i32).to_be_bytes());
+        outer.extend_from_slice(&a);
+        outer.extend_from_slice(&(inner.len() as i32).to_be_bytes());
+        outer.extend_from_slice(&inner);
+
+        let messages = decode(&outer).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].address, "/a");
+        assert_eq!(messages[1].address, "/b");
+    }
+
+    #[test]
+    fn rejects_truncated() {
+        let data = OscMessage::new("/a", vec![OscArg::Int(1)]).encode();
+        assert!(decode(&data[..data.len() - 2]).is_err());
+    }
+}
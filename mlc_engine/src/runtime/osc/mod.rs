@@ -0,0 +1,454 @@
+//! An OSC server, so tools like TouchOSC or QLab can trigger effects and set faders and fixtures.
+//!
+//! | Address                           | Arguments                      |                                  |
+//! |-----------------------------------|--------------------------------|----------------------------------|
+//! | `/mlc/effect/<id>/play`           | None or a number other than 0  | Plays the effect                 |
+//! | `/mlc/effect/<id>/stop`           | None or a number other than 0  | Stops the effect                 |
+//! | `/mlc/fader/<universe>/<channel>` | Int 0 - 255 or float 0.0 - 1.0 | Sets a channel, starting at 1    |
+//! | `/mlc/fixture/<id>/dimmer`        | Float 0.0 - 1.0                | Also `white` and `amber`         |
+//! | `/mlc/fixture/<id>/rgb`           | 3 floats 0.0 - 1.0             |                                  |
+//! | `/mlc/fixture/<id>/pantilt`       | 2 floats 0.0 - 1.0             |                                  |
+//! | `/mlc/fixture/<id>/rotation`      | Float -1.0 - 1.0               |                                  |
+//! | `/mlc/feedback/subscribe`         | None or a number other than 0  | Sends feedback to the sender     |
+//! | `/mlc/feedback/unsubscribe`       | None or a number other than 0  | Stops the feedback to the sender |
+//!
+//! Buttons send 0 when released, so `play`, `stop`, `subscribe` and `unsubscribe` ignore messages with 0.
+//!
+//! The feedback addresses of the [`OscConfig`] and the subscribed peers receive `/mlc/effect/<id>/playing`
+//! with 1 or 0 when an effect starts or stops and `/mlc/effect/<id>/progress` with the progress of every playing
+//! effect as float 0.0 - 1.0. A subscription ends [`SUBSCRIPTION_TIMEOUT`] after the last message of the peer,
+//! at most [`MAX_SUBSCRIBERS`] peers can subscribe at once.
+
+use std::collections::{HashMap, HashSet};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use rocket::fairing::AdHoc;
+use rocket::futures::{future::pending, SinkExt};
+use rocket::tokio::{
+    net::UdpSocket,
+    select,
+    sync::broadcast::{error::RecvError, Receiver, Sender},
+};
+use rocket_okapi::okapi::openapi3::OpenApi;
+
+use mlc_common::curve::FeatureCurves;
+use mlc_common::effect::EffectId;
+use mlc_common::endpoints::OscConfig;
+use mlc_common::patched::feature::FeatureSetRequest;
+use mlc_common::patched::{FixtureId, UniverseAddress, UniverseId};
+use mlc_common::utils::BoundedValue;
+use mlc_common::Info;
+
+use crate::fixture::feature::ApplyFeature;
+use crate::module::Module;
+use crate::project::ProjectHandle;
+use crate::runtime::effects::player::{EffectPlayerCmd, EffectPlayerHandle, EffectPlayerUpdate};
+
+use self::codec::{OscArg, OscMessage};
+
+use super::{fixture_features, RuntimeData};
+
+mod codec;
+
+const SUBSCRIPTION_TIMEOUT: Duration = Duration::from_secs(300);
+const MAX_SUBSCRIBERS: usize = 16;
+
+/// What a received message asks for
+#[derive(Debug, PartialEq)]
+enum OscCommand {
+    Play(EffectId),
+    Stop(EffectId),
+    Subscribe,
+    Unsubscribe,
+    Fader {
+        universe: UniverseId,
+        channel: UniverseAddress,
+        value: u8,
+    },
+    Feature {
+        fixture: FixtureId,
+        request: FeatureSetRequest,
+    },
+}
+
+/// The command of a message, `None` if the address is unknown or the arguments don't fit
+fn parse(msg: &OscMessage) -> Option<OscCommand> {
+    let parts = msg
+        .address
+        .strip_prefix("/mlc/")?
+        .split('/')
+        .collect::<Vec<_>>();
+    let arg = |i: usize| msg.args.get(i).and_then(OscArg::as_f64);
+    let unit = |i: usize| arg(i).map(|v| BoundedValue::create(v.clamp(0.0, 1.0)));
+    // A button that was pressed, not released
+    let pressed = msg.args.first().is_none_or(|a| a.as_f64() != Some(0.0));
+
+    match parts.as_slice() {
+        ["effect", id, "play"] => pressed
+            .then(|| EffectId::from_str(id).ok().map(OscCommand::Play))
+            .flatten(),
+        ["effect", id, "stop"] => pressed
+            .then(|| EffectId::from_str(id).ok().map(OscCommand::Stop))
+            .flatten(),
+        ["feedback", "subscribe"] => pressed.then_some(OscCommand::Subscribe),
+        ["feedback", "unsubscribe"] => pressed.then_some(OscCommand::Unsubscribe),
+        ["fader", universe, channel] => {
+            let channel = u16::from_str(channel).ok()?.checked_sub(1)?;
+            let value = match msg.args.first()? {
+                OscArg::Int(v) => (*v).clamp(0, 255) as u8,
+                a => (a.as_f64()?.clamp(0.0, 1.0) * 255.0).round() as u8,
+            };
+            Some(OscCommand::Fader {
+                universe: UniverseId(u16::from_str(universe).ok()?),
+                channel: UniverseAddress::create(channel).ok()?,
+                value,
+            })
+        }
+        ["fixture", id, feature] => {
+            let request = match *feature {
+                "dimmer" => FeatureSetRequest::Dimmer { value: unit(0)? },
+                "white" => FeatureSetRequest::White { value: unit(0)? },
+                "amber" => FeatureSetRequest::Amber { value: unit(0)? },
+                "rgb" => FeatureSetRequest::Rgb {
+                    red: unit(0)?,
+                    green: unit(1)?,
+                    blue: unit(2)?,
+                },
+                "pantilt" => FeatureSetRequest::PanTilt {
+                    pan: unit(0)?,
+                    tilt: unit(1)?,
+                },
+                "rotation" => FeatureSetRequest::Rotation {
+                    value: BoundedValue::create(arg(0)?.clamp(-1.0, 1.0)),
+                },
+                _ => return None,
+            };
+            Some(OscCommand::Feature {
+                fixture: FixtureId::from_str(id).ok()?,
+                request,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The peers that asked for feedback, the feedback addresses of the config are not part of them
+#[derive(Debug, Default)]
+struct Subscribers {
+    /// When every peer sent its last message
+    peers: HashMap<SocketAddr, Instant>,
+}
+
+impl Subscribers {
+    /// `false` if the peer could not subscribe because too many others did
+    fn subscribe(&mut self, peer: SocketAddr, now: Instant) -> bool {
+        self.prune(now);
+        if self.peers.len() >= MAX_SUBSCRIBERS && !self.peers.contains_key(&peer) {
+            return false;
+        }
+        self.peers.insert(peer, now);
+        true
+    }
+
+    fn unsubscribe(&mut self, peer: SocketAddr) {
+        self.peers.remove(&peer);
+    }
+
+    /// Every message of a subscribed peer keeps its subscription alive
+    fn renew(&mut self, peer: SocketAddr, now: Instant) {
+        if let Some(last) = self.peers.get_mut(&peer) {
+            *last = now;
+        }
+    }
+
+    /// The subscribed peers, without the expired ones
+    fn active(&mut self, now: Instant) -> Vec<SocketAddr> {
+        self.prune(now);
+        self.peers.keys().copied().collect()
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.peers
+            .retain(|_, last| now.duration_since(*last) < SUBSCRIPTION_TIMEOUT);
+    }
+}
+
+pub struct OscModule;
+
+impl Module for OscModule {
+    fn setup(
+        &self,
+        app: rocket::Rocket<rocket::Build>,
+        _spec: &mut OpenApi,
+    ) -> rocket::Rocket<rocket::Build> {
+        app.attach(AdHoc::on_liftoff("OSC server", |rocket| {
+            Box::pin(async move {
+                let project = rocket.state::<ProjectHandle>().expect("Managed").clone();
+                let runtime = rocket.state::<RuntimeData>().expect("Managed").clone();
+                let effects = rocket
+                    .state::<EffectPlayerHandle>()
+                    .expect("Managed")
+                    .clone();
+                let info = rocket.state::<Sender<Info>>().expect("Managed").subscribe();
+                rocket::tokio::spawn(run(project, runtime, effects, info));
+            })
+        }))
+    }
+}
+
+/// Runs the server of the current config and restarts it whenever the config changes
+async fn run(
+    project: ProjectHandle,
+    runtime: RuntimeData,
+    effects: EffectPlayerHandle,
+    mut info: Receiver<Info>,
+) {
+    loop {
+        let config = project.get_endpoint_config().await.osc;
+        let server = async {
+            if config.enabled {
+                if let Err(e) = serve(&config, &project, &runtime, effects.clone()).await {
+                    eprintln!("[OSC] {e}");
+                }
+            }
+            pending::<()>().await
+        };
+
+        select! {
+            _ = server => {}
+            changed = config_changed(&mut info) => {
+                if !changed {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Waits until the endpoint config may have changed, `false` once no more changes can follow
+async fn config_changed(info: &mut Receiver<Info>) -> bool {
+    loop {
+        match info.recv().await {
+            Ok(Info::EndpointConfigChanged | Info::ProjectLoaded) => return true,
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return false,
+        }
+    }
+}
+
+async fn serve(
+    config: &OscConfig,
+    project: &ProjectHandle,
+    runtime: &RuntimeData,
+    mut effects: EffectPlayerHandle,
+) -> Result<(), String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, config.port))
+        .await
+        .map_err(|e| format!("Binding OSC port {} failed: {e}", config.port))?;
+    let mut subscribers = Subscribers::default();
+    let mut playing = HashSet::new();
+    let mut buf = vec![0; u16::MAX as usize];
+
+    loop {
+        select! {
+            r = socket.recv_from(&mut buf) => {
+                let (len, from) = match r {
+                    Ok(r) => r,
+                    Err(e) => {
+                        eprintln!("[OSC] Receiving failed: {e}");
+                        continue;
+                    }
+                };
+                subscribers.renew(from, Instant::now());
+
+                let messages = match codec::decode(&buf[..len]) {
+                    Ok(messages) => messages,
+                    Err(e) => {
+                        eprintln!("[OSC] Invalid packet from {from}: {e}");
+                        continue;
+                    }
+                };
+                for cmd in messages.iter().filter_map(parse) {
+                    match cmd {
+                        OscCommand::Subscribe => {
+                            if !subscribers.subscribe(from, Instant::now()) {
+                                eprintln!("[OSC] Refused the feedback subscription of {from}, {MAX_SUBSCRIBERS} peers are subscribed");
+                            }
+                        }
+                        OscCommand::Unsubscribe => subscribers.unsubscribe(from),
+                        cmd => execute(cmd, project, runtime, &mut effects).await,
+                    }
+                }
+            }
+            update = effects.update_receiver.recv() => {
+                let feedback = match update {
+                    Ok(EffectPlayerUpdate::PlayingEffects(now)) => {
+                        let now = now.into_iter().collect::<HashSet<_>>();
+                        let started = now.difference(&playing).map(|id| playing_message(id, true));
+                        let stopped = playing.difference(&now).map(|id| playing_message(id, false));
+                        let feedback = started.chain(stopped).collect::<Vec<_>>();
+                        playing = now;
+                        feedback
+                    }
+                    Ok(EffectPlayerUpdate::EffectProgresses(progresses)) => progresses
+                        .iter()
+                        .map(|(id, p)| {
+                            OscMessage::new(format!("/mlc/effect/{id}/progress"), vec![OscArg::Float(**p)])
+                        })
+                        .collect(),
+                    Ok(EffectPlayerUpdate::EffectStates(_)) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return Err("The effect player stopped".to_string()),
+                };
+
+                let peers = subscribers
+                    .active(Instant::now())
+                    .into_iter()
+                    .chain(config.feedback.iter().copied())
+                    .collect::<HashSet<_>>();
+                for msg in feedback {
+                    let data = msg.encode();
+                    for peer in &peers {
+                        if let Err(e) = socket.send_to(&data, peer).await {
+                            eprintln!("[OSC] Sending feedback to {peer} failed: {e}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn playing_message(id: &EffectId, playing: bool) -> OscMessage {
+    OscMessage::new(
+        format!("/mlc/effect/{id}/playing"),
+        vec![OscArg::Int(playing as i32)],
+    )
+}
+
+async fn execute(
+    cmd: OscCommand,
+    project: &ProjectHandle,
+    runtime: &RuntimeData,
+    effects: &mut EffectPlayerHandle,
+) {
+    match cmd {
+        OscCommand::Play(id) => {
+            let _ = effects.cmd_sender.send(EffectPlayerCmd::Play { id }).await;
+        }
+        OscCommand::Stop(id) => {
+            let _ = effects.cmd_sender.send(EffectPlayerCmd::Stop { id }).await;
+        }
+        // Handled by the server, as they depend on the sender
+        OscCommand::Subscribe | OscCommand::Unsubscribe => {}
+        OscCommand::Fader {
+            universe,
+            channel,
+            value,
+        } => runtime.set_value(universe, channel, value, 0).await,
+        OscCommand::Feature { fixture, request } => {
+            if let Some(features) = fixture_features(fixture, project).await {
+                let curves = FeatureCurves::of(&project.get_curves().await, fixture);
+                features.apply(request, &curves, runtime).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(address: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage::new(address, args)
+    }
+
+    #[test]
+    fn parse_effects() {
+        let id = EffectId::new_v4();
+        let play = format!("/mlc/effect/{id}/play");
+
+        assert_eq!(parse(&msg(&play, vec![])), Some(OscCommand::Play(id)));
+        assert_eq!(
+            parse(&msg(&play, vec![OscArg::Float(1.0)])),
+            Some(OscCommand::Play(id))
+        );
+        assert_eq!(parse(&msg(&play, vec![OscArg::Float(0.0)])), None);
+        assert_eq!(parse(&msg("/mlc/effect/nope/play", vec![])), None);
+    }
+
+    #[test]
+    fn parse_faders() {
+        assert_eq!(
+            parse(&msg("/mlc/fader/2/1", vec![OscArg::Float(1.0)])),
+            Some(OscCommand::Fader {
+                universe: UniverseId(2),
+                channel: UniverseAddress::create(0).unwrap(),
+                value: 255,
+            })
+        );
+        assert_eq!(
+            parse(&msg("/mlc/fader/1/512", vec![OscArg::Int(17)])),
+            Some(OscCommand::Fader {
+                universe: UniverseId(1),
+                channel: UniverseAddress::create(511).unwrap(),
+                value: 17,
+            })
+        );
+        assert_eq!(parse(&msg("/mlc/fader/1/0", vec![OscArg::Int(1)])), None);
+        assert_eq!(parse(&msg("/mlc/fader/1/513", vec![OscArg::Int(1)])), None);
+        assert_eq!(parse(&msg("/mlc/fader/1/1", vec![])), None);
+    }
+
+    #[test]
+    fn parse_features() {
+        let id = FixtureId::new_v4();
+        let Some(OscCommand::Feature { fixture, request }) = parse(&msg(
+            &format!("/mlc/fixture/{id}/rgb"),
+            vec![OscArg::Float(1.0), OscArg::Float(0.5), OscArg::Int(0)],
+        )) else {
+            panic!("Not parsed as feature");
+        };
+        assert_eq!(fixture, id);
+        let FeatureSetRequest::Rgb { red, green, blue } = request else {
+            panic!("Not parsed as rgb");
+        };
+        assert_eq!((red.take(), green.take(), blue.take()), (1.0, 0.5, 0.0));
+
+        assert_eq!(
+            parse(&msg(
+                &format!("/mlc/fixture/{id}/rgb"),
+                vec![OscArg::Float(1.0)]
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn subscriptions_expire_and_are_capped() {
+        let now = Instant::now();
+        let peer = |port| SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let mut subscribers = Subscribers::default();
+
+        for port in 0..MAX_SUBSCRIBERS as u16 {
+            assert!(subscribers.subscribe(peer(port), now));
+        }
+        assert!(!subscribers.subscribe(peer(100), now));
+        assert!(subscribers.subscribe(peer(0), now));
+
+        subscribers.renew(peer(1), now + SUBSCRIPTION_TIMEOUT / 2);
+        subscribers.renew(peer(100), now + SUBSCRIPTION_TIMEOUT / 2);
+        assert_eq!(
+            subscribers.active(now + SUBSCRIPTION_TIMEOUT),
+            vec![peer(1)]
+        );
+        assert!(subscribers.subscribe(peer(100), now + SUBSCRIPTION_TIMEOUT));
+
+        subscribers.unsubscribe(peer(1));
+        assert_eq!(
+            subscribers.active(now + SUBSCRIPTION_TIMEOUT),
+            vec![peer(100)]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use rocket::tokio::time::{sleep_until, Instant};
+
+use mlc_common::recording::{RecordedFrame, RecordingReader};
+
+use super::RuntimeData;
+
+/// Reads all frames of a recording
+pub fn load(path: &Path) -> Result<Vec<RecordedFrame>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Opening recording {} failed: {e}", path.display()))?;
+    RecordingReader::new(BufReader::new(file))
+        .and_then(|reader| reader.collect())
+        .map_err(|e| format!("Reading recording {} failed: {e}", path.display()))
+}
+
+/// Plays the frames back into the runtime with the timing they were recorded with
+pub async fn play(runtime: RuntimeData, frames: Vec<RecordedFrame>, looped: bool) {
+    loop {
+        let start = Instant::now();
+        for f in &frames {
+            sleep_until(start + f.time).await;
+            runtime.replay_frame(f.universe, &f.values).await;
+        }
+
+        if !looped || frames.is_empty() {
+            break;
+        }
+    }
+}
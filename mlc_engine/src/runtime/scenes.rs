@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use rocket::tokio::time::{interval, Instant, MissedTickBehavior};
+
+use mlc_common::scene::{self, SceneTile};
+
+use super::RuntimeData;
+
+/// How often the values of a fade are updated
+const FADE_STEP: Duration = Duration::from_millis(25);
+
+/// Fades the scene layer from `from` to `to` over `duration`
+pub async fn fade(
+    runtime: RuntimeData,
+    from: Vec<SceneTile>,
+    to: Vec<SceneTile>,
+    duration: Duration,
+) {
+    let start = Instant::now();
+    let mut ticker = interval(FADE_STEP);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        let t = start.elapsed().as_secs_f64() / duration.as_secs_f64();
+        runtime.show_scene(&scene::fade(&from, &to, t)).await;
+        if t >= 1.0 {
+            break;
+        }
+    }
+}
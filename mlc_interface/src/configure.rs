@@ -130,6 +130,7 @@ fn FaderContainer(universe_id: u16) -> Element {
                 universes,
                 channel_indexes,
                 values,
+                ..
             } => {
                 for (i, u_id) in universes.iter().enumerate() {
                     if u_id.0 == universe_id {
@@ -141,11 +142,13 @@ fn FaderContainer(universe_id: u16) -> Element {
                 universe,
                 channel_index,
                 value,
+                ..
             } => {
                 if universe.0 == universe_id {
                     fader_values.write()[channel_index] = value;
                 }
             }
+            RuntimeUpdate::Connected { .. } => {}
         }),
     );
     let ws = subscribe_ws::<(), FaderUpdateRequest>(
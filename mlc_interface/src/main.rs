@@ -58,7 +58,17 @@ fn App() -> Element {
                 }
                 Info::UniversePatchChanged(_)
                 | Info::EndpointConfigChanged
+                | Info::ArtNetNodesChanged
+                | Info::RdmDevicesChanged
+                | Info::ParkedChanged
+                | Info::MergePoliciesChanged
+                | Info::DefaultsChanged
+                | Info::CurvesChanged
+                | Info::EndpointStatesChanged
                 | Info::EffectListChanged
+                | Info::ScenesChanged
+                | Info::GroupsChanged
+                | Info::HighlightChanged
                 | Info::UniversesUpdated
                 | Info::FixtureTypesUpdated
                 | Info::None => {}